@@ -1,6 +1,6 @@
 use anyhow::Result;
 use super::{
-    config_model::{Config, Application, Server, Database, Jwt, JwtSecret, JwtAdminSecret, Services},
+    config_model::{Config, Application, Server, Database, Jwt, JwtSecret, JwtAdminSecret, JwtSigning, Services, Worker, TokenEncryption, SocialImport, TokenRefresh, OAuth, OAuthProvider, LoginProtection, Mail, PasswordReset, EmailVerification, PasswordPolicy, Privacy, Impersonation},
     stage::Stage,
 };
 
@@ -37,8 +37,27 @@ pub fn load() -> Result<Config> {
             admin_secret: std::env::var("JWT_ADMIN_SECRET").expect("JWT_ADMIN_SECRET not set"),
             admin_refresh_secret: std::env::var("JWT_ADMIN_REFRESH_SECRET").expect("JWT_ADMIN_REFRESH_SECRET not set"),
         },
-        access_token_expiration: std::env::var("JWT_ACCESS_TOKEN_EXPIRATION").expect("JWT_ACCESS_TOKEN_EXPIRATION not set"),
-        refresh_token_expiration: std::env::var("JWT_REFRESH_TOKEN_EXPIRATION").expect("JWT_REFRESH_TOKEN_EXPIRATION not set"),
+        access_token_expiration: humantime::parse_duration(
+            &std::env::var("JWT_ACCESS_TOKEN_EXPIRATION").expect("JWT_ACCESS_TOKEN_EXPIRATION not set"),
+        )?,
+        refresh_token_expiration: humantime::parse_duration(
+            &std::env::var("JWT_REFRESH_TOKEN_EXPIRATION").expect("JWT_REFRESH_TOKEN_EXPIRATION not set"),
+        )?,
+        issuer: std::env::var("JWT_ISSUER").unwrap_or_else(|_| "smartpersona".to_string()),
+        audience: std::env::var("JWT_AUDIENCE").unwrap_or_else(|_| "smartpersona".to_string()),
+        admin_audience: std::env::var("JWT_ADMIN_AUDIENCE").unwrap_or_else(|_| "smartpersona-admin".to_string()),
+        // JWT_PUBLIC_KEYS is a list of "key_id:path_to_public_pem"
+        signing: JwtSigning {
+            algorithm: std::env::var("JWT_ALGORITHM").unwrap_or_else(|_| "HS256".to_string()),
+            active_key_id: std::env::var("JWT_ACTIVE_KEY_ID").ok(),
+            private_key_path: std::env::var("JWT_PRIVATE_KEY_PATH").ok(),
+            public_key_paths: std::env::var("JWT_PUBLIC_KEYS")
+                .unwrap_or_default()
+                .split(',')
+                .filter_map(|entry| entry.trim().split_once(':'))
+                .map(|(key_id, path)| (key_id.to_string(), path.to_string()))
+                .collect(),
+        },
     };
 
     // Load Services config
@@ -46,9 +65,123 @@ pub fn load() -> Result<Config> {
         ai_service_url: std::env::var("AI_SERVICE_URL").expect("AI_SERVICE_URL not set"),
     };
 
-  
+    // Load Worker config
+    let worker = Worker {
+        concurrency: std::env::var("WORKER_CONCURRENCY").unwrap_or_else(|_| "2".to_string()).parse()?,
+        poll_interval_ms: std::env::var("WORKER_POLL_INTERVAL_MS").unwrap_or_else(|_| "1000".to_string()).parse()?,
+        lease_timeout: std::env::var("WORKER_LEASE_TIMEOUT").unwrap_or_else(|_| "300".to_string()).parse()?,
+    };
+
+    // Load Token encryption config, TOKEN_ENCRYPTION_KEYS is a list of "key_id:base64_key"
+    let token_encryption = TokenEncryption {
+        active_key_id: std::env::var("TOKEN_ENCRYPTION_ACTIVE_KEY_ID").expect("TOKEN_ENCRYPTION_ACTIVE_KEY_ID not set"),
+        keys: std::env::var("TOKEN_ENCRYPTION_KEYS")
+            .expect("TOKEN_ENCRYPTION_KEYS not set")
+            .split(',')
+            .filter_map(|entry| entry.trim().split_once(':'))
+            .map(|(key_id, key)| (key_id.to_string(), key.to_string()))
+            .collect(),
+    };
+
+    // Load Social import config
+    let social_import = SocialImport {
+        max_posts: std::env::var("SOCIAL_IMPORT_MAX_POSTS").unwrap_or_else(|_| "50".to_string()).parse()?,
+        local_posts_dir: std::env::var("SOCIAL_IMPORT_LOCAL_DIR").ok(),
+        facebook_api_url: std::env::var("FACEBOOK_API_URL").unwrap_or_else(|_| "https://graph.facebook.com/v19.0".to_string()),
+        x_api_url: std::env::var("X_API_URL").unwrap_or_else(|_| "https://api.twitter.com/2".to_string()),
+    };
+
+    // Load Token refresh config
+    let token_refresh = TokenRefresh {
+        interval: std::env::var("TOKEN_REFRESH_INTERVAL").unwrap_or_else(|_| "300".to_string()).parse()?,
+        window: std::env::var("TOKEN_REFRESH_WINDOW").unwrap_or_else(|_| "3600".to_string()).parse()?,
+        retry_after: std::env::var("TOKEN_REFRESH_RETRY_AFTER").unwrap_or_else(|_| "900".to_string()).parse()?,
+        max_failures: std::env::var("TOKEN_REFRESH_MAX_FAILURES").unwrap_or_else(|_| "5".to_string()).parse()?,
+        batch_size: std::env::var("TOKEN_REFRESH_BATCH_SIZE").unwrap_or_else(|_| "100".to_string()).parse()?,
+    };
+
+    // Load OAuth config, OAUTH_PROVIDERS is a list of platforms e.g. "facebook,x"
+    // and each one needs OAUTH_<PLATFORM>_TOKEN_URL, _CLIENT_ID and _CLIENT_SECRET
+    let oauth = OAuth {
+        providers: std::env::var("OAUTH_PROVIDERS")
+            .unwrap_or_default()
+            .split(',')
+            .map(|platform| platform.trim().to_lowercase())
+            .filter(|platform| !platform.is_empty())
+            .map(|platform| {
+                let prefix = format!("OAUTH_{}", platform.to_uppercase());
+                let var = |suffix: &str| {
+                    let name = format!("{}_{}", prefix, suffix);
+                    std::env::var(&name).map_err(|_| anyhow::anyhow!("{} not set", name))
+                };
+                Ok(OAuthProvider {
+                    token_url: var("TOKEN_URL")?,
+                    client_id: var("CLIENT_ID")?,
+                    client_secret: var("CLIENT_SECRET")?,
+                    platform,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?,
+    };
+
+    // Load login protection config, durations are humantime e.g. "1m", "15m"
+    let login_protection = LoginProtection {
+        rate_limit_window: humantime::parse_duration(&std::env::var("LOGIN_RATE_LIMIT_WINDOW").unwrap_or_else(|_| "1m".to_string()))?,
+        ip_max_requests: std::env::var("LOGIN_RATE_LIMIT_IP_MAX").unwrap_or_else(|_| "30".to_string()).parse()?,
+        username_max_attempts: std::env::var("LOGIN_RATE_LIMIT_USERNAME_MAX").unwrap_or_else(|_| "10".to_string()).parse()?,
+        trust_forwarded_for: std::env::var("LOGIN_TRUST_FORWARDED_FOR").map(|value| value == "true").unwrap_or(false),
+        backoff_after: std::env::var("LOGIN_BACKOFF_AFTER").unwrap_or_else(|_| "3".to_string()).parse()?,
+        backoff_base: humantime::parse_duration(&std::env::var("LOGIN_BACKOFF_BASE").unwrap_or_else(|_| "2s".to_string()))?,
+        lockout_after: std::env::var("LOGIN_LOCKOUT_AFTER").unwrap_or_else(|_| "10".to_string()).parse()?,
+        lockout_duration: humantime::parse_duration(&std::env::var("LOGIN_LOCKOUT_DURATION").unwrap_or_else(|_| "15m".to_string()))?,
+    };
+
+    // Load Mail config, MAIL_TRANSPORT is "smtp" or "file"
+    let mail = Mail {
+        transport: std::env::var("MAIL_TRANSPORT").unwrap_or_else(|_| "file".to_string()),
+        from: std::env::var("MAIL_FROM").unwrap_or_else(|_| "SmartPersona <no-reply@localhost>".to_string()),
+        smtp_host: std::env::var("SMTP_HOST").ok(),
+        smtp_port: std::env::var("SMTP_PORT").ok().map(|port| port.parse()).transpose()?,
+        smtp_username: std::env::var("SMTP_USERNAME").ok(),
+        smtp_password: std::env::var("SMTP_PASSWORD").ok(),
+        file_dir: std::env::var("MAIL_FILE_DIR").ok(),
+    };
+
+    // Load Password reset config
+    let password_reset = PasswordReset {
+        token_lifetime: humantime::parse_duration(&std::env::var("PASSWORD_RESET_TOKEN_LIFETIME").unwrap_or_else(|_| "30m".to_string()))?,
+    };
+
+    // Load Email verification config
+    let email_verification = EmailVerification {
+        token_lifetime: humantime::parse_duration(&std::env::var("EMAIL_VERIFICATION_TOKEN_LIFETIME").unwrap_or_else(|_| "24h".to_string()))?,
+    };
+
+    // Load Password policy config
+    let password_policy = PasswordPolicy {
+        min_length: std::env::var("PASSWORD_MIN_LENGTH").unwrap_or_else(|_| "10".to_string()).parse()?,
+        max_length: std::env::var("PASSWORD_MAX_LENGTH").unwrap_or_else(|_| "128".to_string()).parse()?,
+        require_uppercase: std::env::var("PASSWORD_REQUIRE_UPPERCASE").map(|value| value == "true").unwrap_or(false),
+        require_lowercase: std::env::var("PASSWORD_REQUIRE_LOWERCASE").map(|value| value == "true").unwrap_or(false),
+        require_digit: std::env::var("PASSWORD_REQUIRE_DIGIT").map(|value| value == "true").unwrap_or(true),
+        require_symbol: std::env::var("PASSWORD_REQUIRE_SYMBOL").map(|value| value == "true").unwrap_or(false),
+        breached_list_path: std::env::var("PASSWORD_BREACHED_LIST_PATH").ok(),
+    };
+
+    // Load Privacy config
+    let privacy = Privacy {
+        poll_interval: humantime::parse_duration(&std::env::var("PRIVACY_POLL_INTERVAL").unwrap_or_else(|_| "30s".to_string()))?,
+        export_retention: humantime::parse_duration(&std::env::var("DATA_EXPORT_RETENTION").unwrap_or_else(|_| "7days".to_string()))?,
+        export_lease: humantime::parse_duration(&std::env::var("DATA_EXPORT_LEASE").unwrap_or_else(|_| "10m".to_string()))?,
+        erasure_grace_period: humantime::parse_duration(&std::env::var("ERASURE_GRACE_PERIOD").unwrap_or_else(|_| "30days".to_string()))?,
+    };
+
+    // Load Impersonation config
+    let impersonation = Impersonation {
+        token_lifetime: humantime::parse_duration(&std::env::var("IMPERSONATION_TOKEN_LIFETIME").unwrap_or_else(|_| "15m".to_string()))?,
+    };
 
-    Ok(Config { app, server, database, jwt, services })
+    Ok(Config { app, server, database, jwt, services, worker, token_encryption, social_import, token_refresh, oauth, login_protection, mail, password_reset, email_verification, password_policy, privacy, impersonation })
 }
 
 pub fn get_stage() -> Stage{
@@ -56,20 +189,3 @@ pub fn get_stage() -> Stage{
     let stage_str = std::env::var("STAGE").unwrap_or("".to_string());
     Stage::try_from(&stage_str).unwrap_or_default()
 }
-pub fn get_user_secret() -> Result<JwtSecret> {
-    dotenvy::dotenv().ok();
-    Ok(JwtSecret {
-        user_secret: std::env::var("JWT_USER_SECRET").expect("JWT_USER_SECRET not set"),
-        user_refresh_secret: std::env::var("JWT_USER_REFRESH_SECRET")
-            .expect("JWT_USER_REFRESH_SECRET not set"),
-    })
-}
-pub fn get_admin_secret() -> Result<JwtAdminSecret> {
-    dotenvy::dotenv().ok();
-    Ok(JwtAdminSecret {
-        admin_secret: std::env::var("JWT_ADMIN_SECRET")
-            .expect("JWT_ADMIN_SECRET not set"),
-        admin_refresh_secret: std::env::var("JWT_ADMIN_REFRESH_SECRET")
-            .expect("JWT_ADMIN_REFRESH_SECRET not set"),
-    })
-}
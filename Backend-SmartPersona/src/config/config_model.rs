@@ -1,3 +1,5 @@
+use std::{collections::HashMap, time::Duration};
+
 use serde::Deserialize;
 
 // Struct หลักที่รวบรวม Config ทั้งหมด
@@ -8,6 +10,18 @@ pub struct Config {
     pub database: Database,
     pub jwt: Jwt,
     pub services: Services,
+    pub worker: Worker,
+    pub token_encryption: TokenEncryption,
+    pub social_import: SocialImport,
+    pub token_refresh: TokenRefresh,
+    pub oauth: OAuth,
+    pub login_protection: LoginProtection,
+    pub mail: Mail,
+    pub password_reset: PasswordReset,
+    pub email_verification: EmailVerification,
+    pub password_policy: PasswordPolicy,
+    pub privacy: Privacy,
+    pub impersonation: Impersonation,
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -38,8 +52,16 @@ pub struct Jwt {
     #[serde(flatten)]
     pub admin: JwtAdminSecret, // แยก struct admin เพื่อความชัดเจน
 
-    pub access_token_expiration: String,
-    pub refresh_token_expiration: String,
+    // อ่านจาก env แบบ humantime เช่น "15m", "7days"
+    pub access_token_expiration: Duration,
+    pub refresh_token_expiration: Duration,
+
+    // iss / aud ที่ใส่ใน token และตรวจตอน verify, admin ใช้ audience แยกจาก user
+    pub issuer: String,
+    pub audience: String,
+    pub admin_audience: String,
+
+    pub signing: JwtSigning,
 }
 
 // ใช้ชื่อกลางๆ ว่า JwtSecret สำหรับ User ทั่วไป
@@ -56,11 +78,141 @@ pub struct JwtAdminSecret {
     pub admin_refresh_secret: String,
 }
 
+// การเซ็น token แบบ asymmetric (RS256 / EdDSA) ด้วย key จากไฟล์ PEM
+// algorithm เป็น HS256 จะใช้ secret ด้านบนเหมือนเดิม
+// public_key_paths คือ key id -> path ของ public key (SPKI PEM) ที่ยังใช้ตรวจ token ได้ ใส่ key เก่าไว้ตอน rotate
+#[derive(Debug, Clone, Deserialize)]
+pub struct JwtSigning {
+    pub algorithm: String,
+    pub active_key_id: Option<String>,
+    pub private_key_path: Option<String>,
+    pub public_key_paths: HashMap<String, String>,
+}
 
 #[derive(Debug, Clone, Deserialize)]
 pub struct Services {
     pub ai_service_url: String,
 }
 
+// Struct สำหรับตั้งค่า background worker ของ generation_jobs
+#[derive(Debug, Clone, Deserialize)]
+pub struct Worker {
+    pub concurrency: usize,
+    pub poll_interval_ms: u64,
+    pub lease_timeout: u64,
+}
+
+// Master key สำหรับเข้ารหัส token ของ social_connections (key id -> key แบบ base64 ขนาด 32 bytes)
+#[derive(Debug, Clone, Deserialize)]
+pub struct TokenEncryption {
+    pub active_key_id: String,
+    pub keys: HashMap<String, String>,
+}
+
+// Struct สำหรับตั้งค่าการดึงโพสต์จาก social_connections มาวิเคราะห์
+// ถ้ากำหนด local_posts_dir จะอ่านโพสต์จากไฟล์แทนการเรียก API จริง
+#[derive(Debug, Clone, Deserialize)]
+pub struct SocialImport {
+    pub max_posts: usize,
+    pub local_posts_dir: Option<String>,
+    pub facebook_api_url: String,
+    pub x_api_url: String,
+}
+
+// Struct สำหรับตั้งค่างาน refresh token ของ social_connections ที่ใกล้หมดอายุ (หน่วยวินาที)
+#[derive(Debug, Clone, Deserialize)]
+pub struct TokenRefresh {
+    pub interval: u64,
+    pub window: u64,
+    pub retry_after: u64,
+    pub max_failures: i32,
+    // จำนวน connection สูงสุดที่ refresh ต่อรอบ ที่เหลือจะถูกหยิบในรอบถัดไป
+    pub batch_size: i64,
+}
+
 // Struct สำหรับรวมการตั้งค่า OAuth
+#[derive(Debug, Clone, Deserialize)]
+pub struct OAuth {
+    pub providers: Vec<OAuthProvider>,
+}
+
+// token endpoint ของแต่ละ platform (platform ต้องตรงกับ social_connections.platform)
+#[derive(Debug, Clone, Deserialize)]
+pub struct OAuthProvider {
+    pub platform: String,
+    pub token_url: String,
+    pub client_id: String,
+    pub client_secret: String,
+}
+
+// ป้องกันการเดารหัสผ่าน: จำกัดจำนวน request ต่อ IP / ต่อ username ในแต่ละช่วงเวลา
+// และ back-off แบบ exponential หลัง login ผิดครบ backoff_after ครั้ง ล็อกบัญชีเมื่อครบ lockout_after ครั้ง
+// trust_forwarded_for เปิดเมื่ออยู่หลัง reverse proxy หนึ่งชั้น เพื่ออ่าน IP ตัวขวาสุดที่ proxy เติมใน X-Forwarded-For
+#[derive(Debug, Clone, Deserialize)]
+pub struct LoginProtection {
+    pub rate_limit_window: Duration,
+    pub ip_max_requests: u32,
+    pub username_max_attempts: u32,
+    pub trust_forwarded_for: bool,
+    pub backoff_after: i32,
+    pub backoff_base: Duration,
+    pub lockout_after: i32,
+    pub lockout_duration: Duration,
+}
 
+// การส่งอีเมล: transport เป็น "smtp" หรือ "file"
+// "file" ใช้ตอน local development / test จะเขียนอีเมลลง file_dir (ถ้ากำหนด) และ log ออกมาแทนการส่งจริง
+#[derive(Debug, Clone, Deserialize)]
+pub struct Mail {
+    pub transport: String,
+    pub from: String,
+    pub smtp_host: Option<String>,
+    pub smtp_port: Option<u16>,
+    pub smtp_username: Option<String>,
+    pub smtp_password: Option<String>,
+    pub file_dir: Option<String>,
+}
+
+// อายุของลิงก์ตั้งรหัสผ่านใหม่ อ่านแบบ humantime เช่น "30m"
+#[derive(Debug, Clone, Deserialize)]
+pub struct PasswordReset {
+    pub token_lifetime: Duration,
+}
+
+// อายุของลิงก์ยืนยันอีเมลที่ส่งตอนสมัคร อ่านแบบ humantime เช่น "24h"
+#[derive(Debug, Clone, Deserialize)]
+pub struct EmailVerification {
+    pub token_lifetime: Duration,
+}
+
+// กฎของรหัสผ่านใหม่ (ตอนสมัครและตอนตั้งรหัสผ่านใหม่)
+// breached_list_path เป็นไฟล์รายการรหัสผ่านที่เคยรั่ว บรรทัดละหนึ่งรหัส เพิ่มจากรายการที่มีมาในโค้ด
+#[derive(Debug, Clone, Deserialize)]
+pub struct PasswordPolicy {
+    pub min_length: usize,
+    pub max_length: usize,
+    pub require_uppercase: bool,
+    pub require_lowercase: bool,
+    pub require_digit: bool,
+    pub require_symbol: bool,
+    pub breached_list_path: Option<String>,
+}
+
+// งาน export ข้อมูลส่วนตัวและการลบบัญชีตามคำขอ (อ่านแบบ humantime)
+// export_retention คือระยะเวลาที่เก็บไฟล์ export ไว้ให้ดาวน์โหลด
+// export_lease คือระยะเวลาที่ export ทำงานค้างได้ก่อนถือว่า worker ล่มแล้วให้ worker อื่นรับไปทำใหม่
+// erasure_grace_period คือระยะเวลาก่อนลบบัญชีจริง ผู้ใช้ยกเลิกได้ภายในช่วงนี้
+#[derive(Debug, Clone, Deserialize)]
+pub struct Privacy {
+    pub poll_interval: Duration,
+    pub export_retention: Duration,
+    pub export_lease: Duration,
+    pub erasure_grace_period: Duration,
+}
+
+// อายุของ access token ที่แอดมินใช้สวมบทเป็นผู้ใช้ (impersonate) อ่านแบบ humantime เช่น "15m"
+// ไม่มี refresh token หมดอายุแล้วต้องขอใหม่
+#[derive(Debug, Clone, Deserialize)]
+pub struct Impersonation {
+    pub token_lifetime: Duration,
+}
@@ -1,12 +1,13 @@
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AIAnalysisRequest {
     pub user_id: String,
     pub posts: Vec<String>,
+    pub prompt: String,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct AIAnalysisResponse {
     pub personality_tags: Vec<String>,
     pub suggested_theme: String,
@@ -0,0 +1,48 @@
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+use uuid::Uuid;
+
+use crate::infrastructure::postgres::schema::audit_events;
+
+// `actor_id` is who did it and `target_id` what it was done to; either may be missing,
+// e.g. a failed login for an unknown username has neither.
+#[derive(Debug, Clone, Queryable, Selectable)]
+#[diesel(table_name = audit_events)]
+pub struct AuditEventEntity {
+    pub id: Uuid,
+    pub action: String,
+    pub actor_id: Option<Uuid>,
+    pub target_type: Option<String>,
+    pub target_id: Option<Uuid>,
+    pub ip_address: Option<String>,
+    pub user_agent: Option<String>,
+    pub request_id: Option<String>,
+    pub changes: serde_json::Value,
+    pub created_at: NaiveDateTime,
+}
+
+#[derive(Debug, Clone, Insertable)]
+#[diesel(table_name = audit_events)]
+pub struct InsertAuditEventEntity {
+    pub action: String,
+    pub actor_id: Option<Uuid>,
+    pub target_type: Option<String>,
+    pub target_id: Option<Uuid>,
+    pub ip_address: Option<String>,
+    pub user_agent: Option<String>,
+    pub request_id: Option<String>,
+    pub changes: serde_json::Value,
+    pub created_at: NaiveDateTime,
+}
+
+// Filters for the admin audit query; every field is optional and they combine with AND.
+#[derive(Debug, Clone, Default)]
+pub struct AuditEventFilter {
+    pub actor_id: Option<Uuid>,
+    pub target_id: Option<Uuid>,
+    pub action: Option<String>,
+    pub from: Option<NaiveDateTime>,
+    pub to: Option<NaiveDateTime>,
+    pub limit: i64,
+    pub offset: i64,
+}
@@ -0,0 +1,28 @@
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+use uuid::Uuid;
+
+use crate::{domain::entities::generation_job::JobStatus, infrastructure::postgres::schema::data_exports};
+
+// `data` holds the finished export until `expires_at`, when it is purged.
+#[derive(Debug, Clone, Queryable, Selectable)]
+#[diesel(table_name = data_exports)]
+pub struct DataExportEntity {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub status: JobStatus,
+    pub data: Option<serde_json::Value>,
+    pub error: Option<String>,
+    pub created_at: NaiveDateTime,
+    pub started_at: Option<NaiveDateTime>,
+    pub completed_at: Option<NaiveDateTime>,
+    pub expires_at: Option<NaiveDateTime>,
+}
+
+#[derive(Debug, Clone, Insertable)]
+#[diesel(table_name = data_exports)]
+pub struct InsertDataExportEntity {
+    pub user_id: Uuid,
+    pub status: JobStatus,
+    pub created_at: NaiveDateTime,
+}
@@ -0,0 +1,78 @@
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+use diesel_derive_enum::DbEnum;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{
+    domain::entities::ai_analysis::AIAnalysisRequest,
+    infrastructure::postgres::schema::{generation_jobs, sql_types::JobStatus as JobStatusType},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, DbEnum, Serialize, Deserialize)]
+#[ExistingTypePath = "JobStatusType"]
+#[serde(rename_all = "snake_case")]
+pub enum JobStatus {
+    Pending,
+    Running,
+    Completed,
+    Failed,
+}
+
+impl JobStatus {
+    pub fn is_finished(&self) -> bool {
+        matches!(self, JobStatus::Completed | JobStatus::Failed)
+    }
+}
+
+#[derive(Debug, Clone, Queryable, Selectable)]
+#[diesel(table_name = generation_jobs)]
+pub struct GenerationJobEntity {
+    pub id: Uuid,
+    pub requester_id: Uuid,
+    pub status: JobStatus,
+    pub prompt: Option<String>,
+    pub prompt_template_version_id: Option<Uuid>,
+    pub result: Option<serde_json::Value>,
+    pub payload: serde_json::Value,
+    pub error: Option<String>,
+    pub attempts: i32,
+    pub created_at: NaiveDateTime,
+    pub started_at: Option<NaiveDateTime>,
+    pub completed_at: Option<NaiveDateTime>,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Debug, Clone, Insertable)]
+#[diesel(table_name = generation_jobs)]
+pub struct InsertGenerationJobEntity {
+    pub requester_id: Uuid,
+    pub status: JobStatus,
+    pub prompt: Option<String>,
+    pub prompt_template_version_id: Option<Uuid>,
+    pub payload: serde_json::Value,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+// Stored in `generation_jobs.payload`. A job submitted without posts imports them on the
+// worker, which then replaces the payload with the rendered request so retries reuse it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum PersonalityAnalysisPayload {
+    Rendered(AIAnalysisRequest),
+    ImportPosts {
+        requester_id: Uuid,
+        language: Option<String>,
+        tag_count: Option<u32>,
+    },
+}
+
+// Payload of the `generation_job_events` NOTIFY channel, see the notify_generation_job_changes migration.
+#[derive(Debug, Clone, Deserialize)]
+pub struct GenerationJobEvent {
+    #[serde(rename = "id")]
+    pub job_id: Uuid,
+    pub requester_id: Uuid,
+    pub status: JobStatus,
+}
@@ -0,0 +1,41 @@
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+use uuid::Uuid;
+
+use crate::infrastructure::postgres::schema::mfa_recovery_codes;
+
+// The TOTP secret is base32 plaintext here; `MfaPostgres` encrypts it at rest.
+#[derive(Debug, Clone)]
+pub struct UserMfaEntity {
+    pub user_id: Uuid,
+    pub secret: String,
+    // Unset while enrolment waits for the first code.
+    pub confirmed_at: Option<NaiveDateTime>,
+    pub last_used_step: Option<i64>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+impl UserMfaEntity {
+    pub fn is_confirmed(&self) -> bool {
+        self.confirmed_at.is_some()
+    }
+}
+
+#[derive(Debug, Clone, Queryable, Selectable)]
+#[diesel(table_name = mfa_recovery_codes)]
+pub struct RecoveryCodeEntity {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub code_hash: String,
+    pub used_at: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+}
+
+#[derive(Debug, Clone, Insertable)]
+#[diesel(table_name = mfa_recovery_codes)]
+pub struct InsertRecoveryCodeEntity {
+    pub user_id: Uuid,
+    pub code_hash: String,
+    pub created_at: NaiveDateTime,
+}
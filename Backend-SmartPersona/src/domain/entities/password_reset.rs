@@ -0,0 +1,26 @@
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+use uuid::Uuid;
+
+use crate::infrastructure::postgres::schema::password_reset_tokens;
+
+// Only the SHA-256 of the token is stored; the token itself is only ever in the email.
+#[derive(Debug, Clone, Queryable, Selectable)]
+#[diesel(table_name = password_reset_tokens)]
+pub struct PasswordResetTokenEntity {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub token_hash: String,
+    pub expires_at: NaiveDateTime,
+    pub used_at: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+}
+
+#[derive(Debug, Clone, Insertable)]
+#[diesel(table_name = password_reset_tokens)]
+pub struct InsertPasswordResetTokenEntity {
+    pub user_id: Uuid,
+    pub token_hash: String,
+    pub expires_at: NaiveDateTime,
+    pub created_at: NaiveDateTime,
+}
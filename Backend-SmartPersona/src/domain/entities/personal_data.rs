@@ -0,0 +1,18 @@
+use chrono::NaiveDateTime;
+
+use crate::domain::entities::{
+    generation_job::GenerationJobEntity, profile::ProfileEntity, session::SessionEntity,
+    social_connection::SocialConnectionEntity, user::UserEntity,
+};
+
+// Everything stored about one user, gathered for a data export. Social connection tokens
+// are never read for this, so their token fields are always empty.
+#[derive(Debug, Clone)]
+pub struct PersonalDataEntity {
+    pub user: UserEntity,
+    pub profiles: Vec<ProfileEntity>,
+    pub social_connections: Vec<SocialConnectionEntity>,
+    pub generation_jobs: Vec<GenerationJobEntity>,
+    pub sessions: Vec<SessionEntity>,
+    pub mfa_confirmed_at: Option<NaiveDateTime>,
+}
@@ -0,0 +1,48 @@
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+use diesel_derive_enum::DbEnum;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::infrastructure::postgres::schema::{profiles, sql_types::ProfileStatus as ProfileStatusType};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, DbEnum, Serialize, Deserialize)]
+#[ExistingTypePath = "ProfileStatusType"]
+#[serde(rename_all = "snake_case")]
+pub enum ProfileStatus {
+    Public,
+    Private,
+}
+
+#[derive(Debug, Clone, Queryable, Selectable)]
+#[diesel(table_name = profiles)]
+pub struct ProfileEntity {
+    pub id: Uuid,
+    pub owner_id: Uuid,
+    pub status: ProfileStatus,
+    pub content: Option<serde_json::Value>,
+    pub layout_config: Option<serde_json::Value>,
+    pub shareable_link_slug: Option<String>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Debug, Clone, Insertable)]
+#[diesel(table_name = profiles)]
+pub struct InsertProfileEntity {
+    pub owner_id: Uuid,
+    pub status: ProfileStatus,
+    pub content: Option<serde_json::Value>,
+    pub layout_config: Option<serde_json::Value>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Debug, Clone, AsChangeset)]
+#[diesel(table_name = profiles)]
+pub struct EditProfileEntity {
+    pub status: Option<ProfileStatus>,
+    pub content: Option<serde_json::Value>,
+    pub layout_config: Option<serde_json::Value>,
+    pub updated_at: NaiveDateTime,
+}
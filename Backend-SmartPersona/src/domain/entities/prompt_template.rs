@@ -0,0 +1,50 @@
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+use uuid::Uuid;
+
+use crate::infrastructure::postgres::schema::{prompt_template_versions, prompt_templates};
+
+#[derive(Debug, Clone, Queryable, Selectable)]
+#[diesel(table_name = prompt_templates)]
+pub struct PromptTemplateEntity {
+    pub id: Uuid,
+    pub name: String,
+    pub active_version_id: Option<Uuid>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Debug, Clone, Insertable)]
+#[diesel(table_name = prompt_templates)]
+pub struct InsertPromptTemplateEntity {
+    pub name: String,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Debug, Clone, AsChangeset)]
+#[diesel(table_name = prompt_templates)]
+pub struct EditPromptTemplateEntity {
+    pub name: Option<String>,
+    pub updated_at: NaiveDateTime,
+}
+
+// Rows are never updated, a database trigger rejects it. Edits add a new version instead.
+#[derive(Debug, Clone, Queryable, Selectable)]
+#[diesel(table_name = prompt_template_versions)]
+pub struct PromptTemplateVersionEntity {
+    pub id: Uuid,
+    pub template_id: Uuid,
+    pub version: i32,
+    pub template_text: String,
+    pub created_at: NaiveDateTime,
+}
+
+#[derive(Debug, Clone, Insertable)]
+#[diesel(table_name = prompt_template_versions)]
+pub struct InsertPromptTemplateVersionEntity {
+    pub template_id: Uuid,
+    pub version: i32,
+    pub template_text: String,
+    pub created_at: NaiveDateTime,
+}
@@ -0,0 +1,38 @@
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+use uuid::Uuid;
+
+use crate::infrastructure::postgres::schema::sessions;
+
+// A session is one refresh token family: every refresh replaces `current_refresh_token_id`
+// and tokens carry the session id in their `sid` claim.
+#[derive(Debug, Clone, Queryable, Selectable)]
+#[diesel(table_name = sessions)]
+pub struct SessionEntity {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub device: Option<String>,
+    pub created_at: NaiveDateTime,
+    pub last_used_at: NaiveDateTime,
+    pub expires_at: NaiveDateTime,
+    pub revoked_at: Option<NaiveDateTime>,
+    pub current_refresh_token_id: Uuid,
+}
+
+impl SessionEntity {
+    pub fn is_active(&self, now: NaiveDateTime) -> bool {
+        self.revoked_at.is_none() && self.expires_at > now
+    }
+}
+
+#[derive(Debug, Clone, Insertable)]
+#[diesel(table_name = sessions)]
+pub struct InsertSessionEntity {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub device: Option<String>,
+    pub created_at: NaiveDateTime,
+    pub last_used_at: NaiveDateTime,
+    pub expires_at: NaiveDateTime,
+    pub current_refresh_token_id: Uuid,
+}
@@ -0,0 +1,38 @@
+use chrono::NaiveDateTime;
+use uuid::Uuid;
+
+// Tokens here are plaintext; `SocialConnectionPostgres` encrypts them at rest.
+#[derive(Debug, Clone)]
+pub struct SocialConnectionEntity {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub platform: String,
+    pub platform_user_id: String,
+    pub access_token: String,
+    pub refresh_token: Option<String>,
+    pub expires_at: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+    pub refresh_failures: i32,
+    // Set once refreshing keeps failing; the user has to link the account again.
+    pub broken_at: Option<NaiveDateTime>,
+}
+
+#[derive(Debug, Clone)]
+pub struct InsertSocialConnectionEntity {
+    pub user_id: Uuid,
+    pub platform: String,
+    pub platform_user_id: String,
+    pub access_token: String,
+    pub refresh_token: Option<String>,
+    pub expires_at: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+}
+
+// Result of exchanging a refresh token. Providers that don't rotate refresh tokens
+// leave `refresh_token` empty and the current one is kept.
+#[derive(Debug, Clone)]
+pub struct RefreshedTokenEntity {
+    pub access_token: String,
+    pub refresh_token: Option<String>,
+    pub expires_in: Option<i64>,
+}
@@ -1,13 +1,11 @@
-use std::io::Write;
-
 use chrono::NaiveDateTime;
-use diesel::{prelude::*, deserialize::FromSqlRow, expression::AsExpression};
-use diesel::pg::Pg;
+use diesel::prelude::*;
 use uuid::Uuid;
 use crate::infrastructure::postgres::schema::{users, sql_types::{UserRole, UserStatus as UserStatusType}};
 
 use diesel_derive_enum::DbEnum;
-#[derive(Debug, Clone, Copy, PartialEq, Eq, DbEnum)]
+use serde::{Deserialize, Serialize};
+#[derive(Debug, Clone, Copy, PartialEq, Eq, DbEnum, Serialize, Deserialize)]
 #[ExistingTypePath = "UserRole"] 
 pub enum Role {
     PersonaUser,
@@ -15,6 +13,18 @@ pub enum Role {
     Admin,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, DbEnum, Serialize, Deserialize)]
+#[ExistingTypePath = "UserStatusType"]
+pub enum UserStatus {
+    Pending,
+    Active,
+    Suspended,
+    // No longer set by the failed-login lockout, which only uses `locked_until`. Kept for
+    // accounts locked before that; an admin reactivates them.
+    Locked,
+    Banned,
+}
+
 #[derive(Debug, Clone, Queryable, Selectable)]
 #[diesel(table_name = users)]
 pub struct UserEntity {
@@ -23,11 +33,39 @@ pub struct UserEntity {
     pub password_hash : String,
     pub display_name : Option<String>,
     pub role : Role,    
-    // pub status : UserStatus,
+    // Pending until the email is verified.
+    pub status : UserStatus,
     pub created_at : NaiveDateTime,
     pub updated_at : NaiveDateTime,
     pub first_name : String,
     pub last_name : String,
+    pub failed_login_attempts : i32,
+    pub locked_until : Option<NaiveDateTime>,
+    pub email : Option<String>,
+    // Set while a requested account erasure is in its grace period.
+    pub erasure_scheduled_at : Option<NaiveDateTime>,
+}
+
+// `None` leaves a column unchanged; `display_name: Some(None)` clears it.
+#[derive(Debug, Clone, Default, AsChangeset)]
+#[diesel(table_name = users)]
+pub struct UpdateUserEntity {
+    pub display_name : Option<Option<String>>,
+    pub first_name : Option<String>,
+    pub last_name : Option<String>,
+}
+
+// Filters for the admin user search; every field is optional and they combine with AND.
+#[derive(Debug, Clone, Default)]
+pub struct UserSearchFilter {
+    // Matched case-insensitively against username, email and names.
+    pub query : Option<String>,
+    pub role : Option<Role>,
+    pub status : Option<UserStatus>,
+    pub created_from : Option<NaiveDateTime>,
+    pub created_to : Option<NaiveDateTime>,
+    pub limit : i64,
+    pub offset : i64,
 }
 
 #[derive(Debug, Clone,Insertable,Queryable)]
@@ -35,6 +73,7 @@ pub struct UserEntity {
 pub struct RegisterUserEntity {
     pub username : String,
     pub password_hash : String,
+    pub email : String,
     pub first_name : String,
     pub last_name : String,
     pub created_at : NaiveDateTime,
@@ -0,0 +1,114 @@
+use std::fmt;
+
+use serde_json::Value;
+
+use crate::domain::entities::user::UserStatus;
+
+pub type AppResult<T> = Result<T, AppError>;
+
+#[derive(Debug)]
+pub enum AppError {
+    NotFound(String),
+    Conflict(String),
+    // `details` carries field level errors, e.g. `{"username": ["is already taken"]}`.
+    Validation { message: String, details: Option<Value> },
+    Unauthorized(String),
+    Forbidden(String),
+    // The account may not sign in because it is unverified, suspended or banned.
+    AccountInactive(UserStatus),
+    // Rate limited or locked out; `retry_after` is in seconds.
+    TooManyRequests { message: String, retry_after: Option<u64> },
+    // A third party (AI service, social platform, OAuth provider) failed.
+    Upstream(String),
+    // Never shown to clients; logged with the request id instead.
+    Internal(anyhow::Error),
+}
+
+impl AppError {
+    pub fn not_found(message: impl Into<String>) -> Self {
+        Self::NotFound(message.into())
+    }
+
+    pub fn conflict(message: impl Into<String>) -> Self {
+        Self::Conflict(message.into())
+    }
+
+    pub fn validation(message: impl Into<String>) -> Self {
+        Self::Validation { message: message.into(), details: None }
+    }
+
+    pub fn invalid_fields(message: impl Into<String>, details: Value) -> Self {
+        Self::Validation { message: message.into(), details: Some(details) }
+    }
+
+    pub fn unauthorized(message: impl Into<String>) -> Self {
+        Self::Unauthorized(message.into())
+    }
+
+    pub fn forbidden(message: impl Into<String>) -> Self {
+        Self::Forbidden(message.into())
+    }
+
+    pub fn too_many_requests(message: impl Into<String>, retry_after: u64) -> Self {
+        Self::TooManyRequests { message: message.into(), retry_after: Some(retry_after) }
+    }
+
+    pub fn upstream(message: impl Into<String>) -> Self {
+        Self::Upstream(message.into())
+    }
+
+    // Stable identifier clients can branch on.
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::NotFound(_) => "not_found",
+            Self::Conflict(_) => "conflict",
+            Self::Validation { .. } => "validation_error",
+            Self::Unauthorized(_) => "unauthorized",
+            Self::Forbidden(_) => "forbidden",
+            Self::AccountInactive(UserStatus::Pending) => "account_unverified",
+            Self::AccountInactive(UserStatus::Suspended) => "account_suspended",
+            Self::AccountInactive(UserStatus::Banned) => "account_banned",
+            Self::AccountInactive(_) => "account_inactive",
+            Self::TooManyRequests { .. } => "too_many_requests",
+            Self::Upstream(_) => "upstream_error",
+            Self::Internal(_) => "internal_error",
+        }
+    }
+}
+
+impl fmt::Display for AppError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotFound(message)
+            | Self::Conflict(message)
+            | Self::Validation { message, .. }
+            | Self::Unauthorized(message)
+            | Self::Forbidden(message)
+            | Self::TooManyRequests { message, .. }
+            | Self::Upstream(message) => write!(f, "{}", message),
+            Self::AccountInactive(UserStatus::Pending) => write!(f, "Email address is not verified"),
+            Self::AccountInactive(UserStatus::Suspended) => write!(f, "Account is suspended"),
+            Self::AccountInactive(UserStatus::Banned) => write!(f, "Account is banned"),
+            Self::AccountInactive(_) => write!(f, "Account is not active"),
+            Self::Internal(e) => write!(f, "{:#}", e),
+        }
+    }
+}
+
+impl std::error::Error for AppError {}
+
+impl From<anyhow::Error> for AppError {
+    fn from(e: anyhow::Error) -> Self {
+        // Keep the original variant when an AppError was wrapped in anyhow on the way up.
+        match e.downcast::<AppError>() {
+            Ok(app_error) => app_error,
+            Err(e) => Self::Internal(e),
+        }
+    }
+}
+
+impl From<serde_json::Error> for AppError {
+    fn from(e: serde_json::Error) -> Self {
+        Self::Internal(e.into())
+    }
+}
@@ -1,4 +1,6 @@
 pub mod usecase;
 pub mod entities;
+pub mod error;
 pub mod repo;
-pub mod value_object;
\ No newline at end of file
+pub mod value_object;
+pub mod service;
\ No newline at end of file
@@ -0,0 +1,14 @@
+use axum::async_trait;
+
+use crate::domain::{
+    entities::audit_event::{AuditEventEntity, AuditEventFilter, InsertAuditEventEntity},
+    error::AppResult,
+};
+
+#[cfg_attr(test, mockall::automock)]
+#[async_trait]
+pub trait AuditEventRepository {
+    async fn create(&self, insert_audit_event_entity: InsertAuditEventEntity) -> AppResult<AuditEventEntity>;
+    // Returns one page of matching events, newest first, and the total number of matches.
+    async fn search(&self, filter: AuditEventFilter) -> AppResult<(Vec<AuditEventEntity>, i64)>;
+}
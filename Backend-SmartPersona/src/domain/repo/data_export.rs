@@ -0,0 +1,23 @@
+use axum::async_trait;
+use chrono::NaiveDateTime;
+use uuid::Uuid;
+
+use crate::domain::{
+    entities::data_export::{DataExportEntity, InsertDataExportEntity},
+    error::AppResult,
+};
+
+#[cfg_attr(test, mockall::automock)]
+#[async_trait]
+pub trait DataExportRepository {
+    // Conflict when the user already has a pending or running export.
+    async fn create(&self, insert_data_export_entity: InsertDataExportEntity) -> AppResult<DataExportEntity>;
+    async fn find_by_id(&self, export_id: Uuid) -> AppResult<DataExportEntity>;
+    // Locks the oldest pending export with SKIP LOCKED and moves it to running. Exports left
+    // running since before `stale_before` by a crashed worker are claimed again.
+    async fn claim_next(&self, stale_before: NaiveDateTime) -> AppResult<Option<DataExportEntity>>;
+    async fn mark_completed(&self, export_id: Uuid, data: serde_json::Value, expires_at: NaiveDateTime) -> AppResult<()>;
+    async fn mark_failed(&self, export_id: Uuid, error: String) -> AppResult<()>;
+    // Deletes finished exports whose download window closed before `now`.
+    async fn delete_expired(&self, now: NaiveDateTime) -> AppResult<usize>;
+}
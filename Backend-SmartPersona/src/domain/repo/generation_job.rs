@@ -0,0 +1,37 @@
+use crate::domain::error::AppResult;
+use axum::async_trait;
+use chrono::NaiveDateTime;
+use uuid::Uuid;
+
+use crate::domain::entities::generation_job::{GenerationJobEntity, InsertGenerationJobEntity};
+
+#[cfg_attr(test, mockall::automock)]
+#[async_trait]
+pub trait GenerationJobRepository {
+    async fn create(&self, insert_generation_job_entity: InsertGenerationJobEntity) -> AppResult<GenerationJobEntity>;
+    async fn find_by_id(&self, job_id: Uuid) -> AppResult<GenerationJobEntity>;
+    // Newest first.
+    async fn find_by_requester(&self, requester_id: Uuid, limit: i64, offset: i64) -> AppResult<Vec<GenerationJobEntity>>;
+    // Locks the oldest pending job with SKIP LOCKED and moves it to running.
+    async fn claim_next(&self) -> AppResult<Option<GenerationJobEntity>>;
+    // The methods below act on a job the caller claimed. `attempt` is the `attempts` value the
+    // claim returned, so a worker whose job was released and claimed again cannot overwrite the
+    // new run. They return `false` when the job is no longer held under that claim.
+
+    // Stores the prompt rendered on the worker together with the request it belongs to.
+    async fn record_prompt(
+        &self,
+        job_id: Uuid,
+        attempt: i32,
+        prompt: String,
+        prompt_template_version_id: Uuid,
+        payload: serde_json::Value,
+    ) -> AppResult<bool>;
+    // Pushes the heartbeat of the running job forward.
+    async fn extend_lease(&self, job_id: Uuid, attempt: i32) -> AppResult<bool>;
+    async fn mark_completed(&self, job_id: Uuid, attempt: i32, result: serde_json::Value) -> AppResult<bool>;
+    async fn mark_failed(&self, job_id: Uuid, attempt: i32, error: String) -> AppResult<bool>;
+    async fn requeue(&self, job_id: Uuid, attempt: i32, error: String) -> AppResult<bool>;
+    // Returns running jobs whose worker has not sent a heartbeat since `heartbeat_before` to pending.
+    async fn release_stale(&self, heartbeat_before: NaiveDateTime) -> AppResult<usize>;
+}
@@ -0,0 +1,19 @@
+use std::sync::Arc;
+
+use axum::async_trait;
+
+use crate::domain::error::AppResult;
+
+#[derive(Debug, Clone)]
+pub struct EmailMessage {
+    pub to: String,
+    pub subject: String,
+    pub body: String,
+}
+
+#[async_trait]
+pub trait Mailer {
+    async fn send(&self, message: EmailMessage) -> AppResult<()>;
+}
+
+pub type SharedMailer = Arc<dyn Mailer + Send + Sync>;
@@ -0,0 +1,28 @@
+use axum::async_trait;
+use chrono::NaiveDateTime;
+use uuid::Uuid;
+
+use crate::domain::{
+    entities::mfa::{InsertRecoveryCodeEntity, RecoveryCodeEntity, UserMfaEntity},
+    error::AppResult,
+};
+
+#[cfg_attr(test, mockall::automock)]
+#[async_trait]
+pub trait MfaRepository {
+    async fn find_by_user_id(&self, user_id: Uuid) -> AppResult<UserMfaEntity>;
+    // Starts (or restarts) an unconfirmed enrolment with a new secret.
+    async fn start_enrolment(&self, user_id: Uuid, secret: String) -> AppResult<UserMfaEntity>;
+    // Confirms the enrolment and replaces any recovery codes in one transaction.
+    async fn confirm(&self, user_id: Uuid, recovery_codes: Vec<InsertRecoveryCodeEntity>) -> AppResult<()>;
+    // Records `step` as used only if it is newer than the last one. Returns false for a replayed code.
+    async fn use_step(&self, user_id: Uuid, step: i64) -> AppResult<bool>;
+    async fn unused_recovery_codes(&self, user_id: Uuid) -> AppResult<Vec<RecoveryCodeEntity>>;
+    // Returns false when the code was used concurrently.
+    async fn use_recovery_code(&self, recovery_code_id: Uuid) -> AppResult<bool>;
+    async fn replace_recovery_codes(&self, user_id: Uuid, recovery_codes: Vec<InsertRecoveryCodeEntity>) -> AppResult<()>;
+    async fn delete(&self, user_id: Uuid) -> AppResult<()>;
+    // Records the jti of an MFA-pending token that completed a login, clearing expired
+    // ones on the way. Returns false when the token was already used.
+    async fn use_mfa_token(&self, jti: Uuid, user_id: Uuid, expires_at: NaiveDateTime) -> AppResult<bool>;
+}
@@ -1,2 +1,15 @@
 pub mod user;
-pub mod ai_service;
\ No newline at end of file
+pub mod ai_service;
+pub mod profile;
+pub mod generation_job;
+pub mod prompt_template;
+pub mod social_connection;
+pub mod social_post_source;
+pub mod token_refresher;
+pub mod session;
+pub mod mfa;
+pub mod password_reset;
+pub mod mailer;
+pub mod data_export;
+pub mod personal_data;
+pub mod audit_event;
\ No newline at end of file
@@ -0,0 +1,17 @@
+use axum::async_trait;
+use uuid::Uuid;
+
+use crate::domain::{
+    entities::password_reset::{InsertPasswordResetTokenEntity, PasswordResetTokenEntity},
+    error::AppResult,
+};
+
+#[cfg_attr(test, mockall::automock)]
+#[async_trait]
+pub trait PasswordResetRepository {
+    async fn create(&self, insert_password_reset_token_entity: InsertPasswordResetTokenEntity) -> AppResult<PasswordResetTokenEntity>;
+    // In one transaction: uses up the token, sets the new password, voids the user's other
+    // tokens, revokes every session and clears failed logins. Returns the user id, or NotFound
+    // when the token is unknown, used or expired.
+    async fn reset_password(&self, token_hash: String, password_hash: String) -> AppResult<Uuid>;
+}
@@ -0,0 +1,15 @@
+use axum::async_trait;
+use chrono::NaiveDateTime;
+use uuid::Uuid;
+
+use crate::domain::{entities::personal_data::PersonalDataEntity, error::AppResult};
+
+#[cfg_attr(test, mockall::automock)]
+#[async_trait]
+pub trait PersonalDataRepository {
+    async fn collect(&self, user_id: Uuid) -> AppResult<PersonalDataEntity>;
+    // Deletes the user and every row that belongs to them, and strips their personal data
+    // from the audit events that mention them, in one transaction, but only if their
+    // erasure is still scheduled at or before `due_before`. Returns false otherwise.
+    async fn erase(&self, user_id: Uuid, due_before: NaiveDateTime) -> AppResult<bool>;
+}
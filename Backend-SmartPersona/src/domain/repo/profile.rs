@@ -0,0 +1,17 @@
+use crate::domain::error::AppResult;
+use axum::async_trait;
+use uuid::Uuid;
+
+use crate::domain::entities::profile::{EditProfileEntity, InsertProfileEntity, ProfileEntity};
+
+#[async_trait]
+pub trait ProfileRepository {
+    async fn create(&self, insert_profile_entity: InsertProfileEntity) -> AppResult<ProfileEntity>;
+    async fn find_by_id(&self, profile_id: Uuid) -> AppResult<ProfileEntity>;
+    async fn find_by_owner(&self, owner_id: Uuid) -> AppResult<Vec<ProfileEntity>>;
+    async fn update(&self, profile_id: Uuid, edit_profile_entity: EditProfileEntity) -> AppResult<ProfileEntity>;
+    async fn delete(&self, profile_id: Uuid) -> AppResult<()>;
+    async fn find_by_slug(&self, slug: String) -> AppResult<ProfileEntity>;
+    async fn slug_exists(&self, slug: String) -> AppResult<bool>;
+    async fn set_slug(&self, profile_id: Uuid, slug: Option<String>) -> AppResult<ProfileEntity>;
+}
@@ -0,0 +1,37 @@
+use crate::domain::error::AppResult;
+use axum::async_trait;
+use uuid::Uuid;
+
+use crate::domain::entities::prompt_template::{
+    EditPromptTemplateEntity, InsertPromptTemplateEntity, PromptTemplateEntity, PromptTemplateVersionEntity,
+};
+
+#[cfg_attr(test, mockall::automock)]
+#[async_trait]
+pub trait PromptTemplateRepository {
+    // Creates the template together with its first version and marks that version active.
+    async fn create(
+        &self,
+        insert_prompt_template_entity: InsertPromptTemplateEntity,
+        template_text: String,
+    ) -> AppResult<PromptTemplateEntity>;
+    // Archived templates are left out of every lookup except `find_version_by_id`, which
+    // jobs use to show the prompt they were generated from.
+    async fn find_all(&self) -> AppResult<Vec<PromptTemplateEntity>>;
+    async fn find_by_id(&self, template_id: Uuid) -> AppResult<PromptTemplateEntity>;
+    async fn find_by_name(&self, name: String) -> AppResult<PromptTemplateEntity>;
+    // Renames the template and, when `template_text` is given, adds it as the new active
+    // version in the same transaction.
+    async fn update(
+        &self,
+        template_id: Uuid,
+        edit_prompt_template_entity: EditPromptTemplateEntity,
+        template_text: Option<String>,
+    ) -> AppResult<PromptTemplateEntity>;
+    // Soft-deletes the template. Its versions are kept for the jobs that reference them.
+    async fn archive(&self, template_id: Uuid) -> AppResult<()>;
+    async fn find_versions(&self, template_id: Uuid) -> AppResult<Vec<PromptTemplateVersionEntity>>;
+    async fn find_version(&self, template_id: Uuid, version: i32) -> AppResult<PromptTemplateVersionEntity>;
+    async fn find_version_by_id(&self, version_id: Uuid) -> AppResult<PromptTemplateVersionEntity>;
+    async fn set_active_version(&self, template_id: Uuid, version_id: Uuid) -> AppResult<PromptTemplateEntity>;
+}
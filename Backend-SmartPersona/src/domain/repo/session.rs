@@ -0,0 +1,22 @@
+use axum::async_trait;
+use uuid::Uuid;
+
+use crate::domain::{
+    entities::session::{InsertSessionEntity, SessionEntity},
+    error::AppResult,
+};
+
+#[cfg_attr(test, mockall::automock)]
+#[async_trait]
+pub trait SessionRepository {
+    async fn create(&self, insert_session_entity: InsertSessionEntity) -> AppResult<SessionEntity>;
+    async fn find_by_id(&self, session_id: Uuid) -> AppResult<SessionEntity>;
+    // Swaps the current refresh token only if it is still `presented_token_id`. Returns
+    // false when the presented token was already rotated away.
+    async fn rotate_refresh_token(&self, session_id: Uuid, presented_token_id: Uuid, new_token_id: Uuid) -> AppResult<bool>;
+    async fn revoke(&self, session_id: Uuid) -> AppResult<()>;
+    // Returns the number of sessions that were still active.
+    async fn revoke_all_for_user(&self, user_id: Uuid) -> AppResult<usize>;
+    // Same as `revoke_all_for_user` but keeps `keep_session_id`, e.g. the one changing the password.
+    async fn revoke_others_for_user(&self, user_id: Uuid, keep_session_id: Uuid) -> AppResult<usize>;
+}
@@ -0,0 +1,25 @@
+use crate::domain::error::AppResult;
+use axum::async_trait;
+use chrono::NaiveDateTime;
+use uuid::Uuid;
+
+use crate::domain::entities::social_connection::{InsertSocialConnectionEntity, SocialConnectionEntity};
+
+#[cfg_attr(test, mockall::automock)]
+#[async_trait]
+pub trait SocialConnectionRepository {
+    // Replaces the existing connection when the user already linked this platform.
+    async fn upsert(&self, insert_social_connection_entity: InsertSocialConnectionEntity) -> AppResult<SocialConnectionEntity>;
+    async fn find_by_user(&self, user_id: Uuid) -> AppResult<Vec<SocialConnectionEntity>>;
+    async fn find_by_user_and_platform(&self, user_id: Uuid, platform: String) -> AppResult<SocialConnectionEntity>;
+    async fn delete_by_user_and_platform(&self, user_id: Uuid, platform: String) -> AppResult<usize>;
+    // Locks and returns healthy connections expiring before `expires_before` that were not
+    // attempted since `retry_before`, at most `limit` soonest-expiring first, stamping the
+    // attempt so other instances skip them.
+    async fn claim_due_for_refresh(&self, expires_before: NaiveDateTime, retry_before: NaiveDateTime, limit: i64) -> AppResult<Vec<SocialConnectionEntity>>;
+    // Stores refreshed tokens and clears the failure count.
+    async fn update_tokens(&self, social_connection_entity: SocialConnectionEntity) -> AppResult<SocialConnectionEntity>;
+    // Returns the number of consecutive failures including this one.
+    async fn record_refresh_failure(&self, connection_id: Uuid) -> AppResult<i32>;
+    async fn mark_broken(&self, connection_id: Uuid) -> AppResult<()>;
+}
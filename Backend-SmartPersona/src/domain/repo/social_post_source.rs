@@ -0,0 +1,11 @@
+use crate::domain::error::AppResult;
+use axum::async_trait;
+
+use crate::domain::entities::social_connection::SocialConnectionEntity;
+
+#[async_trait]
+pub trait SocialPostSource {
+    // Matches `social_connections.platform`.
+    fn platform(&self) -> &str;
+    async fn fetch_recent_posts(&self, connection: &SocialConnectionEntity, limit: usize) -> AppResult<Vec<String>>;
+}
@@ -0,0 +1,11 @@
+use crate::domain::error::AppResult;
+use axum::async_trait;
+
+use crate::domain::entities::social_connection::RefreshedTokenEntity;
+
+#[async_trait]
+pub trait TokenRefresher {
+    // Matches `social_connections.platform`.
+    fn platform(&self) -> &str;
+    async fn refresh(&self, refresh_token: &str) -> AppResult<RefreshedTokenEntity>;
+}
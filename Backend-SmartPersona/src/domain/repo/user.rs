@@ -1,9 +1,34 @@
-use crate::domain::{entities::user::{UserEntity,RegisterUserEntity}};
-use anyhow::Result;
+use crate::domain::{entities::user::{UserEntity,RegisterUserEntity,UpdateUserEntity,UserSearchFilter,Role,UserStatus}};
+use crate::domain::error::AppResult;
 use axum::async_trait;
+use chrono::NaiveDateTime;
 use uuid::Uuid;
+#[cfg_attr(test, mockall::automock)]
 #[async_trait]
 pub trait UserRepository {
-    async fn register(&self,register_user_entity:RegisterUserEntity) -> Result<Uuid>;
-    async fn find_by_username(&self,username:String) -> Result<UserEntity>;
+    async fn register(&self,register_user_entity:RegisterUserEntity) -> AppResult<Uuid>;
+    // Case-insensitive, matching the unique index on `lower(username)`.
+    async fn find_by_username(&self,username:String) -> AppResult<UserEntity>;
+    async fn find_by_id(&self,user_id:Uuid) -> AppResult<UserEntity>;
+    // Emails are stored lower-cased, callers pass them lower-cased too.
+    async fn find_by_email(&self,email:String) -> AppResult<UserEntity>;
+    // Returns the number of consecutive failed logins including this one.
+    async fn record_failed_login(&self,user_id:Uuid) -> AppResult<i32>;
+    // Refuses logins until `until`. Only `locked_until` is written, never the status, so a
+    // lockout cannot hide or replace a suspension or ban.
+    async fn delay_login(&self,user_id:Uuid,until:NaiveDateTime) -> AppResult<()>;
+    // Clears the counter and any lockout, e.g. after a successful login or an admin unlock.
+    async fn clear_failed_logins(&self,user_id:Uuid) -> AppResult<()>;
+    // Moves a pending account to active; false when it was not pending.
+    async fn verify_email(&self,user_id:Uuid) -> AppResult<bool>;
+    // Bumps `updated_at` along with the changed columns.
+    async fn update(&self,user_id:Uuid,update_user_entity:UpdateUserEntity) -> AppResult<UserEntity>;
+    // `Some` starts the grace period before erasure, `None` cancels it.
+    async fn schedule_erasure(&self,user_id:Uuid,erasure_scheduled_at:Option<NaiveDateTime>) -> AppResult<()>;
+    async fn find_due_for_erasure(&self,now:NaiveDateTime) -> AppResult<Vec<Uuid>>;
+    // Returns one page of matching users, newest first, and the total number of matches.
+    async fn search(&self,filter:UserSearchFilter) -> AppResult<(Vec<UserEntity>,i64)>;
+    async fn update_role(&self,user_id:Uuid,role:Role) -> AppResult<UserEntity>;
+    async fn update_status(&self,user_id:Uuid,status:UserStatus) -> AppResult<UserEntity>;
+    async fn update_password(&self,user_id:Uuid,password_hash:String) -> AppResult<()>;
 }
\ No newline at end of file
@@ -0,0 +1,248 @@
+use std::sync::Arc;
+
+use chrono::Utc;
+use serde::Serialize;
+use serde_json::{json, Map, Value};
+use sha2::{Digest, Sha256};
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::domain::{
+    entities::audit_event::{AuditEventEntity, AuditEventFilter, InsertAuditEventEntity},
+    error::AppResult,
+    repo::audit_event::AuditEventRepository,
+};
+
+pub type SharedAuditEventRepository = Arc<dyn AuditEventRepository + Send + Sync>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuditAction {
+    LoginSucceeded,
+    LoginFailed,
+    TokenRefreshed,
+    UserRegistered,
+    EmailVerified,
+    AccountUpdated,
+    PasswordChanged,
+    AccountDeleted,
+    RoleChanged,
+    UserSuspended,
+    UserBanned,
+    UserReactivated,
+    UserLoggedOut,
+    UserUnlocked,
+    ProfilePublished,
+    ProfileUnpublished,
+    ShareLinkCreated,
+    ShareLinkRevoked,
+    ImpersonationStarted,
+}
+
+impl AuditAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::LoginSucceeded => "auth.login",
+            Self::LoginFailed => "auth.login_failed",
+            Self::TokenRefreshed => "auth.refresh",
+            Self::UserRegistered => "user.register",
+            Self::EmailVerified => "user.verify_email",
+            Self::AccountUpdated => "user.update",
+            Self::PasswordChanged => "user.change_password",
+            Self::AccountDeleted => "user.delete",
+            Self::RoleChanged => "admin.user.change_role",
+            Self::UserSuspended => "admin.user.suspend",
+            Self::UserBanned => "admin.user.ban",
+            Self::UserReactivated => "admin.user.reactivate",
+            Self::UserLoggedOut => "admin.user.logout",
+            Self::UserUnlocked => "admin.user.unlock",
+            Self::ProfilePublished => "profile.publish",
+            Self::ProfileUnpublished => "profile.unpublish",
+            Self::ShareLinkCreated => "profile.share_link",
+            Self::ShareLinkRevoked => "profile.revoke_share_link",
+            Self::ImpersonationStarted => "admin.user.impersonate",
+        }
+    }
+}
+
+/// Who made the request and from where, taken from the HTTP request by its extractor.
+#[derive(Debug, Clone, Default)]
+pub struct AuditContext {
+    pub actor_id: Option<Uuid>,
+    pub ip_address: Option<String>,
+    pub user_agent: Option<String>,
+    pub request_id: Option<String>,
+}
+
+/// One thing that happened. The actor defaults to the one in the `AuditContext`; set it
+/// explicitly where the caller is not signed in yet, e.g. on login.
+#[derive(Debug, Clone)]
+pub struct AuditEvent {
+    action: AuditAction,
+    actor_id: Option<Uuid>,
+    target_type: Option<&'static str>,
+    target_id: Option<Uuid>,
+    changes: Value,
+}
+
+impl AuditEvent {
+    pub fn new(action: AuditAction) -> Self {
+        Self {
+            action,
+            actor_id: None,
+            target_type: None,
+            target_id: None,
+            changes: json!({}),
+        }
+    }
+
+    pub fn actor(mut self, actor_id: Uuid) -> Self {
+        self.actor_id = Some(actor_id);
+        self
+    }
+
+    pub fn target_user(self, user_id: Uuid) -> Self {
+        self.target("user", user_id)
+    }
+
+    pub fn target_profile(self, profile_id: Uuid) -> Self {
+        self.target("profile", profile_id)
+    }
+
+    pub fn changes(mut self, changes: Value) -> Self {
+        self.changes = changes;
+        self
+    }
+
+    fn target(mut self, target_type: &'static str, target_id: Uuid) -> Self {
+        self.target_type = Some(target_type);
+        self.target_id = Some(target_id);
+        self
+    }
+}
+
+pub struct AuditLogger {
+    audit_event_repository: SharedAuditEventRepository,
+}
+
+impl AuditLogger {
+    pub fn new(audit_event_repository: SharedAuditEventRepository) -> Self {
+        Self { audit_event_repository }
+    }
+
+    // Never fails the action being audited; a lost event is logged instead.
+    pub async fn record(&self, context: &AuditContext, event: AuditEvent) {
+        let action = event.action.as_str();
+        let actor_id = event.actor_id.or(context.actor_id);
+        info!(
+            action,
+            actor_id = ?actor_id,
+            target_id = ?event.target_id,
+            "audit event"
+        );
+
+        let insert_audit_event_entity = InsertAuditEventEntity {
+            action: action.to_string(),
+            actor_id,
+            target_type: event.target_type.map(str::to_string),
+            target_id: event.target_id,
+            ip_address: context.ip_address.clone(),
+            user_agent: context.user_agent.clone(),
+            request_id: context.request_id.clone(),
+            changes: event.changes,
+            created_at: Utc::now().naive_utc(),
+        };
+        if let Err(e) = self.audit_event_repository.create(insert_audit_event_entity).await {
+            warn!("failed to record audit event {}: {}", action, e);
+        }
+    }
+
+    pub async fn search(&self, filter: AuditEventFilter) -> AppResult<(Vec<AuditEventEntity>, i64)> {
+        self.audit_event_repository.search(filter).await
+    }
+}
+
+/// `{ field: { from, to } }` for every top-level field that differs between the two
+/// serialized values. `updated_at` is left out since it changes on every write.
+pub fn diff<T: Serialize>(before: &T, after: &T) -> Value {
+    let (Ok(Value::Object(before)), Ok(Value::Object(after))) = (serde_json::to_value(before), serde_json::to_value(after))
+    else {
+        return json!({});
+    };
+
+    let mut changes = Map::new();
+    for (field, to) in &after {
+        if field == "updated_at" {
+            continue;
+        }
+        let from = before.get(field).cloned().unwrap_or(Value::Null);
+        if &from != to {
+            changes.insert(field.clone(), json!({ "from": from, "to": to }));
+        }
+    }
+    Value::Object(changes)
+}
+
+/// Stands in for free text that may identify someone but belongs to no account, such as
+/// the name typed into a failed login, so erasure never has to find it. The same input
+/// always gives the same value, so repeated attempts can still be told apart.
+pub fn pseudonymise(value: &str) -> String {
+    format!("{:x}", Sha256::digest(value.trim().to_lowercase().as_bytes()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Serialize)]
+    struct Account {
+        username: &'static str,
+        status: &'static str,
+        display_name: Option<&'static str>,
+        updated_at: i64,
+    }
+
+    fn account() -> Account {
+        Account {
+            username: "alice",
+            status: "active",
+            display_name: None,
+            updated_at: 1,
+        }
+    }
+
+    #[test]
+    fn lists_only_the_changed_fields_as_from_and_to() {
+        let after = Account {
+            status: "suspended",
+            display_name: Some("Alice"),
+            ..account()
+        };
+        assert_eq!(
+            diff(&account(), &after),
+            json!({
+                "status": { "from": "active", "to": "suspended" },
+                "display_name": { "from": null, "to": "Alice" },
+            })
+        );
+    }
+
+    #[test]
+    fn skips_updated_at() {
+        let after = Account { updated_at: 2, ..account() };
+        assert_eq!(diff(&account(), &after), json!({}));
+    }
+
+    #[test]
+    fn non_objects_have_no_diff() {
+        assert_eq!(diff(&1, &2), json!({}));
+    }
+
+    #[test]
+    fn pseudonymises_consistently_without_keeping_the_value() {
+        let pseudonym = pseudonymise("Alice@Example.com");
+        assert_eq!(pseudonym, pseudonymise(" alice@example.com "));
+        assert_ne!(pseudonym, pseudonymise("bob@example.com"));
+        assert_eq!(pseudonym.len(), 64);
+        assert!(!pseudonym.contains("alice"));
+    }
+}
@@ -0,0 +1 @@
+pub mod audit_logger;
\ No newline at end of file
@@ -0,0 +1,263 @@
+use std::{sync::Arc, time::Duration};
+
+use chrono::{TimeDelta, Utc};
+use serde_json::json;
+use uuid::Uuid;
+
+use crate::{
+    domain::{
+        entities::user::{Role, UserStatus},
+        error::{AppError, AppResult},
+        repo::{
+            generation_job::GenerationJobRepository, profile::ProfileRepository, session::SessionRepository,
+            user::UserRepository,
+        },
+        service::audit_logger::{self, AuditAction, AuditContext, AuditEvent, AuditLogger},
+        value_object::{
+            admin_user::{ForceLogoutModel, ImpersonationModel, PageModel, PageQuery, UserListQuery},
+            generation_job::GenerationJobModel,
+            profile::ProfileModel,
+            user::UserModel,
+        },
+    },
+    infrastructure::jwt_authentication::{
+        self,
+        jwt_keys::{JwtKeys, TokenKind},
+        jwt_model::{ActorClaim, Claims},
+    },
+};
+
+pub struct AdminUserUseCase<T1, T2, T3, T4>
+where
+    T1: UserRepository + Send + Sync,
+    T2: SessionRepository + Send + Sync,
+    T3: ProfileRepository + Send + Sync,
+    T4: GenerationJobRepository + Send + Sync,
+{
+    user_repository: Arc<T1>,
+    session_repository: Arc<T2>,
+    profile_repository: Arc<T3>,
+    generation_job_repository: Arc<T4>,
+    audit_logger: Arc<AuditLogger>,
+    jwt_keys: Arc<JwtKeys>,
+    impersonation_token_lifetime: Duration,
+}
+
+impl<T1, T2, T3, T4> AdminUserUseCase<T1, T2, T3, T4>
+where
+    T1: UserRepository + Send + Sync,
+    T2: SessionRepository + Send + Sync,
+    T3: ProfileRepository + Send + Sync,
+    T4: GenerationJobRepository + Send + Sync,
+{
+    pub fn new(
+        user_repository: Arc<T1>,
+        session_repository: Arc<T2>,
+        profile_repository: Arc<T3>,
+        generation_job_repository: Arc<T4>,
+        audit_logger: Arc<AuditLogger>,
+        jwt_keys: Arc<JwtKeys>,
+        impersonation_token_lifetime: Duration,
+    ) -> Self {
+        Self {
+            user_repository,
+            session_repository,
+            profile_repository,
+            generation_job_repository,
+            audit_logger,
+            jwt_keys,
+            impersonation_token_lifetime,
+        }
+    }
+
+    pub async fn list_users(&self, query: UserListQuery) -> AppResult<PageModel<UserModel>> {
+        let filter = query.to_filter()?;
+        let page_query = query.page_query();
+        let (users, total) = self.user_repository.search(filter).await?;
+        Ok(PageModel {
+            items: users.into_iter().map(UserModel::from).collect(),
+            page: page_query.page(),
+            per_page: page_query.per_page(),
+            total,
+        })
+    }
+
+    pub async fn get_user(&self, user_id: Uuid) -> AppResult<UserModel> {
+        let user = self.user_repository.find_by_id(user_id).await?;
+        Ok(UserModel::from(user))
+    }
+
+    pub async fn user_profiles(&self, user_id: Uuid) -> AppResult<Vec<ProfileModel>> {
+        let user = self.user_repository.find_by_id(user_id).await?;
+        let profiles = self.profile_repository.find_by_owner(user.id).await?;
+        Ok(profiles.into_iter().map(ProfileModel::from).collect())
+    }
+
+    pub async fn user_jobs(&self, user_id: Uuid, page_query: PageQuery) -> AppResult<Vec<GenerationJobModel>> {
+        let (limit, offset) = page_query.limit_offset()?;
+        let user = self.user_repository.find_by_id(user_id).await?;
+        let jobs = self.generation_job_repository.find_by_requester(user.id, limit, offset).await?;
+        Ok(jobs.into_iter().map(GenerationJobModel::from).collect())
+    }
+
+    pub async fn change_role(&self, admin_id: Uuid, user_id: Uuid, role: Role, context: &AuditContext) -> AppResult<UserModel> {
+        // Stops an admin from demoting themselves by accident.
+        ensure_not_self(admin_id, user_id)?;
+        let before = UserModel::from(self.user_repository.find_by_id(user_id).await?);
+        let after = UserModel::from(self.user_repository.update_role(user_id, role).await?);
+        // Roles are baked into access tokens, so make the user sign in again to pick up the new one.
+        self.session_repository.revoke_all_for_user(user_id).await?;
+
+        self.audit(context, AuditAction::RoleChanged, user_id, audit_logger::diff(&before, &after)).await;
+        Ok(after)
+    }
+
+    pub async fn suspend(&self, admin_id: Uuid, user_id: Uuid, context: &AuditContext) -> AppResult<UserModel> {
+        self.restrict(admin_id, user_id, UserStatus::Suspended, AuditAction::UserSuspended, context).await
+    }
+
+    pub async fn ban(&self, admin_id: Uuid, user_id: Uuid, context: &AuditContext) -> AppResult<UserModel> {
+        self.restrict(admin_id, user_id, UserStatus::Banned, AuditAction::UserBanned, context).await
+    }
+
+    // Lifts a suspension, ban or lock, and clears any login lockout so the user can sign in
+    // straight away. Pending accounts still have to verify their email.
+    pub async fn reactivate(&self, admin_id: Uuid, user_id: Uuid, context: &AuditContext) -> AppResult<UserModel> {
+        ensure_not_self(admin_id, user_id)?;
+        let before = UserModel::from(self.user_repository.find_by_id(user_id).await?);
+        ensure_reactivatable(before.status)?;
+        let after = UserModel::from(self.user_repository.update_status(user_id, UserStatus::Active).await?);
+        self.user_repository.clear_failed_logins(user_id).await?;
+
+        self.audit(context, AuditAction::UserReactivated, user_id, audit_logger::diff(&before, &after)).await;
+        Ok(after)
+    }
+
+    // Revokes every session; access tokens are checked against their session, so they stop working at once.
+    pub async fn force_logout(&self, user_id: Uuid, context: &AuditContext) -> AppResult<ForceLogoutModel> {
+        let user = self.user_repository.find_by_id(user_id).await?;
+        let revoked_sessions = self.session_repository.revoke_all_for_user(user.id).await?;
+
+        self.audit(context, AuditAction::UserLoggedOut, user.id, json!({ "revoked_sessions": revoked_sessions })).await;
+        Ok(ForceLogoutModel { revoked_sessions })
+    }
+
+    // Lifts a lockout or back-off from failed logins straight away.
+    pub async fn unlock(&self, user_id: Uuid, context: &AuditContext) -> AppResult<()> {
+        let user = self.user_repository.find_by_id(user_id).await?;
+        self.user_repository.clear_failed_logins(user.id).await?;
+
+        let changes = json!({ "failed_login_attempts": { "from": user.failed_login_attempts, "to": 0 } });
+        self.audit(context, AuditAction::UserUnlocked, user.id, changes).await;
+        Ok(())
+    }
+
+    // Issues a short-lived user access token for `user_id` with the admin in the `act` claim.
+    // It is bound to the admin's session and never comes with a refresh token.
+    pub async fn impersonate(
+        &self,
+        admin_id: Uuid,
+        admin_session_id: Uuid,
+        user_id: Uuid,
+        context: &AuditContext,
+    ) -> AppResult<(String, ImpersonationModel)> {
+        ensure_not_self(admin_id, user_id)?;
+        let user = self.user_repository.find_by_id(user_id).await?;
+        if user.role == Role::Admin {
+            return Err(AppError::forbidden("Administrators cannot be impersonated"));
+        }
+
+        let now = Utc::now().naive_utc();
+        let expires_at = TimeDelta::from_std(self.impersonation_token_lifetime)
+            .ok()
+            .and_then(|lifetime| now.checked_add_signed(lifetime))
+            .ok_or_else(|| anyhow::anyhow!("Token lifetime {:?} is out of range", self.impersonation_token_lifetime))?;
+        let claims = Claims {
+            sub: user.id.to_string(),
+            role: user.role,
+            iss: self.jwt_keys.issuer.clone(),
+            aud: self.jwt_keys.audience(TokenKind::UserAccess).to_string(),
+            exp: expires_at.and_utc().timestamp() as usize,
+            iat: now.and_utc().timestamp() as usize,
+            jti: Uuid::new_v4().to_string(),
+            sid: admin_session_id.to_string(),
+            act: Some(ActorClaim { sub: admin_id.to_string() }),
+        };
+        let access_token = jwt_authentication::generate_token(&self.jwt_keys, TokenKind::UserAccess, &claims)?;
+
+        let changes = json!({ "session_id": admin_session_id, "expires_at": expires_at });
+        self.audit(context, AuditAction::ImpersonationStarted, user.id, changes).await;
+        Ok((
+            access_token,
+            ImpersonationModel {
+                user: UserModel::from(user),
+                impersonator_id: admin_id,
+                expires_at,
+                expires_in: (expires_at - now).num_seconds(),
+            },
+        ))
+    }
+
+    async fn restrict(
+        &self,
+        admin_id: Uuid,
+        user_id: Uuid,
+        status: UserStatus,
+        action: AuditAction,
+        context: &AuditContext,
+    ) -> AppResult<UserModel> {
+        ensure_not_self(admin_id, user_id)?;
+        let before = UserModel::from(self.user_repository.find_by_id(user_id).await?);
+        let after = UserModel::from(self.user_repository.update_status(user_id, status).await?);
+        self.session_repository.revoke_all_for_user(user_id).await?;
+
+        self.audit(context, action, user_id, audit_logger::diff(&before, &after)).await;
+        Ok(after)
+    }
+
+    async fn audit(&self, context: &AuditContext, action: AuditAction, user_id: Uuid, changes: serde_json::Value) {
+        let event = AuditEvent::new(action).target_user(user_id).changes(changes);
+        self.audit_logger.record(context, event).await;
+    }
+}
+
+fn ensure_not_self(admin_id: Uuid, user_id: Uuid) -> AppResult<()> {
+    if admin_id == user_id {
+        return Err(AppError::forbidden("Administrators cannot change their own account here"));
+    }
+    Ok(())
+}
+
+fn ensure_reactivatable(status: UserStatus) -> AppResult<()> {
+    match status {
+        UserStatus::Suspended | UserStatus::Banned | UserStatus::Locked => Ok(()),
+        UserStatus::Active => Err(AppError::conflict("User is already active")),
+        UserStatus::Pending => Err(AppError::conflict("User has not verified their email yet")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn restricted_accounts_can_be_reactivated() {
+        for status in [UserStatus::Suspended, UserStatus::Banned, UserStatus::Locked] {
+            assert!(ensure_reactivatable(status).is_ok());
+        }
+    }
+
+    #[test]
+    fn active_and_pending_accounts_cannot_be_reactivated() {
+        for status in [UserStatus::Active, UserStatus::Pending] {
+            assert!(matches!(ensure_reactivatable(status), Err(AppError::Conflict(_))));
+        }
+    }
+
+    #[test]
+    fn admins_cannot_change_their_own_account() {
+        let admin_id = Uuid::new_v4();
+        assert!(matches!(ensure_not_self(admin_id, admin_id), Err(AppError::Forbidden(_))));
+        assert!(ensure_not_self(admin_id, Uuid::new_v4()).is_ok());
+    }
+}
@@ -1,37 +1,148 @@
 use std::sync::Arc;
-use anyhow::Result;
+use chrono::Utc;
+use tracing::warn;
+use uuid::Uuid;
 
 use crate::domain::{
+    error::{AppError, AppResult},
     entities::ai_analysis::{AIAnalysisRequest, AIAnalysisResponse, ChatRequest, ChatResponse},
-    repo::ai_service::AIServiceRepository,
+    repo::{
+        ai_service::AIServiceRepository, prompt_template::PromptTemplateRepository,
+        social_connection::SocialConnectionRepository, social_post_source::SocialPostSource,
+    },
+    value_object::prompt_template::{PromptVariables, RenderedPrompt, PERSONALITY_ANALYSIS_TEMPLATE},
 };
 
-pub struct AIAnalysisUseCase<T>
+const DEFAULT_LANGUAGE: &str = "English";
+const DEFAULT_TAG_COUNT: u32 = 5;
+
+pub type SharedSocialPostSource = Arc<dyn SocialPostSource + Send + Sync>;
+
+pub struct AIAnalysisUseCase<T1, T2, T3>
 where
-    T: AIServiceRepository + Send + Sync,
+    T1: AIServiceRepository + Send + Sync,
+    T2: PromptTemplateRepository + Send + Sync,
+    T3: SocialConnectionRepository + Send + Sync,
 {
-    ai_service_repository: Arc<T>,
+    ai_service_repository: Arc<T1>,
+    prompt_template_repository: Arc<T2>,
+    social_connection_repository: Arc<T3>,
+    social_post_sources: Vec<SharedSocialPostSource>,
+    max_imported_posts: usize,
 }
 
-impl<T> AIAnalysisUseCase<T>
+impl<T1, T2, T3> AIAnalysisUseCase<T1, T2, T3>
 where
-    T: AIServiceRepository + Send + Sync,
+    T1: AIServiceRepository + Send + Sync,
+    T2: PromptTemplateRepository + Send + Sync,
+    T3: SocialConnectionRepository + Send + Sync,
 {
-    pub fn new(ai_service_repository: Arc<T>) -> Self {
-        Self { ai_service_repository }
+    pub fn new(
+        ai_service_repository: Arc<T1>,
+        prompt_template_repository: Arc<T2>,
+        social_connection_repository: Arc<T3>,
+        social_post_sources: Vec<SharedSocialPostSource>,
+        max_imported_posts: usize,
+    ) -> Self {
+        Self {
+            ai_service_repository,
+            prompt_template_repository,
+            social_connection_repository,
+            social_post_sources,
+            max_imported_posts,
+        }
     }
 
-    pub async fn analyze_user_personality(&self, user_id: String, posts: Vec<String>) -> Result<AIAnalysisResponse> {
-        let request = AIAnalysisRequest {
-            user_id,
-            posts,
+    // Collects recent posts from every linked platform that has a source. A platform that
+    // fails or whose token has expired is skipped so one broken link doesn't block the rest.
+    pub async fn import_user_posts(&self, user_id: Uuid) -> AppResult<Vec<String>> {
+        let connections = self.social_connection_repository.find_by_user(user_id).await?;
+        let now = Utc::now().naive_utc();
+        let mut posts = Vec::new();
+
+        for connection in connections {
+            if posts.len() >= self.max_imported_posts {
+                break;
+            }
+            let Some(source) = self
+                .social_post_sources
+                .iter()
+                .find(|source| source.platform() == connection.platform)
+            else {
+                continue;
+            };
+            if connection.broken_at.is_some() || connection.expires_at.is_some_and(|expires_at| expires_at <= now) {
+                warn!("skipping expired {} connection {}", connection.platform, connection.id);
+                continue;
+            }
+
+            let remaining = self.max_imported_posts - posts.len();
+            match source.fetch_recent_posts(&connection, remaining).await {
+                Ok(fetched) => posts.extend(
+                    fetched
+                        .into_iter()
+                        .filter(|post| !post.trim().is_empty())
+                        .take(remaining),
+                ),
+                Err(e) => warn!("failed to import {} posts for connection {}: {}", connection.platform, connection.id, e),
+            }
+        }
+
+        if posts.is_empty() {
+            return Err(AppError::validation("No posts could be imported from linked social accounts"));
+        }
+        Ok(posts)
+    }
+
+    // Renders the active version of the `personality_analysis` template so the AI service
+    // receives the final prompt, and reports which version produced it.
+    pub async fn render_personality_prompt(
+        &self,
+        posts: Vec<String>,
+        language: Option<String>,
+        tag_count: Option<u32>,
+    ) -> AppResult<RenderedPrompt> {
+        let template = self
+            .prompt_template_repository
+            .find_by_name(PERSONALITY_ANALYSIS_TEMPLATE.to_string())
+            .await?;
+        let version_id = template
+            .active_version_id
+            .ok_or_else(|| AppError::Internal(anyhow::anyhow!("Prompt template has no active version")))?;
+        let version = self.prompt_template_repository.find_version_by_id(version_id).await?;
+
+        let variables = PromptVariables {
+            posts: Some(posts),
+            language: Some(language.unwrap_or_else(|| DEFAULT_LANGUAGE.to_string())),
+            tag_count: Some(tag_count.unwrap_or(DEFAULT_TAG_COUNT)),
         };
 
+        Ok(RenderedPrompt {
+            template_version_id: version.id,
+            text: variables.render(&version.template_text)?,
+        })
+    }
+
+    pub async fn analyze(&self, request: AIAnalysisRequest) -> AppResult<AIAnalysisResponse> {
         let response = self.ai_service_repository.analyze_personality(request).await?;
         Ok(response)
     }
 
-    pub async fn chat_with_bot(&self, message: String) -> Result<ChatResponse> {
+    pub async fn analyze_user_personality(&self, user_id: Uuid) -> AppResult<AIAnalysisResponse> {
+        let posts = self.import_user_posts(user_id).await?;
+        let prompt = self
+            .render_personality_prompt(posts.clone(), None, None)
+            .await?;
+
+        self.analyze(AIAnalysisRequest {
+            user_id: user_id.to_string(),
+            posts,
+            prompt: prompt.text,
+        })
+        .await
+    }
+
+    pub async fn chat_with_bot(&self, message: String) -> AppResult<ChatResponse> {
         let request = ChatRequest {
             message,
         };
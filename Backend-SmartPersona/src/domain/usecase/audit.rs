@@ -0,0 +1,32 @@
+use std::sync::Arc;
+
+use crate::domain::{
+    error::AppResult,
+    service::audit_logger::AuditLogger,
+    value_object::{
+        admin_user::PageModel,
+        audit_event::{AuditEventModel, AuditEventQuery},
+    },
+};
+
+pub struct AuditUseCase {
+    audit_logger: Arc<AuditLogger>,
+}
+
+impl AuditUseCase {
+    pub fn new(audit_logger: Arc<AuditLogger>) -> Self {
+        Self { audit_logger }
+    }
+
+    pub async fn list_events(&self, query: AuditEventQuery) -> AppResult<PageModel<AuditEventModel>> {
+        let filter = query.to_filter()?;
+        let page_query = query.page_query();
+        let (events, total) = self.audit_logger.search(filter).await?;
+        Ok(PageModel {
+            items: events.into_iter().map(AuditEventModel::from).collect(),
+            page: page_query.page(),
+            per_page: page_query.per_page(),
+            total,
+        })
+    }
+}
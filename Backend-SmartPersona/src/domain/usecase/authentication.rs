@@ -1,189 +1,947 @@
-use std::sync::Arc;
+use std::{sync::Arc, time::Duration};
 
-use anyhow::Result;
-use chrono::{Duration, Utc};
+use chrono::{DateTime, NaiveDateTime, TimeDelta, Utc};
+use rand::{distributions::Alphanumeric, Rng};
+use serde_json::json;
+use tracing::warn;
+use uuid::Uuid;
 
 use crate::{
-    config::config_loader::{get_admin_secret, get_user_secret},
-    domain::{entities::user, repo::user::UserRepository},
+    domain::{
+        entities::{
+            mfa::{InsertRecoveryCodeEntity, UserMfaEntity},
+            session::{InsertSessionEntity, SessionEntity},
+            user::{Role, UserEntity, UserStatus},
+        },
+        error::{AppError, AppResult},
+        repo::{mfa::MfaRepository, session::SessionRepository, user::UserRepository},
+        service::audit_logger::{self, AuditAction, AuditContext, AuditEvent, AuditLogger},
+    },
     infrastructure::{
         hashingpassword,
         jwt_authentication::{
             self,
-            authentication_model::LoginModel,
-            jwt_model::{Claims, Passport, Roles},
+            authentication_model::{LoginModel, MfaEnrolmentModel, MfaVerifyModel},
+            jwt_keys::{JwtKeys, TokenKind},
+            jwt_model::{Claims, LoginOutcome, MfaPendingClaims, Passport},
         },
+        totp,
     },
 };
 
-pub struct AuthenticationUseCase<T>
+// Long enough to open the authenticator app, short enough that a stolen token is useless.
+const MFA_TOKEN_LIFETIME_SECONDS: i64 = 300;
+const RECOVERY_CODE_COUNT: usize = 10;
+const RECOVERY_CODE_HALF_LENGTH: usize = 5;
+// Checked against when there is no password hash to check, so refusing an unknown or locked
+// account takes as long as refusing a wrong password. Uses the same parameters as `hashingpassword::hash`.
+const DUMMY_PASSWORD_HASH: &str = "$argon2id$v=19$m=19456,t=2,p=1$kBhj8+cBkgbxxFHhbA7F5w$+VqRFWKS1Coxio177n1auiSMEfDRQ7FT1+L9NyBoYvk";
+
+pub struct AuthenticationUseCase<T1, T2, T3>
 where
-    T: UserRepository + Send + Sync,
-    // T2: GuildCommandersRepository + Send + Sync,
+    T1: UserRepository + Send + Sync,
+    T2: SessionRepository + Send + Sync,
+    T3: MfaRepository + Send + Sync,
 {
-    User_repository: Arc<T>,
-    // guild_commanders_repository: Arc<T2>,
+    user_repository: Arc<T1>,
+    session_repository: Arc<T2>,
+    mfa_repository: Arc<T3>,
+    jwt_keys: Arc<JwtKeys>,
+    audit_logger: Arc<AuditLogger>,
+    settings: AuthenticationSettings,
+}
+
+pub struct AuthenticationSettings {
+    pub access_token_lifetime: Duration,
+    pub refresh_token_lifetime: Duration,
+    // Shown as the account's issuer in authenticator apps.
+    pub mfa_issuer: String,
+    pub lockout: LockoutPolicy,
+}
+
+// After `backoff_after` consecutive failures each further one doubles the wait, starting
+// at `backoff_base`; reaching `lockout_after` locks the account for `lockout_duration`.
+pub struct LockoutPolicy {
+    pub backoff_after: i32,
+    pub backoff_base: Duration,
+    pub lockout_after: i32,
+    pub lockout_duration: Duration,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum FailedLoginDelay {
+    Backoff(Duration),
+    Lockout(Duration),
 }
 
-impl<T> AuthenticationUseCase<T>
+impl LockoutPolicy {
+    fn delay_after(&self, failed_attempts: i32) -> Option<FailedLoginDelay> {
+        if failed_attempts >= self.lockout_after {
+            Some(FailedLoginDelay::Lockout(self.lockout_duration))
+        } else if failed_attempts >= self.backoff_after {
+            let doublings = (failed_attempts - self.backoff_after).min(16) as u32;
+            let delay = self
+                .backoff_base
+                .saturating_mul(2u32.pow(doublings))
+                .min(self.lockout_duration);
+            Some(FailedLoginDelay::Backoff(delay))
+        } else {
+            None
+        }
+    }
+}
+
+impl<T1, T2, T3> AuthenticationUseCase<T1, T2, T3>
 where
-    T:  UserRepository + Send + Sync,
+    T1: UserRepository + Send + Sync,
+    T2: SessionRepository + Send + Sync,
+    T3: MfaRepository + Send + Sync,
 {
-    pub fn new(User_repository: Arc<T>) -> Self {
+    pub fn new(
+        user_repository: Arc<T1>,
+        session_repository: Arc<T2>,
+        mfa_repository: Arc<T3>,
+        jwt_keys: Arc<JwtKeys>,
+        audit_logger: Arc<AuditLogger>,
+        settings: AuthenticationSettings,
+    ) -> Self {
         Self {
-            User_repository,
+            user_repository,
+            session_repository,
+            mfa_repository,
+            jwt_keys,
+            audit_logger,
+            settings,
         }
     }
 
-    pub async fn user_login(&self, login_model: LoginModel) -> Result<Passport> {
-        let secret_env = get_user_secret()?;
+    pub async fn user_login(
+        &self,
+        login_model: LoginModel,
+        device: Option<String>,
+        context: &AuditContext,
+    ) -> AppResult<LoginOutcome> {
+        let user = self.verify_credentials(login_model, context).await?;
 
-        let user = self
-            .User_repository
-            .find_by_username(login_model.username.clone())
-            .await?;
+        self.begin_login(user, TokenKind::UserAccess, device, context).await
+    }
+
+    pub async fn user_refresh_token(&self, refresh_token: String, context: &AuditContext) -> AppResult<Passport> {
+        let claims = jwt_authentication::verify_token(&self.jwt_keys, TokenKind::UserRefresh, refresh_token)
+            .map_err(|_| AppError::unauthorized("Invalid refresh token"))?;
+        let (session, refresh_token_id) = self.rotate(&claims, context).await?;
+        let user = self.user_repository.find_by_id(session.user_id).await?;
+
+        self.issue_passport(&session, refresh_token_id, user.role, TokenKind::UserAccess)
+    }
 
-        let original_password = user.password_hash;
-        let login_password = login_model.password;
+    pub async fn admin_login(
+        &self,
+        login_model: LoginModel,
+        device: Option<String>,
+        context: &AuditContext,
+    ) -> AppResult<LoginOutcome> {
+        let user = self.verify_credentials(login_model, context).await?;
 
-        if !hashingpassword::verify(login_password, original_password)? {
-            return Err(anyhow::anyhow!("Invalid password"));
+        // Check if user has the Admin role
+        if user.role != Role::Admin {
+            self.audit_failed_login(context, Some(user.id), &user.username, "admin_access_required").await;
+            return Err(AppError::forbidden("Admin access required"));
         }
 
-        let access_token_claims = Claims {
-            sub: user.id.to_string(),
-            role: Roles::UserAndCompany,
-            exp: (Utc::now() + Duration::days(1)).timestamp() as usize,
-            iat: Utc::now().timestamp() as usize,
+        self.begin_login(user, TokenKind::AdminAccess, device, context).await
+    }
+
+    pub async fn admin_refresh_token(&self, refresh_token: String, context: &AuditContext) -> AppResult<Passport> {
+        let claims = jwt_authentication::verify_token(&self.jwt_keys, TokenKind::AdminRefresh, refresh_token)
+            .map_err(|_| AppError::unauthorized("Invalid refresh token"))?;
+        let (session, refresh_token_id) = self.rotate(&claims, context).await?;
+        let user = self.user_repository.find_by_id(session.user_id).await?;
+
+        // An admin demoted since the last refresh loses the admin session straight away.
+        if user.role != Role::Admin {
+            self.session_repository.revoke(session.id).await?;
+            return Err(AppError::forbidden("Admin access required"));
+        }
+
+        self.issue_passport(&session, refresh_token_id, user.role, TokenKind::AdminAccess)
+    }
+
+    // Revokes the session behind a user or admin refresh token. Unknown or invalid
+    // tokens are ignored so logging out always succeeds.
+    pub async fn logout(&self, refresh_token: String) -> AppResult<()> {
+        let user_claims = jwt_authentication::verify_token(&self.jwt_keys, TokenKind::UserRefresh, refresh_token.clone());
+        let claims = match user_claims {
+            Ok(claims) => claims,
+            Err(_) => match jwt_authentication::verify_token(&self.jwt_keys, TokenKind::AdminRefresh, refresh_token) {
+                Ok(claims) => claims,
+                Err(_) => return Ok(()),
+            },
         };
 
-        let refresh_token_claims = Claims {
-            sub: user.id.to_string(),
-            role: Roles::UserAndCompany,
-            exp: (Utc::now() + Duration::days(7)).timestamp() as usize,
-            iat: Utc::now().timestamp() as usize,
+        if let Ok(session_id) = Uuid::parse_str(&claims.sid) {
+            self.session_repository.revoke(session_id).await?;
+        }
+        Ok(())
+    }
+
+    pub async fn logout_all(&self, user_id: Uuid) -> AppResult<usize> {
+        self.session_repository.revoke_all_for_user(user_id).await
+    }
+
+    // Second login step. For an admin finishing a forced enrolment the first code also
+    // confirms it, and the new recovery codes are returned alongside the passport.
+    pub async fn verify_mfa(
+        &self,
+        mfa_verify_model: MfaVerifyModel,
+        device: Option<String>,
+        context: &AuditContext,
+    ) -> AppResult<(Passport, Option<Vec<String>>)> {
+        let claims = self.verify_mfa_token(&mfa_verify_model.mfa_token)?;
+        let user_id = Uuid::parse_str(&claims.sub).map_err(|_| AppError::unauthorized("Invalid MFA token"))?;
+        let user = self.user_repository.find_by_id(user_id).await?;
+        ensure_not_locked(&user)?;
+        ensure_active(&user)?;
+        let mfa = self.find_mfa(user.id).await?;
+
+        let second_factor = if mfa.is_confirmed() {
+            self.check_second_factor(&mfa, &mfa_verify_model.code).await.map(|()| None)
+        } else {
+            self.confirm_enrolment(&mfa, &mfa_verify_model.code).await.map(Some)
+        };
+        let recovery_codes = match second_factor {
+            Ok(recovery_codes) => recovery_codes,
+            Err(e @ AppError::Unauthorized(_)) => {
+                self.record_failed_attempt(user.id).await?;
+                self.audit_failed_login(context, Some(user.id), &user.username, "invalid_mfa_code").await;
+                return Err(e);
+            }
+            Err(e) => return Err(e),
         };
 
-        let access_token =
-            jwt_authentication::generate_token(secret_env.user_secret, &access_token_claims)?;
+        let access_kind = if claims.admin { TokenKind::AdminAccess } else { TokenKind::UserAccess };
+        if access_kind.is_admin() && user.role != Role::Admin {
+            return Err(AppError::forbidden("Admin access required"));
+        }
+        self.use_mfa_token(&claims, user.id).await?;
 
-        let refresh_token =
-            jwt_authentication::generate_token(secret_env.user_refresh_secret, &refresh_token_claims)?;
+        self.clear_failed_attempts(&user).await?;
+        let session = self.start_session(user.id, device).await?;
+        self.audit_login(context, &session, access_kind, true).await;
+        let passport = self.issue_passport(&session, session.current_refresh_token_id, user.role, access_kind)?;
+        Ok((passport, recovery_codes))
+    }
 
-        Ok(Passport {
-            refresh_token,
-            access_token,
+    // Forced enrolment for admins who logged in without MFA; authorised by the MFA token
+    // because they have no session yet.
+    pub async fn start_login_mfa_enrolment(&self, mfa_token: String) -> AppResult<MfaEnrolmentModel> {
+        let claims = self.verify_mfa_token(&mfa_token)?;
+        let user_id = Uuid::parse_str(&claims.sub).map_err(|_| AppError::unauthorized("Invalid MFA token"))?;
+
+        self.start_mfa_enrolment(user_id).await
+    }
+
+    pub async fn start_mfa_enrolment(&self, user_id: Uuid) -> AppResult<MfaEnrolmentModel> {
+        match self.mfa_repository.find_by_user_id(user_id).await {
+            Ok(mfa) if mfa.is_confirmed() => return Err(AppError::conflict("MFA is already enabled")),
+            Ok(_) | Err(AppError::NotFound(_)) => {}
+            Err(e) => return Err(e),
+        }
+
+        let user = self.user_repository.find_by_id(user_id).await?;
+        let mfa = self.mfa_repository.start_enrolment(user.id, totp::generate_secret()).await?;
+        let otpauth_uri = totp::otpauth_uri(&mfa.secret, &self.settings.mfa_issuer, &user.username)?;
+
+        Ok(MfaEnrolmentModel {
+            secret: mfa.secret,
+            otpauth_uri,
         })
     }
 
-    pub async fn user_refresh_token(&self, refresh_token: String) -> Result<Passport> {
-        let secret_env = get_user_secret()?;
+    pub async fn confirm_mfa_enrolment(&self, user_id: Uuid, code: String) -> AppResult<Vec<String>> {
+        let mfa = self.find_mfa(user_id).await?;
+        if mfa.is_confirmed() {
+            return Err(AppError::conflict("MFA is already enabled"));
+        }
 
-        let claims = jwt_authentication::verify_token(
-            secret_env.user_refresh_secret.clone(),
-            refresh_token.clone(),
-        )?;
+        self.confirm_enrolment(&mfa, &code).await
+    }
 
-        let access_token_claims = Claims {
-            sub: claims.sub.clone(),
-            role: Roles::UserAndCompany,
-            exp: (Utc::now() + Duration::days(1)).timestamp() as usize,
-            iat: Utc::now().timestamp() as usize,
+    // Replaces every recovery code; the old ones stop working.
+    pub async fn regenerate_recovery_codes(&self, user_id: Uuid, code: String) -> AppResult<Vec<String>> {
+        let mfa = self.find_mfa(user_id).await?;
+        if !mfa.is_confirmed() {
+            return Err(AppError::validation("MFA is not enabled"));
+        }
+        if !self.verify_totp(&mfa, &code).await? {
+            return Err(AppError::unauthorized("Invalid authentication code"));
+        }
+
+        let (recovery_codes, insert_recovery_codes) = generate_recovery_codes(user_id)?;
+        self.mfa_repository
+            .replace_recovery_codes(user_id, insert_recovery_codes)
+            .await?;
+        Ok(recovery_codes)
+    }
+
+    pub async fn disable_mfa(&self, user_id: Uuid, role: Role, code: String) -> AppResult<()> {
+        if role == Role::Admin {
+            return Err(AppError::forbidden("MFA is mandatory for admins"));
+        }
+
+        let mfa = self.find_mfa(user_id).await?;
+        if mfa.is_confirmed() {
+            self.check_second_factor(&mfa, &code).await?;
+        }
+        self.mfa_repository.delete(user_id).await
+    }
+
+    // Unknown usernames, wrong passwords and locked or backed-off accounts all get the same
+    // error so accounts can't be enumerated. The per-username rate limit in front of this
+    // treats every name alike.
+    async fn verify_credentials(&self, login_model: LoginModel, context: &AuditContext) -> AppResult<UserEntity> {
+        let invalid_credentials = || AppError::unauthorized("Invalid username or password");
+
+        let user = match self.user_repository.find_by_username(login_model.username.clone()).await {
+            Ok(user) => user,
+            Err(AppError::NotFound(_)) => {
+                verify_dummy_password(login_model.password);
+                self.audit_failed_login(context, None, &login_model.username, "unknown_username").await;
+                return Err(invalid_credentials());
+            }
+            Err(e) => return Err(e),
         };
+        if ensure_not_locked(&user).is_err() {
+            verify_dummy_password(login_model.password);
+            self.audit_failed_login(context, Some(user.id), &user.username, "locked").await;
+            return Err(invalid_credentials());
+        }
 
-        let refresh_token_claims = Claims {
-            sub: claims.sub,
-            role: Roles::UserAndCompany,
-            exp: claims.exp,
-            iat: Utc::now().timestamp() as usize,
+        if !hashingpassword::verify(login_model.password, user.password_hash.clone())? {
+            self.record_failed_attempt(user.id).await?;
+            self.audit_failed_login(context, Some(user.id), &user.username, "invalid_password").await;
+            return Err(invalid_credentials());
+        }
+        // Checked only after the password so the status of an account is not revealed to guessers.
+        if let Err(e) = ensure_active(&user) {
+            self.audit_failed_login(context, Some(user.id), &user.username, e.code()).await;
+            return Err(e);
+        }
+
+        Ok(user)
+    }
+
+    // The target is the account being logged into; there is no actor since nobody is signed in.
+    // A name that matches no account is only stored pseudonymised.
+    async fn audit_failed_login(&self, context: &AuditContext, user_id: Option<Uuid>, username: &str, reason: &str) {
+        let event = match user_id {
+            Some(user_id) => AuditEvent::new(AuditAction::LoginFailed)
+                .target_user(user_id)
+                .changes(json!({ "reason": reason })),
+            None => AuditEvent::new(AuditAction::LoginFailed)
+                .changes(json!({ "username_hash": audit_logger::pseudonymise(username), "reason": reason })),
         };
+        self.audit_logger.record(context, event).await;
+    }
 
-        let access_token =
-            jwt_authentication::generate_token(secret_env.user_secret, &access_token_claims)?;
+    async fn audit_login(&self, context: &AuditContext, session: &SessionEntity, access_kind: TokenKind, mfa: bool) {
+        let event = AuditEvent::new(AuditAction::LoginSucceeded)
+            .actor(session.user_id)
+            .target_user(session.user_id)
+            .changes(json!({ "session_id": session.id, "admin": access_kind.is_admin(), "mfa": mfa }));
+        self.audit_logger.record(context, event).await;
+    }
 
-        let refresh_token =
-            jwt_authentication::generate_token(secret_env.user_refresh_secret, &refresh_token_claims)?;
+    async fn record_failed_attempt(&self, user_id: Uuid) -> AppResult<()> {
+        let failed_attempts = self.user_repository.record_failed_login(user_id).await?;
+        let now = Utc::now().naive_utc();
+
+        match self.settings.lockout.delay_after(failed_attempts) {
+            Some(FailedLoginDelay::Lockout(duration)) => {
+                warn!("locking user {} after {} failed login attempts", user_id, failed_attempts);
+                self.user_repository.delay_login(user_id, expires_after(now, duration)?).await?;
+            }
+            Some(FailedLoginDelay::Backoff(delay)) => {
+                self.user_repository.delay_login(user_id, expires_after(now, delay)?).await?;
+            }
+            None => {}
+        }
+        Ok(())
+    }
 
-        Ok(Passport {
-            refresh_token,
-            access_token,
-        })
+    async fn clear_failed_attempts(&self, user: &UserEntity) -> AppResult<()> {
+        if user.failed_login_attempts > 0 || user.locked_until.is_some() {
+            self.user_repository.clear_failed_logins(user.id).await?;
+        }
+        Ok(())
+    }
+
+    // Users with MFA, and every admin, get an MFA token instead of a session.
+    async fn begin_login(
+        &self,
+        user: UserEntity,
+        access_kind: TokenKind,
+        device: Option<String>,
+        context: &AuditContext,
+    ) -> AppResult<LoginOutcome> {
+        let mfa_confirmed = match self.mfa_repository.find_by_user_id(user.id).await {
+            Ok(mfa) => mfa.is_confirmed(),
+            Err(AppError::NotFound(_)) => false,
+            Err(e) => return Err(e),
+        };
+
+        if mfa_confirmed {
+            return Ok(LoginOutcome::MfaRequired {
+                mfa_token: self.issue_mfa_token(user.id, access_kind)?,
+            });
+        }
+        if user.role == Role::Admin {
+            return Ok(LoginOutcome::MfaEnrolmentRequired {
+                mfa_token: self.issue_mfa_token(user.id, access_kind)?,
+            });
+        }
+
+        self.clear_failed_attempts(&user).await?;
+        let session = self.start_session(user.id, device).await?;
+        self.audit_login(context, &session, access_kind, false).await;
+        let passport = self.issue_passport(&session, session.current_refresh_token_id, user.role, access_kind)?;
+        Ok(LoginOutcome::Authenticated(passport))
+    }
+
+    async fn find_mfa(&self, user_id: Uuid) -> AppResult<UserMfaEntity> {
+        match self.mfa_repository.find_by_user_id(user_id).await {
+            Err(AppError::NotFound(_)) => Err(AppError::validation("MFA enrolment has not been started")),
+            result => result,
+        }
     }
 
-    pub async fn admin_login(&self, login_model: LoginModel) -> Result<Passport> {
-        let secret_env = get_admin_secret()?;
+    async fn confirm_enrolment(&self, mfa: &UserMfaEntity, code: &str) -> AppResult<Vec<String>> {
+        if !self.verify_totp(mfa, code).await? {
+            return Err(AppError::unauthorized("Invalid authentication code"));
+        }
 
-        let user = self
-            .User_repository
-            .find_by_username(login_model.username.clone())
+        let (recovery_codes, insert_recovery_codes) = generate_recovery_codes(mfa.user_id)?;
+        self.mfa_repository
+            .confirm(mfa.user_id, insert_recovery_codes)
             .await?;
+        Ok(recovery_codes)
+    }
 
-        // Check if user has the Admin role
-        if user.role != user::Role::Admin {
-         return Err(anyhow::anyhow!("Unauthorized: Not an admin"));
+    // Accepts a current TOTP code or, failing that, an unused recovery code.
+    async fn check_second_factor(&self, mfa: &UserMfaEntity, code: &str) -> AppResult<()> {
+        if self.verify_totp(mfa, code).await? {
+            return Ok(());
         }
 
-        if !hashingpassword::verify(login_model.password, user.password_hash)? {
-            return Err(anyhow::anyhow!("Invalid password"));
+        let normalized_code = normalize_recovery_code(code);
+        for recovery_code in self.mfa_repository.unused_recovery_codes(mfa.user_id).await? {
+            if hashingpassword::verify(normalized_code.clone(), recovery_code.code_hash.clone())?
+                && self.mfa_repository.use_recovery_code(recovery_code.id).await?
+            {
+                return Ok(());
+            }
         }
 
+        Err(AppError::unauthorized("Invalid authentication code"))
+    }
+
+    // A code is only good once, even inside its 30 second window.
+    async fn verify_totp(&self, mfa: &UserMfaEntity, code: &str) -> AppResult<bool> {
+        match totp::verify(&mfa.secret, code, Utc::now().timestamp() as u64)? {
+            Some(step) => self.mfa_repository.use_step(mfa.user_id, step).await,
+            None => Ok(false),
+        }
+    }
+
+    fn issue_mfa_token(&self, user_id: Uuid, access_kind: TokenKind) -> AppResult<String> {
+        let now = Utc::now();
+        let claims = MfaPendingClaims {
+            sub: user_id.to_string(),
+            iss: self.jwt_keys.issuer.clone(),
+            aud: self.jwt_keys.audience(TokenKind::MfaPending).to_string(),
+            exp: (now + TimeDelta::seconds(MFA_TOKEN_LIFETIME_SECONDS)).timestamp() as usize,
+            iat: now.timestamp() as usize,
+            jti: Uuid::new_v4().to_string(),
+            admin: access_kind.is_admin(),
+        };
+
+        Ok(jwt_authentication::generate_mfa_token(&self.jwt_keys, &claims)?)
+    }
+
+    fn verify_mfa_token(&self, mfa_token: &str) -> AppResult<MfaPendingClaims> {
+        jwt_authentication::verify_mfa_token(&self.jwt_keys, mfa_token)
+            .map_err(|_| AppError::unauthorized("Invalid or expired MFA token"))
+    }
+
+    // An MFA token completes one login only, so a leaked token and code pair cannot
+    // open a second session while the code is still within its window.
+    async fn use_mfa_token(&self, claims: &MfaPendingClaims, user_id: Uuid) -> AppResult<()> {
+        let invalid = || AppError::unauthorized("Invalid or expired MFA token");
+        let jti = Uuid::parse_str(&claims.jti).map_err(|_| invalid())?;
+        let expires_at = DateTime::from_timestamp(claims.exp as i64, 0).ok_or_else(invalid)?.naive_utc();
+
+        if !self.mfa_repository.use_mfa_token(jti, user_id, expires_at).await? {
+            return Err(AppError::unauthorized("MFA token has already been used"));
+        }
+        Ok(())
+    }
+
+    async fn start_session(&self, user_id: Uuid, device: Option<String>) -> AppResult<SessionEntity> {
+        let now = Utc::now().naive_utc();
+        self.session_repository
+            .create(InsertSessionEntity {
+                id: Uuid::new_v4(),
+                user_id,
+                device,
+                created_at: now,
+                last_used_at: now,
+                expires_at: expires_after(now, self.settings.refresh_token_lifetime)?,
+                current_refresh_token_id: Uuid::new_v4(),
+            })
+            .await
+    }
+
+    // Refresh tokens are single use. Presenting one that was already exchanged means it
+    // leaked, so the whole session (the token family) is revoked and the user must log in.
+    // Callers re-read the user afterwards; the role in the presented claims may be stale.
+    async fn rotate(&self, claims: &Claims, context: &AuditContext) -> AppResult<(SessionEntity, Uuid)> {
+        let revoked = || AppError::unauthorized("Session has been revoked");
+
+        let session_id = Uuid::parse_str(&claims.sid).map_err(|_| revoked())?;
+        let presented_token_id = Uuid::parse_str(&claims.jti).map_err(|_| revoked())?;
+        let session = match self.session_repository.find_by_id(session_id).await {
+            Ok(session) => session,
+            Err(AppError::NotFound(_)) => return Err(revoked()),
+            Err(e) => return Err(e),
+        };
+
+        if session.user_id.to_string() != claims.sub || !session.is_active(Utc::now().naive_utc()) {
+            return Err(revoked());
+        }
+
+        let new_token_id = Uuid::new_v4();
+        if !self
+            .session_repository
+            .rotate_refresh_token(session.id, presented_token_id, new_token_id)
+            .await?
+        {
+            warn!("refresh token reuse detected, revoking session {}", session.id);
+            self.session_repository.revoke(session.id).await?;
+            return Err(AppError::unauthorized("Refresh token has already been used, please log in again"));
+        }
+
+        let event = AuditEvent::new(AuditAction::TokenRefreshed)
+            .actor(session.user_id)
+            .target_user(session.user_id)
+            .changes(json!({ "session_id": session.id }));
+        self.audit_logger.record(context, event).await;
+        Ok((session, new_token_id))
+    }
+
+    // The refresh token lives exactly as long as its session, so rotating it never
+    // extends how long the user stays logged in.
+    fn issue_passport(
+        &self,
+        session: &SessionEntity,
+        refresh_token_id: Uuid,
+        role: Role,
+        access_kind: TokenKind,
+    ) -> AppResult<Passport> {
+        let refresh_kind = access_kind.refresh();
+        let now = Utc::now().naive_utc();
+        let access_token_expires_at = expires_after(now, self.settings.access_token_lifetime)?;
+
         let access_token_claims = Claims {
-            sub: user.id.to_string(),
-            role: Roles::Admin,
-            exp: (Utc::now() + Duration::days(1)).timestamp() as usize,
-            iat: Utc::now().timestamp() as usize,
+            sub: session.user_id.to_string(),
+            role,
+            iss: self.jwt_keys.issuer.clone(),
+            aud: self.jwt_keys.audience(access_kind).to_string(),
+            exp: access_token_expires_at.and_utc().timestamp() as usize,
+            iat: now.and_utc().timestamp() as usize,
+            jti: Uuid::new_v4().to_string(),
+            sid: session.id.to_string(),
+            act: None,
         };
 
         let refresh_token_claims = Claims {
-            sub: user.id.to_string(),
-            role: Roles::Admin,
-            exp: (Utc::now() + Duration::days(7)).timestamp() as usize,
-            iat: Utc::now().timestamp() as usize,
+            sub: session.user_id.to_string(),
+            role,
+            iss: self.jwt_keys.issuer.clone(),
+            aud: self.jwt_keys.audience(refresh_kind).to_string(),
+            exp: session.expires_at.and_utc().timestamp() as usize,
+            iat: now.and_utc().timestamp() as usize,
+            jti: refresh_token_id.to_string(),
+            sid: session.id.to_string(),
+            act: None,
         };
 
-        let access_token = 
-            jwt_authentication::generate_token(secret_env.admin_secret, &access_token_claims)?;
-
-        let refresh_token = 
-            jwt_authentication::generate_token(secret_env.admin_refresh_secret, &refresh_token_claims)?;
+        let access_token = jwt_authentication::generate_token(&self.jwt_keys, access_kind, &access_token_claims)?;
+        let refresh_token = jwt_authentication::generate_token(&self.jwt_keys, refresh_kind, &refresh_token_claims)?;
 
         Ok(Passport {
             refresh_token,
             access_token,
+            access_token_expires_in: (access_token_expires_at - now).num_seconds(),
+            refresh_token_expires_in: (session.expires_at - now).num_seconds().max(0),
         })
     }
+}
 
-   pub async fn admin_refresh_token(&self, refresh_token: String) -> Result<Passport> {
-        let secret_env = get_admin_secret()?;
+fn verify_dummy_password(password: String) {
+    let _ = hashingpassword::verify(password, DUMMY_PASSWORD_HASH.to_string());
+}
 
-        let claims = jwt_authentication::verify_token(
-            secret_env.admin_refresh_secret.clone(),
-            refresh_token.clone(),
-        )?;
+fn ensure_not_locked(user: &UserEntity) -> AppResult<()> {
+    let now = Utc::now().naive_utc();
+    match user.locked_until {
+        Some(locked_until) if locked_until > now => Err(AppError::too_many_requests(
+            "Too many failed login attempts, please try again later",
+            (locked_until - now).num_seconds().max(1) as u64,
+        )),
+        _ => Ok(()),
+    }
+}
 
-        let access_token_claims = Claims {
-            sub: claims.sub.clone(),
-            role: Roles::Admin,
-            exp: (Utc::now() + Duration::days(1)).timestamp() as usize,
-            iat: Utc::now().timestamp() as usize,
-        };
+// Login lockouts only set `locked_until` and are handled by `ensure_not_locked`.
+fn ensure_active(user: &UserEntity) -> AppResult<()> {
+    match user.status {
+        UserStatus::Active => Ok(()),
+        status => Err(AppError::AccountInactive(status)),
+    }
+}
 
-        let refresh_token_claims = Claims {
-            sub: claims.sub,
-            role: Roles::UserAndCompany,
-            exp: claims.exp,
-            iat: Utc::now().timestamp() as usize,
+fn expires_after(now: NaiveDateTime, lifetime: Duration) -> AppResult<NaiveDateTime> {
+    TimeDelta::from_std(lifetime)
+        .ok()
+        .and_then(|lifetime| now.checked_add_signed(lifetime))
+        .ok_or_else(|| anyhow::anyhow!("Token lifetime {:?} is out of range", lifetime).into())
+}
+
+// Returns the codes to show the user once, and their hashes to store.
+fn generate_recovery_codes(user_id: Uuid) -> AppResult<(Vec<String>, Vec<InsertRecoveryCodeEntity>)> {
+    let now = Utc::now().naive_utc();
+    let mut recovery_codes = Vec::with_capacity(RECOVERY_CODE_COUNT);
+    let mut insert_recovery_codes = Vec::with_capacity(RECOVERY_CODE_COUNT);
+
+    for _ in 0..RECOVERY_CODE_COUNT {
+        let code: String = rand::thread_rng()
+            .sample_iter(&Alphanumeric)
+            .take(RECOVERY_CODE_HALF_LENGTH * 2)
+            .map(|c| char::from(c).to_ascii_lowercase())
+            .collect();
+        insert_recovery_codes.push(InsertRecoveryCodeEntity {
+            user_id,
+            code_hash: hashingpassword::hash(code.clone())?,
+            created_at: now,
+        });
+        recovery_codes.push(format!("{}-{}", &code[..RECOVERY_CODE_HALF_LENGTH], &code[RECOVERY_CODE_HALF_LENGTH..]));
+    }
+
+    Ok((recovery_codes, insert_recovery_codes))
+}
+
+fn normalize_recovery_code(code: &str) -> String {
+    code.chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .map(|c| c.to_ascii_lowercase())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use crate::{
+        config::config_model::{Jwt, JwtAdminSecret, JwtSecret, JwtSigning},
+        domain::repo::{
+            audit_event::MockAuditEventRepository, mfa::MockMfaRepository, session::MockSessionRepository,
+            user::MockUserRepository,
+        },
+    };
+
+    use super::*;
+
+    fn policy() -> LockoutPolicy {
+        LockoutPolicy {
+            backoff_after: 3,
+            backoff_base: Duration::from_secs(2),
+            lockout_after: 10,
+            lockout_duration: Duration::from_secs(15 * 60),
+        }
+    }
+
+    #[test]
+    fn no_delay_before_backoff_starts() {
+        assert_eq!(policy().delay_after(1), None);
+        assert_eq!(policy().delay_after(2), None);
+    }
+
+    #[test]
+    fn backoff_doubles_with_each_failure() {
+        let policy = policy();
+        assert_eq!(policy.delay_after(3), Some(FailedLoginDelay::Backoff(Duration::from_secs(2))));
+        assert_eq!(policy.delay_after(4), Some(FailedLoginDelay::Backoff(Duration::from_secs(4))));
+        assert_eq!(policy.delay_after(5), Some(FailedLoginDelay::Backoff(Duration::from_secs(8))));
+        assert_eq!(policy.delay_after(9), Some(FailedLoginDelay::Backoff(Duration::from_secs(128))));
+    }
+
+    #[test]
+    fn backoff_never_exceeds_the_lockout_duration() {
+        let policy = LockoutPolicy {
+            lockout_after: 100,
+            ..policy()
         };
+        assert_eq!(policy.delay_after(50), Some(FailedLoginDelay::Backoff(policy.lockout_duration)));
+    }
 
-        let access_token =
-            jwt_authentication::generate_token(secret_env.admin_secret, &access_token_claims)?;
+    #[test]
+    fn locks_once_the_threshold_is_reached() {
+        let policy = policy();
+        assert_eq!(policy.delay_after(10), Some(FailedLoginDelay::Lockout(policy.lockout_duration)));
+        assert_eq!(policy.delay_after(11), Some(FailedLoginDelay::Lockout(policy.lockout_duration)));
+    }
 
-        let refresh_token =
-            jwt_authentication::generate_token(secret_env.admin_refresh_secret, &refresh_token_claims)?;
+    fn user(status: UserStatus, locked_until: Option<NaiveDateTime>) -> UserEntity {
+        let now = Utc::now().naive_utc();
+        UserEntity {
+            id: Uuid::new_v4(),
+            username: "alice".to_string(),
+            password_hash: String::new(),
+            display_name: None,
+            role: Role::PersonaUser,
+            status,
+            created_at: now,
+            updated_at: now,
+            first_name: "Alice".to_string(),
+            last_name: "Example".to_string(),
+            failed_login_attempts: 0,
+            locked_until,
+            email: None,
+            erasure_scheduled_at: None,
+        }
+    }
 
-        Ok(Passport {
-            refresh_token,
-            access_token,
+    #[test]
+    fn only_active_accounts_may_sign_in() {
+        assert!(ensure_active(&user(UserStatus::Active, None)).is_ok());
+        for status in [UserStatus::Pending, UserStatus::Suspended, UserStatus::Locked, UserStatus::Banned] {
+            assert!(matches!(ensure_active(&user(status, None)), Err(AppError::AccountInactive(s)) if s == status));
+        }
+    }
+
+    #[test]
+    fn lockout_ends_when_locked_until_passes() {
+        let now = Utc::now().naive_utc();
+        let locked = user(UserStatus::Active, Some(now + TimeDelta::minutes(5)));
+        assert!(matches!(ensure_not_locked(&locked), Err(AppError::TooManyRequests { .. })));
+        assert!(ensure_not_locked(&user(UserStatus::Active, Some(now - TimeDelta::seconds(1)))).is_ok());
+        assert!(ensure_not_locked(&user(UserStatus::Active, None)).is_ok());
+    }
+
+    #[test]
+    fn an_expired_lockout_does_not_lift_a_ban() {
+        let banned = user(UserStatus::Banned, Some(Utc::now().naive_utc() - TimeDelta::seconds(1)));
+        assert!(ensure_not_locked(&banned).is_ok());
+        assert!(matches!(ensure_active(&banned), Err(AppError::AccountInactive(UserStatus::Banned))));
+    }
+
+    #[test]
+    fn dummy_password_hash_costs_as_much_as_a_real_one() {
+        assert!(!hashingpassword::verify("correct horse battery".to_string(), DUMMY_PASSWORD_HASH.to_string()).unwrap());
+
+        let real = hashingpassword::hash("correct horse battery".to_string()).unwrap();
+        let parameters = |hash: &str| hash.rsplitn(3, '$').last().unwrap().to_string();
+        assert_eq!(parameters(DUMMY_PASSWORD_HASH), parameters(&real));
+    }
+
+    struct Mocks {
+        user_repository: MockUserRepository,
+        session_repository: MockSessionRepository,
+        audit_event_repository: MockAuditEventRepository,
+    }
+
+    impl Mocks {
+        fn new() -> Self {
+            Self {
+                user_repository: MockUserRepository::new(),
+                session_repository: MockSessionRepository::new(),
+                audit_event_repository: MockAuditEventRepository::new(),
+            }
+        }
+
+        fn use_case(self) -> AuthenticationUseCase<MockUserRepository, MockSessionRepository, MockMfaRepository> {
+            AuthenticationUseCase::new(
+                Arc::new(self.user_repository),
+                Arc::new(self.session_repository),
+                Arc::new(MockMfaRepository::new()),
+                Arc::new(jwt_keys()),
+                Arc::new(AuditLogger::new(Arc::new(self.audit_event_repository))),
+                AuthenticationSettings {
+                    access_token_lifetime: Duration::from_secs(15 * 60),
+                    refresh_token_lifetime: Duration::from_secs(7 * 24 * 60 * 60),
+                    mfa_issuer: "SmartPersona".to_string(),
+                    lockout: policy(),
+                },
+            )
+        }
+    }
+
+    fn jwt_keys() -> JwtKeys {
+        JwtKeys::load(&Jwt {
+            user: JwtSecret {
+                user_secret: "user-secret".to_string(),
+                user_refresh_secret: "user-refresh-secret".to_string(),
+            },
+            admin: JwtAdminSecret {
+                admin_secret: "admin-secret".to_string(),
+                admin_refresh_secret: "admin-refresh-secret".to_string(),
+            },
+            access_token_expiration: Duration::from_secs(15 * 60),
+            refresh_token_expiration: Duration::from_secs(7 * 24 * 60 * 60),
+            issuer: "smartpersona".to_string(),
+            audience: "smartpersona-app".to_string(),
+            admin_audience: "smartpersona-admin".to_string(),
+            signing: JwtSigning {
+                algorithm: "HS256".to_string(),
+                active_key_id: None,
+                private_key_path: None,
+                public_key_paths: HashMap::new(),
+            },
         })
+        .unwrap()
+    }
+
+    fn session(user_id: Uuid, current_refresh_token_id: Uuid) -> SessionEntity {
+        let now = Utc::now().naive_utc();
+        SessionEntity {
+            id: Uuid::new_v4(),
+            user_id,
+            device: None,
+            created_at: now,
+            last_used_at: now,
+            expires_at: now + TimeDelta::days(7),
+            revoked_at: None,
+            current_refresh_token_id,
+        }
     }
-    }
\ No newline at end of file
+
+    fn refresh_token(session: &SessionEntity, token_id: Uuid) -> String {
+        let keys = jwt_keys();
+        let now = Utc::now().timestamp() as usize;
+        let claims = Claims {
+            sub: session.user_id.to_string(),
+            role: Role::PersonaUser,
+            iss: keys.issuer.clone(),
+            aud: keys.audience(TokenKind::UserRefresh).to_string(),
+            exp: session.expires_at.and_utc().timestamp() as usize,
+            iat: now,
+            jti: token_id.to_string(),
+            sid: session.id.to_string(),
+            act: None,
+        };
+        jwt_authentication::generate_token(&keys, TokenKind::UserRefresh, &claims).unwrap()
+    }
+
+    #[tokio::test]
+    async fn reusing_a_rotated_refresh_token_revokes_the_session() {
+        let used_token_id = Uuid::new_v4();
+        let session = session(Uuid::new_v4(), Uuid::new_v4());
+        let session_id = session.id;
+        let token = refresh_token(&session, used_token_id);
+        let mut mocks = Mocks::new();
+        mocks
+            .session_repository
+            .expect_find_by_id()
+            .return_once(move |_| Ok(session));
+        mocks
+            .session_repository
+            .expect_rotate_refresh_token()
+            .withf(move |id, presented, _| *id == session_id && *presented == used_token_id)
+            .times(1)
+            .returning(|_, _, _| Ok(false));
+        mocks
+            .session_repository
+            .expect_revoke()
+            .withf(move |id| *id == session_id)
+            .times(1)
+            .returning(|_| Ok(()));
+
+        let result = mocks.use_case().user_refresh_token(token, &AuditContext::default()).await;
+
+        assert!(matches!(result, Err(AppError::Unauthorized(message)) if message.contains("already been used")));
+    }
+
+    #[tokio::test]
+    async fn rotating_a_refresh_token_issues_a_new_one() {
+        let current_token_id = Uuid::new_v4();
+        let user = user(UserStatus::Active, None);
+        let session = session(user.id, current_token_id);
+        let session_id = session.id;
+        let token = refresh_token(&session, current_token_id);
+        let issued_token_id = Arc::new(std::sync::Mutex::new(None));
+        let mut mocks = Mocks::new();
+        mocks
+            .session_repository
+            .expect_find_by_id()
+            .return_once(move |_| Ok(session));
+        let issued = Arc::clone(&issued_token_id);
+        mocks
+            .session_repository
+            .expect_rotate_refresh_token()
+            .withf(move |id, presented, new| *id == session_id && *presented == current_token_id && new != presented)
+            .times(1)
+            .returning(move |_, _, new| {
+                *issued.lock().unwrap() = Some(new);
+                Ok(true)
+            });
+        mocks.session_repository.expect_revoke().never();
+        mocks.user_repository.expect_find_by_id().return_once(move |_| Ok(user));
+        mocks
+            .audit_event_repository
+            .expect_create()
+            .withf(|entity| entity.action == AuditAction::TokenRefreshed.as_str())
+            .times(1)
+            .returning(|_| Err(AppError::not_found("not stored in tests")));
+
+        let passport = mocks
+            .use_case()
+            .user_refresh_token(token, &AuditContext::default())
+            .await
+            .unwrap();
+
+        let claims = jwt_authentication::verify_token(&jwt_keys(), TokenKind::UserRefresh, passport.refresh_token).unwrap();
+        assert_eq!(Some(Uuid::parse_str(&claims.jti).unwrap()), *issued_token_id.lock().unwrap());
+        assert_ne!(claims.jti, current_token_id.to_string());
+        assert_eq!(claims.sid, session_id.to_string());
+    }
+
+    #[tokio::test]
+    async fn unknown_and_locked_accounts_are_refused_alike() {
+        let locked = user(UserStatus::Active, Some(Utc::now().naive_utc() + TimeDelta::minutes(5)));
+        let mut mocks = Mocks::new();
+        mocks
+            .user_repository
+            .expect_find_by_username()
+            .returning(move |username| match username.as_str() {
+                "alice" => Ok(locked.clone()),
+                _ => Err(AppError::not_found("Resource not found")),
+            });
+        // Neither the account's name nor the unknown one is written to the audit log.
+        mocks
+            .audit_event_repository
+            .expect_create()
+            .withf(|entity| !entity.changes.to_string().contains("alice") && !entity.changes.to_string().contains("mallory"))
+            .times(2)
+            .returning(|_| Err(AppError::not_found("not stored in tests")));
+        let use_case = mocks.use_case();
+
+        for username in ["alice", "mallory"] {
+            let login_model = LoginModel {
+                username: username.to_string(),
+                password: "correct horse battery".to_string(),
+            };
+            let result = use_case.user_login(login_model, None, &AuditContext::default()).await;
+            assert!(matches!(result, Err(AppError::Unauthorized(message)) if message == "Invalid username or password"));
+        }
+    }
+}
@@ -0,0 +1,634 @@
+use std::sync::Arc;
+
+use chrono::{Duration, Utc};
+use tracing::warn;
+use uuid::Uuid;
+
+use crate::domain::{
+    error::{AppError, AppResult},
+    entities::{
+        ai_analysis::AIAnalysisRequest,
+        generation_job::{GenerationJobEntity, InsertGenerationJobEntity, JobStatus, PersonalityAnalysisPayload},
+    },
+    repo::{
+        ai_service::AIServiceRepository, generation_job::GenerationJobRepository,
+        prompt_template::PromptTemplateRepository, social_connection::SocialConnectionRepository,
+    },
+    usecase::ai_analysis::AIAnalysisUseCase,
+    value_object::generation_job::GenerationJobModel,
+};
+
+const MAX_ATTEMPTS: i32 = 3;
+
+pub struct GenerationJobUseCase<T1, T2, T3, T4>
+where
+    T1: GenerationJobRepository + Send + Sync,
+    T2: AIServiceRepository + Send + Sync,
+    T3: PromptTemplateRepository + Send + Sync,
+    T4: SocialConnectionRepository + Send + Sync,
+{
+    generation_job_repository: Arc<T1>,
+    ai_analysis_use_case: Arc<AIAnalysisUseCase<T2, T3, T4>>,
+}
+
+impl<T1, T2, T3, T4> GenerationJobUseCase<T1, T2, T3, T4>
+where
+    T1: GenerationJobRepository + Send + Sync,
+    T2: AIServiceRepository + Send + Sync,
+    T3: PromptTemplateRepository + Send + Sync,
+    T4: SocialConnectionRepository + Send + Sync,
+{
+    pub fn new(generation_job_repository: Arc<T1>, ai_analysis_use_case: Arc<AIAnalysisUseCase<T2, T3, T4>>) -> Self {
+        Self {
+            generation_job_repository,
+            ai_analysis_use_case,
+        }
+    }
+
+    // With `posts` the prompt is rendered at submission so a bad template is reported to
+    // the caller straight away, and the job pins the template version that produced its
+    // result. Without them the requester's linked social accounts are imported on the
+    // worker, since the platforms are too slow to call on the request path.
+    pub async fn submit_personality_analysis(
+        &self,
+        requester_id: Uuid,
+        posts: Option<Vec<String>>,
+        language: Option<String>,
+        tag_count: Option<u32>,
+    ) -> AppResult<GenerationJobModel> {
+        let (payload, prompt) = match posts {
+            Some(posts) if !posts.is_empty() => {
+                let prompt = self
+                    .ai_analysis_use_case
+                    .render_personality_prompt(posts.clone(), language, tag_count)
+                    .await?;
+                let request = AIAnalysisRequest {
+                    user_id: requester_id.to_string(),
+                    posts,
+                    prompt: prompt.text.clone(),
+                };
+                (PersonalityAnalysisPayload::Rendered(request), Some(prompt))
+            }
+            _ => (
+                PersonalityAnalysisPayload::ImportPosts {
+                    requester_id,
+                    language,
+                    tag_count,
+                },
+                None,
+            ),
+        };
+
+        let job = self
+            .generation_job_repository
+            .create(InsertGenerationJobEntity {
+                requester_id,
+                status: JobStatus::Pending,
+                prompt_template_version_id: prompt.as_ref().map(|prompt| prompt.template_version_id),
+                prompt: prompt.map(|prompt| prompt.text),
+                payload: serde_json::to_value(&payload)?,
+                created_at: Utc::now().naive_utc(),
+                updated_at: Utc::now().naive_utc(),
+            })
+            .await?;
+        Ok(job.into())
+    }
+
+    // Jobs requested by someone else are reported as missing so ids cannot be probed.
+    pub async fn get_own(&self, requester_id: Uuid, job_id: Uuid) -> AppResult<GenerationJobModel> {
+        let job = self
+            .generation_job_repository
+            .find_by_id(job_id)
+            .await
+            .map_err(|e| match e {
+                AppError::NotFound(_) => AppError::not_found("Job not found"),
+                e => e,
+            })?;
+
+        if job.requester_id != requester_id {
+            return Err(AppError::not_found("Job not found"));
+        }
+
+        Ok(job.into())
+    }
+
+    /// Claims and runs one pending job. Returns `false` when the queue is empty.
+    /// While the job runs its lease is extended every `heartbeat_interval`, so a slow
+    /// AI call is not mistaken for a crashed worker and handed to another one.
+    pub async fn process_next(&self, heartbeat_interval: std::time::Duration) -> AppResult<bool> {
+        let Some(job) = self.generation_job_repository.claim_next().await? else {
+            return Ok(false);
+        };
+
+        if job.attempts > MAX_ATTEMPTS {
+            let held = self
+                .generation_job_repository
+                .mark_failed(job.id, job.attempts, "Exceeded maximum attempts".to_string())
+                .await?;
+            RunOutcome::from_held(held).report(&job);
+            return Ok(true);
+        }
+
+        // A payload that cannot be read fails the same way on every attempt, so it is not retried.
+        let payload = match serde_json::from_value::<PersonalityAnalysisPayload>(job.payload.clone()) {
+            Ok(payload) => payload,
+            Err(e) => {
+                let held = self
+                    .generation_job_repository
+                    .mark_failed(job.id, job.attempts, format!("Job payload is invalid: {}", e))
+                    .await?;
+                RunOutcome::from_held(held).report(&job);
+                return Ok(true);
+            }
+        };
+
+        let outcome = match self.run_with_heartbeat(&job, payload, heartbeat_interval).await {
+            Ok(outcome) => outcome,
+            Err(e) if job.attempts < MAX_ATTEMPTS => RunOutcome::from_held(
+                self.generation_job_repository
+                    .requeue(job.id, job.attempts, e.to_string())
+                    .await?,
+            ),
+            Err(e) => RunOutcome::from_held(
+                self.generation_job_repository
+                    .mark_failed(job.id, job.attempts, e.to_string())
+                    .await?,
+            ),
+        };
+        outcome.report(&job);
+
+        Ok(true)
+    }
+
+    pub async fn release_stale(&self, lease_timeout: Duration) -> AppResult<usize> {
+        self.generation_job_repository
+            .release_stale((Utc::now() - lease_timeout).naive_utc())
+            .await
+    }
+
+    // Stops waiting for the run as soon as a heartbeat finds the lease gone; dropping the
+    // future abandons the AI call instead of spending it on a result nobody can store.
+    async fn run_with_heartbeat(
+        &self,
+        job: &GenerationJobEntity,
+        payload: PersonalityAnalysisPayload,
+        heartbeat_interval: std::time::Duration,
+    ) -> AppResult<RunOutcome> {
+        let run = self.run(job, payload);
+        tokio::pin!(run);
+
+        let mut heartbeat = tokio::time::interval(heartbeat_interval);
+        heartbeat.tick().await;
+        loop {
+            tokio::select! {
+                outcome = &mut run => return outcome,
+                _ = heartbeat.tick() => {
+                    match self.generation_job_repository.extend_lease(job.id, job.attempts).await {
+                        Ok(true) => {}
+                        Ok(false) => return Ok(RunOutcome::LeaseLost),
+                        Err(e) => warn!("failed to extend lease of generation job {}: {}", job.id, e),
+                    }
+                }
+            }
+        }
+    }
+
+    async fn run(&self, job: &GenerationJobEntity, payload: PersonalityAnalysisPayload) -> AppResult<RunOutcome> {
+        let request = match payload {
+            PersonalityAnalysisPayload::Rendered(request) => request,
+            PersonalityAnalysisPayload::ImportPosts {
+                requester_id,
+                language,
+                tag_count,
+            } => {
+                let Some(request) = self
+                    .prepare_imported_request(job, requester_id, language, tag_count)
+                    .await?
+                else {
+                    return Ok(RunOutcome::LeaseLost);
+                };
+                request
+            }
+        };
+        let response = self.ai_analysis_use_case.analyze(request).await?;
+
+        let held = self
+            .generation_job_repository
+            .mark_completed(job.id, job.attempts, serde_json::to_value(response)?)
+            .await?;
+        Ok(RunOutcome::from_held(held))
+    }
+
+    // Returns `None` when the job was taken from this worker before the prompt could be stored.
+    async fn prepare_imported_request(
+        &self,
+        job: &GenerationJobEntity,
+        requester_id: Uuid,
+        language: Option<String>,
+        tag_count: Option<u32>,
+    ) -> AppResult<Option<AIAnalysisRequest>> {
+        let posts = self.ai_analysis_use_case.import_user_posts(requester_id).await?;
+        let prompt = self
+            .ai_analysis_use_case
+            .render_personality_prompt(posts.clone(), language, tag_count)
+            .await?;
+        let request = AIAnalysisRequest {
+            user_id: requester_id.to_string(),
+            posts,
+            prompt: prompt.text.clone(),
+        };
+
+        let held = self
+            .generation_job_repository
+            .record_prompt(
+                job.id,
+                job.attempts,
+                prompt.text,
+                prompt.template_version_id,
+                serde_json::to_value(PersonalityAnalysisPayload::Rendered(request.clone()))?,
+            )
+            .await?;
+        Ok(held.then_some(request))
+    }
+}
+
+// What became of a claimed job on this worker.
+enum RunOutcome {
+    Finished,
+    // The job was released as stale or claimed again while it ran, so this worker's
+    // result was dropped and the job is left to whoever holds it now.
+    LeaseLost,
+}
+
+impl RunOutcome {
+    fn from_held(held: bool) -> Self {
+        if held { Self::Finished } else { Self::LeaseLost }
+    }
+
+    fn report(&self, job: &GenerationJobEntity) {
+        if let Self::LeaseLost = self {
+            warn!("lost the lease on generation job {} (attempt {}), discarding this run", job.id, job.attempts);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        sync::{
+            Mutex,
+            atomic::{AtomicUsize, Ordering},
+        },
+        time::Duration as StdDuration,
+    };
+
+    use axum::async_trait;
+
+    use crate::domain::{
+        entities::{
+            ai_analysis::{AIAnalysisResponse, ChatRequest, ChatResponse},
+            prompt_template::{PromptTemplateEntity, PromptTemplateVersionEntity},
+        },
+        repo::{
+            generation_job::MockGenerationJobRepository, prompt_template::MockPromptTemplateRepository,
+            social_connection::MockSocialConnectionRepository,
+        },
+    };
+
+    use super::*;
+
+    const HEARTBEAT: StdDuration = StdDuration::from_millis(10);
+
+    // Answers after `delay`, so heartbeats can fire while the call is in flight, and keeps
+    // the prompts it was sent.
+    struct FakeAIService {
+        delay: StdDuration,
+        fails: bool,
+        calls: AtomicUsize,
+        prompts: Mutex<Vec<String>>,
+    }
+
+    impl FakeAIService {
+        fn new(delay: StdDuration, fails: bool) -> Arc<Self> {
+            Arc::new(Self {
+                delay,
+                fails,
+                calls: AtomicUsize::new(0),
+                prompts: Mutex::new(Vec::new()),
+            })
+        }
+    }
+
+    #[async_trait]
+    impl AIServiceRepository for FakeAIService {
+        async fn analyze_personality(&self, request: AIAnalysisRequest) -> AppResult<AIAnalysisResponse> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            self.prompts.lock().unwrap().push(request.prompt);
+            tokio::time::sleep(self.delay).await;
+            if self.fails {
+                return Err(AppError::upstream("AI service is unavailable"));
+            }
+            Ok(AIAnalysisResponse {
+                personality_tags: vec!["curious".to_string()],
+                suggested_theme: "ocean".to_string(),
+            })
+        }
+
+        async fn chat(&self, _request: ChatRequest) -> AppResult<ChatResponse> {
+            unimplemented!()
+        }
+    }
+
+    type TestUseCase = GenerationJobUseCase<
+        MockGenerationJobRepository,
+        FakeAIService,
+        MockPromptTemplateRepository,
+        MockSocialConnectionRepository,
+    >;
+
+    fn use_case(generation_job_repository: MockGenerationJobRepository, ai_service: Arc<FakeAIService>) -> TestUseCase {
+        use_case_with_templates(generation_job_repository, ai_service, MockPromptTemplateRepository::new())
+    }
+
+    fn use_case_with_templates(
+        generation_job_repository: MockGenerationJobRepository,
+        ai_service: Arc<FakeAIService>,
+        prompt_template_repository: MockPromptTemplateRepository,
+    ) -> TestUseCase {
+        GenerationJobUseCase::new(
+            Arc::new(generation_job_repository),
+            Arc::new(AIAnalysisUseCase::new(
+                ai_service,
+                Arc::new(prompt_template_repository),
+                Arc::new(MockSocialConnectionRepository::new()),
+                Vec::new(),
+                10,
+            )),
+        )
+    }
+
+    fn job(attempts: i32, payload: serde_json::Value) -> GenerationJobEntity {
+        let now = Utc::now().naive_utc();
+        GenerationJobEntity {
+            id: Uuid::new_v4(),
+            requester_id: Uuid::new_v4(),
+            status: JobStatus::Running,
+            prompt: Some("Describe this person".to_string()),
+            prompt_template_version_id: Some(Uuid::new_v4()),
+            result: None,
+            payload,
+            error: None,
+            attempts,
+            created_at: now,
+            started_at: Some(now),
+            completed_at: None,
+            updated_at: now,
+        }
+    }
+
+    fn rendered() -> serde_json::Value {
+        serde_json::to_value(PersonalityAnalysisPayload::Rendered(AIAnalysisRequest {
+            user_id: Uuid::new_v4().to_string(),
+            posts: vec!["Went diving again".to_string()],
+            prompt: "Describe this person".to_string(),
+        }))
+        .unwrap()
+    }
+
+    fn claiming(job: GenerationJobEntity) -> MockGenerationJobRepository {
+        let mut generation_job_repository = MockGenerationJobRepository::new();
+        generation_job_repository
+            .expect_claim_next()
+            .times(1)
+            .return_once(move || Ok(Some(job)));
+        generation_job_repository
+    }
+
+    #[tokio::test]
+    async fn reports_an_empty_queue() {
+        let mut generation_job_repository = MockGenerationJobRepository::new();
+        generation_job_repository.expect_claim_next().returning(|| Ok(None));
+
+        let processed = use_case(generation_job_repository, FakeAIService::new(StdDuration::ZERO, false))
+            .process_next(HEARTBEAT)
+            .await
+            .unwrap();
+
+        assert!(!processed);
+    }
+
+    #[tokio::test]
+    async fn completes_a_job_under_its_claim() {
+        let job = job(1, rendered());
+        let job_id = job.id;
+        let mut generation_job_repository = claiming(job);
+        generation_job_repository
+            .expect_mark_completed()
+            .withf(move |id, attempt, result| *id == job_id && *attempt == 1 && result["suggested_theme"] == "ocean")
+            .times(1)
+            .returning(|_, _, _| Ok(true));
+
+        let processed = use_case(generation_job_repository, FakeAIService::new(StdDuration::ZERO, false))
+            .process_next(HEARTBEAT)
+            .await
+            .unwrap();
+
+        assert!(processed);
+    }
+
+    #[tokio::test]
+    async fn requeues_a_failed_attempt() {
+        let job = job(1, rendered());
+        let job_id = job.id;
+        let mut generation_job_repository = claiming(job);
+        generation_job_repository
+            .expect_requeue()
+            .withf(move |id, attempt, error| *id == job_id && *attempt == 1 && error == "AI service is unavailable")
+            .times(1)
+            .returning(|_, _, _| Ok(true));
+
+        use_case(generation_job_repository, FakeAIService::new(StdDuration::ZERO, true))
+            .process_next(HEARTBEAT)
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn fails_the_job_when_its_last_attempt_fails() {
+        let job = job(MAX_ATTEMPTS, rendered());
+        let mut generation_job_repository = claiming(job);
+        generation_job_repository
+            .expect_mark_failed()
+            .withf(|_, attempt, error| *attempt == MAX_ATTEMPTS && error == "AI service is unavailable")
+            .times(1)
+            .returning(|_, _, _| Ok(true));
+
+        use_case(generation_job_repository, FakeAIService::new(StdDuration::ZERO, true))
+            .process_next(HEARTBEAT)
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn fails_a_job_past_the_attempt_limit_without_running_it() {
+        let job = job(MAX_ATTEMPTS + 1, rendered());
+        let mut generation_job_repository = claiming(job);
+        generation_job_repository
+            .expect_mark_failed()
+            .withf(|_, _, error| error == "Exceeded maximum attempts")
+            .times(1)
+            .returning(|_, _, _| Ok(true));
+        let ai_service = FakeAIService::new(StdDuration::ZERO, false);
+
+        use_case(generation_job_repository, Arc::clone(&ai_service))
+            .process_next(HEARTBEAT)
+            .await
+            .unwrap();
+
+        assert_eq!(ai_service.calls.load(Ordering::SeqCst), 0);
+    }
+
+    #[tokio::test]
+    async fn fails_a_job_with_an_unreadable_payload_without_retrying() {
+        let job = job(1, serde_json::json!({}));
+        let mut generation_job_repository = claiming(job);
+        generation_job_repository
+            .expect_mark_failed()
+            .withf(|_, attempt, error| *attempt == 1 && error.starts_with("Job payload is invalid"))
+            .times(1)
+            .returning(|_, _, _| Ok(true));
+        let ai_service = FakeAIService::new(StdDuration::ZERO, false);
+
+        use_case(generation_job_repository, Arc::clone(&ai_service))
+            .process_next(HEARTBEAT)
+            .await
+            .unwrap();
+
+        assert_eq!(ai_service.calls.load(Ordering::SeqCst), 0);
+    }
+
+    #[tokio::test]
+    async fn extends_the_lease_while_the_ai_call_runs() {
+        let job = job(1, rendered());
+        let job_id = job.id;
+        let mut generation_job_repository = claiming(job);
+        generation_job_repository
+            .expect_extend_lease()
+            .withf(move |id, attempt| *id == job_id && *attempt == 1)
+            .times(1..)
+            .returning(|_, _| Ok(true));
+        generation_job_repository
+            .expect_mark_completed()
+            .times(1)
+            .returning(|_, _, _| Ok(true));
+
+        use_case(generation_job_repository, FakeAIService::new(HEARTBEAT * 5, false))
+            .process_next(HEARTBEAT)
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn abandons_the_run_once_the_lease_is_lost() {
+        let job = job(1, rendered());
+        let mut generation_job_repository = claiming(job);
+        generation_job_repository
+            .expect_extend_lease()
+            .times(1)
+            .returning(|_, _| Ok(false));
+        // No mark_completed, requeue or mark_failed: the job now belongs to another worker.
+
+        let processed = use_case(generation_job_repository, FakeAIService::new(StdDuration::from_secs(60), false))
+            .process_next(HEARTBEAT)
+            .await
+            .unwrap();
+
+        assert!(processed);
+    }
+
+    #[tokio::test]
+    async fn leaves_a_failed_job_alone_once_the_lease_is_lost() {
+        let job = job(1, rendered());
+        let mut generation_job_repository = claiming(job);
+        generation_job_repository
+            .expect_requeue()
+            .times(1)
+            .returning(|_, _, _| Ok(false));
+
+        let processed = use_case(generation_job_repository, FakeAIService::new(StdDuration::ZERO, true))
+            .process_next(HEARTBEAT)
+            .await
+            .unwrap();
+
+        assert!(processed);
+    }
+
+    #[tokio::test]
+    async fn runs_a_job_with_its_pinned_template_version() {
+        let now = Utc::now().naive_utc();
+        let template_id = Uuid::new_v4();
+        let versions: Vec<PromptTemplateVersionEntity> = (1..=2)
+            .map(|version| PromptTemplateVersionEntity {
+                id: Uuid::new_v4(),
+                template_id,
+                version,
+                template_text: format!("v{}: {{{{posts}}}}", version),
+                created_at: now,
+            })
+            .collect();
+        let pinned_version_id = versions[0].id;
+        let newer_version_id = versions[1].id;
+
+        let active_version_id = Arc::new(Mutex::new(pinned_version_id));
+        let mut prompt_template_repository = MockPromptTemplateRepository::new();
+        let active = Arc::clone(&active_version_id);
+        prompt_template_repository.expect_find_by_name().returning(move |name| {
+            Ok(PromptTemplateEntity {
+                id: template_id,
+                name,
+                active_version_id: Some(*active.lock().unwrap()),
+                created_at: now,
+                updated_at: now,
+            })
+        });
+        prompt_template_repository
+            .expect_find_version_by_id()
+            .returning(move |version_id| Ok(versions.iter().find(|version| version.id == version_id).unwrap().clone()));
+
+        let submitted = Arc::new(Mutex::new(None::<GenerationJobEntity>));
+        let mut generation_job_repository = MockGenerationJobRepository::new();
+        let created = Arc::clone(&submitted);
+        generation_job_repository.expect_create().times(1).returning(move |entity| {
+            let mut job = job(0, entity.payload);
+            job.status = entity.status;
+            job.prompt = entity.prompt;
+            job.prompt_template_version_id = entity.prompt_template_version_id;
+            *created.lock().unwrap() = Some(job.clone());
+            Ok(job)
+        });
+        let claimed = Arc::clone(&submitted);
+        generation_job_repository.expect_claim_next().times(1).returning(move || {
+            let mut job = claimed.lock().unwrap().clone().unwrap();
+            job.status = JobStatus::Running;
+            job.attempts = 1;
+            Ok(Some(job))
+        });
+        generation_job_repository
+            .expect_mark_completed()
+            .times(1)
+            .returning(|_, _, _| Ok(true));
+        let ai_service = FakeAIService::new(StdDuration::ZERO, false);
+        let use_case = use_case_with_templates(generation_job_repository, Arc::clone(&ai_service), prompt_template_repository);
+
+        let model = use_case
+            .submit_personality_analysis(Uuid::new_v4(), Some(vec!["Went diving again".to_string()]), None, None)
+            .await
+            .unwrap();
+        // A newer version goes live before the worker picks the job up.
+        *active_version_id.lock().unwrap() = newer_version_id;
+        use_case.process_next(HEARTBEAT).await.unwrap();
+
+        assert_eq!(model.prompt_template_version_id, Some(pinned_version_id));
+        assert_eq!(*ai_service.prompts.lock().unwrap(), vec!["v1: Went diving again".to_string()]);
+    }
+}
@@ -1,3 +1,12 @@
 pub mod user;
 pub mod authentication;
-pub mod ai_analysis;
\ No newline at end of file
+pub mod ai_analysis;
+pub mod profile;
+pub mod generation_job;
+pub mod prompt_template;
+pub mod social_connection;
+pub mod social_connection_refresh;
+pub mod admin_user;
+pub mod password_reset;
+pub mod privacy;
+pub mod audit;
\ No newline at end of file
@@ -0,0 +1,379 @@
+use std::{sync::Arc, time::Duration};
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use chrono::{TimeDelta, Utc};
+use rand::RngCore;
+use serde_json::json;
+use sha2::{Digest, Sha256};
+use tracing::{info, warn};
+
+use crate::{
+    domain::{
+        entities::{password_reset::InsertPasswordResetTokenEntity, user::UserEntity},
+        error::{AppError, AppResult},
+        repo::{
+            mailer::{EmailMessage, SharedMailer},
+            password_reset::PasswordResetRepository,
+            user::UserRepository,
+        },
+    },
+    infrastructure::{
+        hashingpassword,
+        password_policy::PasswordValidator,
+        jwt_authentication::authentication_model::{ForgotPasswordModel, ResetPasswordModel},
+    },
+};
+
+const RESET_TOKEN_BYTES: usize = 32;
+
+pub struct PasswordResetUseCase<T1, T2>
+where
+    T1: UserRepository + Send + Sync,
+    T2: PasswordResetRepository + Send + Sync,
+{
+    user_repository: Arc<T1>,
+    password_reset_repository: Arc<T2>,
+    mailer: SharedMailer,
+    password_validator: Arc<PasswordValidator>,
+    // Frontend page that receives `?token=...` and posts it back to /reset-password.
+    reset_url: String,
+    token_lifetime: Duration,
+}
+
+impl<T1, T2> PasswordResetUseCase<T1, T2>
+where
+    T1: UserRepository + Send + Sync,
+    T2: PasswordResetRepository + Send + Sync,
+{
+    pub fn new(
+        user_repository: Arc<T1>,
+        password_reset_repository: Arc<T2>,
+        mailer: SharedMailer,
+        password_validator: Arc<PasswordValidator>,
+        reset_url: String,
+        token_lifetime: Duration,
+    ) -> Self {
+        Self {
+            user_repository,
+            password_reset_repository,
+            mailer,
+            password_validator,
+            reset_url,
+            token_lifetime,
+        }
+    }
+
+    // Always succeeds for a well-formed request so the response does not reveal which emails
+    // have an account. The email is sent in the background for the same reason.
+    pub async fn forgot_password(&self, forgot_password_model: ForgotPasswordModel) -> AppResult<()> {
+        let email = forgot_password_model.email.trim().to_lowercase();
+        if email.is_empty() {
+            return Err(AppError::validation("Email is required"));
+        }
+
+        let user = match self.user_repository.find_by_email(email.clone()).await {
+            Ok(user) => user,
+            Err(AppError::NotFound(_)) => {
+                info!("password reset requested for an unknown email");
+                return Ok(());
+            }
+            Err(e) => return Err(e),
+        };
+
+        let token = self.issue_token(&user).await?;
+        let message = EmailMessage {
+            to: email,
+            subject: "Reset your password".to_string(),
+            body: format!(
+                "Hi {},\n\nUse the link below to choose a new password. It expires in {} and can only be used once.\n\n{}?token={}\n\nIf you did not ask for this, you can ignore this email.",
+                user.first_name,
+                humantime::format_duration(self.token_lifetime),
+                self.reset_url,
+                token,
+            ),
+        };
+
+        let mailer = Arc::clone(&self.mailer);
+        tokio::spawn(async move {
+            if let Err(e) = mailer.send(message).await {
+                warn!("failed to send password reset email: {}", e);
+            }
+        });
+
+        Ok(())
+    }
+
+    // Sets the new password and signs the user out everywhere, since whoever held the old
+    // password may still have a session.
+    pub async fn reset_password(&self, reset_password_model: ResetPasswordModel) -> AppResult<()> {
+        let password_problems = self.password_validator.check(&reset_password_model.new_password, None);
+        if !password_problems.is_empty() {
+            return Err(AppError::invalid_fields(
+                "Invalid new password",
+                json!({ "new_password": password_problems }),
+            ));
+        }
+
+        let password_hash = hashingpassword::hash(reset_password_model.new_password)?;
+        match self
+            .password_reset_repository
+            .reset_password(hash_token(&reset_password_model.token), password_hash)
+            .await
+        {
+            Ok(_) => Ok(()),
+            Err(AppError::NotFound(_)) => Err(AppError::validation("Invalid or expired reset token")),
+            Err(e) => Err(e),
+        }
+    }
+
+    // Stores only the hash; the returned token goes into the email.
+    async fn issue_token(&self, user: &UserEntity) -> AppResult<String> {
+        let mut bytes = [0u8; RESET_TOKEN_BYTES];
+        rand::thread_rng().fill_bytes(&mut bytes);
+        let token = URL_SAFE_NO_PAD.encode(bytes);
+
+        let now = Utc::now().naive_utc();
+        let expires_at = TimeDelta::from_std(self.token_lifetime)
+            .ok()
+            .and_then(|lifetime| now.checked_add_signed(lifetime))
+            .ok_or_else(|| anyhow::anyhow!("Reset token lifetime {:?} is out of range", self.token_lifetime))?;
+
+        self.password_reset_repository
+            .create(InsertPasswordResetTokenEntity {
+                user_id: user.id,
+                token_hash: hash_token(&token),
+                expires_at,
+                created_at: now,
+            })
+            .await?;
+
+        Ok(token)
+    }
+}
+
+fn hash_token(token: &str) -> String {
+    format!("{:x}", Sha256::digest(token.trim().as_bytes()))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        path::{Path, PathBuf},
+        sync::Mutex,
+    };
+
+    use chrono::NaiveDateTime;
+    use uuid::Uuid;
+
+    use super::*;
+    use crate::{
+        config::config_model::PasswordPolicy,
+        domain::{
+            entities::{
+                password_reset::PasswordResetTokenEntity,
+                user::{Role, UserStatus},
+            },
+            repo::{password_reset::MockPasswordResetRepository, user::MockUserRepository},
+        },
+        infrastructure::mailer::file::FileMailer,
+    };
+
+    const RESET_URL: &str = "http://localhost:3000/reset-password";
+
+    fn validator() -> Arc<PasswordValidator> {
+        Arc::new(
+            PasswordValidator::new(&PasswordPolicy {
+                min_length: 12,
+                max_length: 128,
+                require_uppercase: false,
+                require_lowercase: false,
+                require_digit: false,
+                require_symbol: false,
+                breached_list_path: None,
+            })
+            .unwrap(),
+        )
+    }
+
+    fn user(email: &str) -> UserEntity {
+        let now = NaiveDateTime::default();
+        UserEntity {
+            id: Uuid::new_v4(),
+            username: "alice".to_string(),
+            password_hash: String::new(),
+            display_name: None,
+            role: Role::PersonaUser,
+            status: UserStatus::Active,
+            created_at: now,
+            updated_at: now,
+            first_name: "Alice".to_string(),
+            last_name: "Example".to_string(),
+            failed_login_attempts: 0,
+            locked_until: None,
+            email: Some(email.to_string()),
+            erasure_scheduled_at: None,
+        }
+    }
+
+    fn mail_dir() -> PathBuf {
+        std::env::temp_dir().join(format!("password-reset-test-{}", Uuid::new_v4()))
+    }
+
+    fn use_case(
+        user_repository: MockUserRepository,
+        password_reset_repository: MockPasswordResetRepository,
+        mail_dir: &Path,
+    ) -> PasswordResetUseCase<MockUserRepository, MockPasswordResetRepository> {
+        PasswordResetUseCase::new(
+            Arc::new(user_repository),
+            Arc::new(password_reset_repository),
+            Arc::new(FileMailer::new(Some(mail_dir.to_path_buf()))),
+            validator(),
+            RESET_URL.to_string(),
+            Duration::from_secs(30 * 60),
+        )
+    }
+
+    // The email goes out from a spawned task, so wait for it to land.
+    async fn read_single_email(dir: &Path) -> String {
+        for _ in 0..100 {
+            let email = std::fs::read_dir(dir)
+                .ok()
+                .and_then(|mut entries| entries.next())
+                .and_then(|entry| std::fs::read_to_string(entry.ok()?.path()).ok());
+            // The file can be seen before the write finishes.
+            if let Some(email) = email.filter(|email| email.ends_with('\n')) {
+                return email;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        panic!("no email was written to {}", dir.display());
+    }
+
+    #[tokio::test]
+    async fn emailed_token_resets_the_password() {
+        let dir = mail_dir();
+        let user = user("alice@example.com");
+        let user_id = user.id;
+        let stored_hash = Arc::new(Mutex::new(None::<String>));
+        let new_password_hash = Arc::new(Mutex::new(None::<String>));
+
+        let mut user_repository = MockUserRepository::new();
+        user_repository
+            .expect_find_by_email()
+            .withf(|email| email == "alice@example.com")
+            .returning(move |_| Ok(user.clone()));
+
+        let mut password_reset_repository = MockPasswordResetRepository::new();
+        let created = Arc::clone(&stored_hash);
+        password_reset_repository
+            .expect_create()
+            .times(1)
+            .returning(move |entity| {
+                *created.lock().unwrap() = Some(entity.token_hash.clone());
+                Ok(PasswordResetTokenEntity {
+                    id: Uuid::new_v4(),
+                    user_id: entity.user_id,
+                    token_hash: entity.token_hash,
+                    expires_at: entity.expires_at,
+                    used_at: None,
+                    created_at: entity.created_at,
+                })
+            });
+        let expected = Arc::clone(&stored_hash);
+        let updated = Arc::clone(&new_password_hash);
+        password_reset_repository
+            .expect_reset_password()
+            .times(1)
+            .withf(move |token_hash, _| Some(token_hash) == expected.lock().unwrap().as_ref())
+            .returning(move |_, password_hash| {
+                *updated.lock().unwrap() = Some(password_hash);
+                Ok(user_id)
+            });
+
+        let use_case = use_case(user_repository, password_reset_repository, &dir);
+        use_case
+            .forgot_password(ForgotPasswordModel { email: " Alice@Example.com ".to_string() })
+            .await
+            .unwrap();
+
+        let email = read_single_email(&dir).await;
+        assert!(email.starts_with("To: alice@example.com\nSubject: Reset your password\n"));
+        let token = email
+            .lines()
+            .find_map(|line| line.strip_prefix(&format!("{}?token=", RESET_URL)))
+            .expect("reset link in the email")
+            .to_string();
+        assert_eq!(stored_hash.lock().unwrap().as_deref(), Some(hash_token(&token).as_str()));
+
+        use_case
+            .reset_password(ResetPasswordModel {
+                token,
+                new_password: "correct horse battery".to_string(),
+            })
+            .await
+            .unwrap();
+
+        let password_hash = new_password_hash.lock().unwrap().clone().unwrap();
+        assert!(hashingpassword::verify("correct horse battery".to_string(), password_hash).unwrap());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn unknown_email_sends_nothing() {
+        let dir = mail_dir();
+        let mut user_repository = MockUserRepository::new();
+        user_repository
+            .expect_find_by_email()
+            .returning(|_| Err(AppError::not_found("User not found")));
+        let mut password_reset_repository = MockPasswordResetRepository::new();
+        password_reset_repository.expect_create().never();
+
+        use_case(user_repository, password_reset_repository, &dir)
+            .forgot_password(ForgotPasswordModel { email: "nobody@example.com".to_string() })
+            .await
+            .unwrap();
+
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert!(!dir.exists());
+    }
+
+    #[tokio::test]
+    async fn unknown_or_used_token_is_a_validation_error() {
+        let mut password_reset_repository = MockPasswordResetRepository::new();
+        password_reset_repository
+            .expect_reset_password()
+            .returning(|_, _| Err(AppError::not_found("Token not found")));
+
+        let result = use_case(MockUserRepository::new(), password_reset_repository, &mail_dir())
+            .reset_password(ResetPasswordModel {
+                token: "not-a-real-token".to_string(),
+                new_password: "correct horse battery".to_string(),
+            })
+            .await;
+
+        assert!(matches!(result, Err(AppError::Validation { .. })));
+    }
+
+    #[tokio::test]
+    async fn weak_password_is_rejected_before_the_token_is_used() {
+        let mut password_reset_repository = MockPasswordResetRepository::new();
+        password_reset_repository.expect_reset_password().never();
+
+        let result = use_case(MockUserRepository::new(), password_reset_repository, &mail_dir())
+            .reset_password(ResetPasswordModel {
+                token: "some-token".to_string(),
+                new_password: "short".to_string(),
+            })
+            .await;
+
+        assert!(matches!(result, Err(AppError::Validation { .. })));
+    }
+
+    #[test]
+    fn token_hash_ignores_surrounding_whitespace() {
+        assert_eq!(hash_token(" abc \n"), hash_token("abc"));
+        assert_ne!(hash_token("abc"), hash_token("abd"));
+        assert_eq!(hash_token("abc").len(), 64);
+    }
+}
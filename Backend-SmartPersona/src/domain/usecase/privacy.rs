@@ -0,0 +1,421 @@
+use std::{sync::Arc, time::Duration};
+
+use chrono::{NaiveDateTime, TimeDelta, Utc};
+use serde_json::json;
+use tracing::{error, info, warn};
+use uuid::Uuid;
+
+use crate::{
+    domain::{
+        entities::{
+            data_export::{DataExportEntity, InsertDataExportEntity},
+            generation_job::JobStatus,
+        },
+        error::{AppError, AppResult},
+        repo::{
+            data_export::DataExportRepository, personal_data::PersonalDataRepository, session::SessionRepository,
+            user::UserRepository,
+        },
+        service::audit_logger::{AuditAction, AuditContext, AuditEvent, AuditLogger},
+        value_object::personal_data::{DataExportModel, ErasureModel, PersonalDataExportModel, RequestErasureModel},
+    },
+    infrastructure::hashingpassword,
+};
+
+pub struct PrivacySettings {
+    pub export_retention: Duration,
+    // An export still running after this long is assumed abandoned by a crashed worker.
+    pub export_lease: Duration,
+    pub erasure_grace_period: Duration,
+}
+
+pub struct PrivacyUseCase<T1, T2, T3, T4>
+where
+    T1: UserRepository + Send + Sync,
+    T2: SessionRepository + Send + Sync,
+    T3: DataExportRepository + Send + Sync,
+    T4: PersonalDataRepository + Send + Sync,
+{
+    user_repository: Arc<T1>,
+    session_repository: Arc<T2>,
+    data_export_repository: Arc<T3>,
+    personal_data_repository: Arc<T4>,
+    audit_logger: Arc<AuditLogger>,
+    settings: PrivacySettings,
+}
+
+impl<T1, T2, T3, T4> PrivacyUseCase<T1, T2, T3, T4>
+where
+    T1: UserRepository + Send + Sync,
+    T2: SessionRepository + Send + Sync,
+    T3: DataExportRepository + Send + Sync,
+    T4: PersonalDataRepository + Send + Sync,
+{
+    pub fn new(
+        user_repository: Arc<T1>,
+        session_repository: Arc<T2>,
+        data_export_repository: Arc<T3>,
+        personal_data_repository: Arc<T4>,
+        audit_logger: Arc<AuditLogger>,
+        settings: PrivacySettings,
+    ) -> Self {
+        Self {
+            user_repository,
+            session_repository,
+            data_export_repository,
+            personal_data_repository,
+            audit_logger,
+            settings,
+        }
+    }
+
+    // Queues an export for the background worker; one export per user at a time, enforced by
+    // a unique index so concurrent requests cannot both get through.
+    pub async fn request_export(&self, user_id: Uuid) -> AppResult<DataExportModel> {
+        match self
+            .data_export_repository
+            .create(InsertDataExportEntity {
+                user_id,
+                status: JobStatus::Pending,
+                created_at: Utc::now().naive_utc(),
+            })
+            .await
+        {
+            Ok(data_export) => Ok(DataExportModel::from(data_export)),
+            Err(AppError::Conflict(_)) => Err(AppError::conflict("A data export is already in progress")),
+            Err(e) => Err(e),
+        }
+    }
+
+    pub async fn get_export(&self, user_id: Uuid, export_id: Uuid) -> AppResult<DataExportModel> {
+        let data_export = self.find_export(user_id, export_id).await?;
+        Ok(DataExportModel::from(data_export))
+    }
+
+    pub async fn download_export(&self, user_id: Uuid, export_id: Uuid) -> AppResult<serde_json::Value> {
+        let data_export = self.find_export(user_id, export_id).await?;
+        if data_export.status != JobStatus::Completed {
+            return Err(AppError::conflict("Data export is not ready"));
+        }
+        match (data_export.data, data_export.expires_at) {
+            (Some(data), Some(expires_at)) if expires_at > Utc::now().naive_utc() => Ok(data),
+            _ => Err(AppError::not_found("Data export has expired")),
+        }
+    }
+
+    // Returns whether an export was processed, so the worker can poll again straight away.
+    pub async fn process_next_export(&self) -> AppResult<bool> {
+        let now = Utc::now().naive_utc();
+        let stale_before = TimeDelta::from_std(self.settings.export_lease)
+            .ok()
+            .and_then(|lease| now.checked_sub_signed(lease))
+            .ok_or_else(|| anyhow::anyhow!("Export lease {:?} is out of range", self.settings.export_lease))?;
+        let Some(data_export) = self
+            .data_export_repository
+            .claim_next(stale_before)
+            .await?
+        else {
+            return Ok(false);
+        };
+
+        let exported = match self.personal_data_repository.collect(data_export.user_id).await {
+            Ok(personal_data) => serde_json::to_value(PersonalDataExportModel::new(personal_data, now))
+                .map_err(AppError::from),
+            Err(e) => Err(e),
+        };
+
+        match exported {
+            Ok(data) => {
+                let expires_at = after(now, self.settings.export_retention)?;
+                self.data_export_repository
+                    .mark_completed(data_export.id, data, expires_at)
+                    .await?;
+                info!("data export {} completed", data_export.id);
+            }
+            Err(e) => {
+                error!("data export {} failed: {}", data_export.id, e);
+                self.data_export_repository
+                    .mark_failed(data_export.id, "Failed to collect personal data".to_string())
+                    .await?;
+            }
+        }
+        Ok(true)
+    }
+
+    pub async fn purge_expired_exports(&self) -> AppResult<usize> {
+        self.data_export_repository
+            .delete_expired(Utc::now().naive_utc())
+            .await
+    }
+
+    // The account is erased once the grace period ends unless the user cancels first.
+    // Other devices are signed out now; the requesting session stays so it can cancel.
+    pub async fn request_erasure(
+        &self,
+        user_id: Uuid,
+        session_id: Uuid,
+        request_erasure_model: RequestErasureModel,
+        context: &AuditContext,
+    ) -> AppResult<ErasureModel> {
+        let user = self.user_repository.find_by_id(user_id).await?;
+        if !hashingpassword::verify(request_erasure_model.password, user.password_hash.clone())? {
+            return Err(AppError::invalid_fields(
+                "Password is incorrect",
+                json!({ "password": ["is incorrect"] }),
+            ));
+        }
+        if let Some(erasure_scheduled_at) = user.erasure_scheduled_at {
+            return Ok(ErasureModel { erasure_scheduled_at });
+        }
+
+        let erasure_scheduled_at = after(Utc::now().naive_utc(), self.settings.erasure_grace_period)?;
+        self.user_repository
+            .schedule_erasure(user.id, Some(erasure_scheduled_at))
+            .await?;
+        self.session_repository
+            .revoke_others_for_user(user.id, session_id)
+            .await?;
+        info!("erasure of user {} scheduled for {}", user.id, erasure_scheduled_at);
+
+        let event = AuditEvent::new(AuditAction::AccountDeleted)
+            .target_user(user.id)
+            .changes(json!({ "erasure_scheduled_at": erasure_scheduled_at }));
+        self.audit_logger.record(context, event).await;
+        Ok(ErasureModel { erasure_scheduled_at })
+    }
+
+    pub async fn cancel_erasure(&self, user_id: Uuid) -> AppResult<()> {
+        let user = self.user_repository.find_by_id(user_id).await?;
+        if user.erasure_scheduled_at.is_none() {
+            return Err(AppError::not_found("No erasure is scheduled"));
+        }
+        self.user_repository.schedule_erasure(user.id, None).await
+    }
+
+    // Erases every account whose grace period has ended; one failure does not stop the rest.
+    pub async fn erase_due(&self) -> AppResult<usize> {
+        let now = Utc::now().naive_utc();
+        let mut erased = 0;
+        for user_id in self.user_repository.find_due_for_erasure(now).await? {
+            match self.personal_data_repository.erase(user_id, now).await {
+                Ok(true) => {
+                    info!("erased user {}", user_id);
+                    erased += 1;
+                }
+                Ok(false) => {}
+                Err(e) => warn!("failed to erase user {}: {}", user_id, e),
+            }
+        }
+        Ok(erased)
+    }
+
+    // Someone else's export is reported as missing rather than forbidden.
+    async fn find_export(&self, user_id: Uuid, export_id: Uuid) -> AppResult<DataExportEntity> {
+        let data_export = self
+            .data_export_repository
+            .find_by_id(export_id)
+            .await
+            .map_err(|e| match e {
+                AppError::NotFound(_) => AppError::not_found("Data export not found"),
+                e => e,
+            })?;
+        if data_export.user_id != user_id {
+            return Err(AppError::not_found("Data export not found"));
+        }
+        Ok(data_export)
+    }
+}
+
+fn after(now: NaiveDateTime, duration: Duration) -> AppResult<NaiveDateTime> {
+    TimeDelta::from_std(duration)
+        .ok()
+        .and_then(|duration| now.checked_add_signed(duration))
+        .ok_or_else(|| anyhow::anyhow!("Duration {:?} is out of range", duration).into())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::domain::{
+        entities::user::{Role, UserEntity, UserStatus},
+        repo::{
+            audit_event::MockAuditEventRepository, data_export::MockDataExportRepository,
+            personal_data::MockPersonalDataRepository, session::MockSessionRepository, user::MockUserRepository,
+        },
+    };
+
+    use super::*;
+
+    const PASSWORD: &str = "correct horse battery";
+
+    struct Mocks {
+        user_repository: MockUserRepository,
+        session_repository: MockSessionRepository,
+        data_export_repository: MockDataExportRepository,
+        audit_event_repository: MockAuditEventRepository,
+    }
+
+    impl Mocks {
+        fn new() -> Self {
+            Self {
+                user_repository: MockUserRepository::new(),
+                session_repository: MockSessionRepository::new(),
+                data_export_repository: MockDataExportRepository::new(),
+                audit_event_repository: MockAuditEventRepository::new(),
+            }
+        }
+
+        fn use_case(
+            self,
+        ) -> PrivacyUseCase<MockUserRepository, MockSessionRepository, MockDataExportRepository, MockPersonalDataRepository>
+        {
+            PrivacyUseCase::new(
+                Arc::new(self.user_repository),
+                Arc::new(self.session_repository),
+                Arc::new(self.data_export_repository),
+                Arc::new(MockPersonalDataRepository::new()),
+                Arc::new(AuditLogger::new(Arc::new(self.audit_event_repository))),
+                PrivacySettings {
+                    export_retention: Duration::from_secs(7 * 24 * 60 * 60),
+                    export_lease: Duration::from_secs(10 * 60),
+                    erasure_grace_period: Duration::from_secs(30 * 24 * 60 * 60),
+                },
+            )
+        }
+    }
+
+    fn user() -> UserEntity {
+        let now = Utc::now().naive_utc();
+        UserEntity {
+            id: Uuid::new_v4(),
+            username: "alice".to_string(),
+            password_hash: hashingpassword::hash(PASSWORD.to_string()).unwrap(),
+            display_name: None,
+            role: Role::PersonaUser,
+            status: UserStatus::Active,
+            created_at: now,
+            updated_at: now,
+            first_name: "Alice".to_string(),
+            last_name: "Example".to_string(),
+            failed_login_attempts: 0,
+            locked_until: None,
+            email: None,
+            erasure_scheduled_at: None,
+        }
+    }
+
+    fn created(entity: InsertDataExportEntity) -> DataExportEntity {
+        DataExportEntity {
+            id: Uuid::new_v4(),
+            user_id: entity.user_id,
+            status: entity.status,
+            data: None,
+            error: None,
+            created_at: entity.created_at,
+            started_at: None,
+            completed_at: None,
+            expires_at: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn queues_an_export() {
+        let user_id = Uuid::new_v4();
+        let mut mocks = Mocks::new();
+        mocks
+            .data_export_repository
+            .expect_create()
+            .withf(move |entity| entity.user_id == user_id && entity.status == JobStatus::Pending)
+            .returning(|entity| Ok(created(entity)));
+
+        let data_export = mocks.use_case().request_export(user_id).await.unwrap();
+        assert_eq!(data_export.status, JobStatus::Pending);
+    }
+
+    #[tokio::test]
+    async fn second_unfinished_export_is_a_conflict() {
+        let mut mocks = Mocks::new();
+        mocks
+            .data_export_repository
+            .expect_create()
+            .returning(|_| Err(AppError::conflict("Resource already exists")));
+
+        let result = mocks.use_case().request_export(Uuid::new_v4()).await;
+        assert!(matches!(result, Err(AppError::Conflict(message)) if message == "A data export is already in progress"));
+    }
+
+    #[tokio::test]
+    async fn claims_exports_left_running_past_the_lease() {
+        let mut mocks = Mocks::new();
+        mocks
+            .data_export_repository
+            .expect_claim_next()
+            .withf(|stale_before| {
+                let lease = Utc::now().naive_utc() - *stale_before;
+                (TimeDelta::minutes(10)..TimeDelta::minutes(11)).contains(&lease)
+            })
+            .returning(|_| Ok(None));
+
+        assert!(!mocks.use_case().process_next_export().await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn erasure_is_scheduled_after_the_grace_period_and_audited() {
+        let user = user();
+        let user_id = user.id;
+        let session_id = Uuid::new_v4();
+        let mut mocks = Mocks::new();
+        mocks.user_repository.expect_find_by_id().returning(move |_| Ok(user.clone()));
+        mocks
+            .user_repository
+            .expect_schedule_erasure()
+            .times(1)
+            .withf(move |id, erasure_scheduled_at| {
+                let grace_period = erasure_scheduled_at.map(|at| at - Utc::now().naive_utc());
+                *id == user_id && grace_period.is_some_and(|grace_period| grace_period > TimeDelta::days(29))
+            })
+            .returning(|_, _| Ok(()));
+        mocks
+            .session_repository
+            .expect_revoke_others_for_user()
+            .times(1)
+            .withf(move |id, keep_session_id| *id == user_id && *keep_session_id == session_id)
+            .returning(|_, _| Ok(2));
+        mocks
+            .audit_event_repository
+            .expect_create()
+            .times(1)
+            .withf(move |event| event.action == "user.delete" && event.target_id == Some(user_id))
+            .returning(|_| Err(AppError::not_found("not stored in this test")));
+
+        let erasure = mocks
+            .use_case()
+            .request_erasure(
+                user_id,
+                session_id,
+                RequestErasureModel { password: PASSWORD.to_string() },
+                &AuditContext::default(),
+            )
+            .await
+            .unwrap();
+        assert!(erasure.erasure_scheduled_at > Utc::now().naive_utc() + TimeDelta::days(29));
+    }
+
+    #[tokio::test]
+    async fn erasure_needs_the_password() {
+        let user = user();
+        let mut mocks = Mocks::new();
+        mocks.user_repository.expect_find_by_id().returning(move |_| Ok(user.clone()));
+        mocks.user_repository.expect_schedule_erasure().never();
+
+        let result = mocks
+            .use_case()
+            .request_erasure(
+                Uuid::new_v4(),
+                Uuid::new_v4(),
+                RequestErasureModel { password: "wrong password".to_string() },
+                &AuditContext::default(),
+            )
+            .await;
+        assert!(matches!(result, Err(AppError::Validation { .. })));
+    }
+}
@@ -0,0 +1,306 @@
+use std::sync::Arc;
+
+use rand::{distributions::Alphanumeric, Rng};
+use serde_json::json;
+use uuid::Uuid;
+
+use crate::domain::{
+    error::{AppError, AppResult},
+    entities::{profile::{ProfileEntity, ProfileStatus}, user::UserEntity},
+    repo::{profile::ProfileRepository, user::UserRepository},
+    service::audit_logger::{AuditAction, AuditContext, AuditEvent, AuditLogger},
+    value_object::profile::{CreateProfileModel, EditProfileModel, ProfileModel, PublicProfileModel},
+};
+
+const SLUG_BASE_MAX_LENGTH: usize = 60;
+const SLUG_SUFFIX_LENGTH: usize = 6;
+const SLUG_MAX_ATTEMPTS: usize = 10;
+
+pub struct ProfileUseCase<T1, T2>
+where
+    T1: ProfileRepository + Send + Sync,
+    T2: UserRepository + Send + Sync,
+{
+    profile_repository: Arc<T1>,
+    user_repository: Arc<T2>,
+    audit_logger: Arc<AuditLogger>,
+}
+
+impl<T1, T2> ProfileUseCase<T1, T2>
+where
+    T1: ProfileRepository + Send + Sync,
+    T2: UserRepository + Send + Sync,
+{
+    pub fn new(profile_repository: Arc<T1>, user_repository: Arc<T2>, audit_logger: Arc<AuditLogger>) -> Self {
+        Self {
+            profile_repository,
+            user_repository,
+            audit_logger,
+        }
+    }
+
+    pub async fn create(&self, owner_id: Uuid, create_profile_model: CreateProfileModel) -> AppResult<ProfileModel> {
+        let profile = self
+            .profile_repository
+            .create(create_profile_model.to_entity(owner_id))
+            .await?;
+        Ok(profile.into())
+    }
+
+    pub async fn list_own(&self, owner_id: Uuid) -> AppResult<Vec<ProfileModel>> {
+        let profiles = self.profile_repository.find_by_owner(owner_id).await?;
+        Ok(profiles.into_iter().map(ProfileModel::from).collect())
+    }
+
+    pub async fn get_own(&self, owner_id: Uuid, profile_id: Uuid) -> AppResult<ProfileModel> {
+        let profile = self.find_owned(owner_id, profile_id).await?;
+        Ok(profile.into())
+    }
+
+    pub async fn update_own(
+        &self,
+        owner_id: Uuid,
+        profile_id: Uuid,
+        edit_profile_model: EditProfileModel,
+        context: &AuditContext,
+    ) -> AppResult<ProfileModel> {
+        let before = self.find_owned(owner_id, profile_id).await?;
+        let profile = self
+            .profile_repository
+            .update(profile_id, edit_profile_model.to_entity())
+            .await?;
+
+        // Only visibility changes are audited; content edits are the owner's own business.
+        if before.status != profile.status {
+            let action = match profile.status {
+                ProfileStatus::Public => AuditAction::ProfilePublished,
+                ProfileStatus::Private => AuditAction::ProfileUnpublished,
+            };
+            let event = AuditEvent::new(action)
+                .target_profile(profile.id)
+                .changes(json!({ "status": { "from": before.status, "to": profile.status } }));
+            self.audit_logger.record(context, event).await;
+        }
+        Ok(profile.into())
+    }
+
+    pub async fn delete_own(&self, owner_id: Uuid, profile_id: Uuid) -> AppResult<()> {
+        self.find_owned(owner_id, profile_id).await?;
+        self.profile_repository.delete(profile_id).await
+    }
+
+    pub async fn regenerate_slug(&self, owner_id: Uuid, profile_id: Uuid, context: &AuditContext) -> AppResult<ProfileModel> {
+        let before = self.find_owned(owner_id, profile_id).await?;
+        let owner = self.user_repository.find_by_id(owner_id).await?;
+        let profile = self.assign_unique_slug(&owner, profile_id).await?;
+        self.audit_slug_change(context, AuditAction::ShareLinkCreated, &before, &profile).await;
+        Ok(profile.into())
+    }
+
+    pub async fn revoke_slug(&self, owner_id: Uuid, profile_id: Uuid, context: &AuditContext) -> AppResult<ProfileModel> {
+        let before = self.find_owned(owner_id, profile_id).await?;
+        let profile = self.profile_repository.set_slug(profile_id, None).await?;
+        self.audit_slug_change(context, AuditAction::ShareLinkRevoked, &before, &profile).await;
+        Ok(profile.into())
+    }
+
+    // Private profiles behind a slug are reported as missing, same as an unknown slug.
+    pub async fn find_public_by_slug(&self, slug: String) -> AppResult<PublicProfileModel> {
+        let profile = self
+            .profile_repository
+            .find_by_slug(slug)
+            .await
+            .map_err(|e| match e {
+                AppError::NotFound(_) => AppError::not_found("Profile not found"),
+                e => e,
+            })?;
+
+        if profile.status != ProfileStatus::Public {
+            return Err(AppError::not_found("Profile not found"));
+        }
+
+        let owner = self.user_repository.find_by_id(profile.owner_id).await?;
+
+        Ok(PublicProfileModel {
+            shareable_link_slug: profile.shareable_link_slug.unwrap_or_default(),
+            display_name: owner.display_name,
+            content: profile.content,
+            layout_config: profile.layout_config,
+            updated_at: profile.updated_at,
+        })
+    }
+
+    async fn audit_slug_change(&self, context: &AuditContext, action: AuditAction, before: &ProfileEntity, after: &ProfileEntity) {
+        let event = AuditEvent::new(action).target_profile(after.id).changes(json!({
+            "shareable_link_slug": { "from": before.shareable_link_slug, "to": after.shareable_link_slug }
+        }));
+        self.audit_logger.record(context, event).await;
+    }
+
+    // Profiles owned by someone else are reported as missing so ids cannot be probed.
+    async fn find_owned(&self, owner_id: Uuid, profile_id: Uuid) -> AppResult<ProfileEntity> {
+        let profile = self
+            .profile_repository
+            .find_by_id(profile_id)
+            .await
+            .map_err(|e| match e {
+                AppError::NotFound(_) => AppError::not_found("Profile not found"),
+                e => e,
+            })?;
+
+        if profile.owner_id != owner_id {
+            return Err(AppError::not_found("Profile not found"));
+        }
+
+        Ok(profile)
+    }
+
+    // Another profile can take the chosen slug between the check and the write; the UNIQUE
+    // constraint then reports a conflict and a fresh slug is picked.
+    async fn assign_unique_slug(&self, owner: &UserEntity, profile_id: Uuid) -> AppResult<ProfileEntity> {
+        for _ in 0..SLUG_MAX_ATTEMPTS {
+            let slug = self.generate_unique_slug(owner).await?;
+            match self.profile_repository.set_slug(profile_id, Some(slug)).await {
+                Err(AppError::Conflict(_)) => continue,
+                result => return result,
+            }
+        }
+
+        Err(AppError::conflict("Could not generate a unique slug"))
+    }
+
+    // The readable base is tried first, then random suffixes are appended until a free
+    // slug is found.
+    async fn generate_unique_slug(&self, owner: &UserEntity) -> AppResult<String> {
+        let base = slug_base(owner);
+
+        if !self.profile_repository.slug_exists(base.clone()).await? {
+            return Ok(base);
+        }
+
+        for _ in 0..SLUG_MAX_ATTEMPTS {
+            let candidate = format!("{}-{}", base, random_suffix());
+            if !self.profile_repository.slug_exists(candidate.clone()).await? {
+                return Ok(candidate);
+            }
+        }
+
+        Err(AppError::conflict("Could not generate a unique slug"))
+    }
+}
+
+fn slug_base(owner: &UserEntity) -> String {
+    let candidates = [
+        owner.display_name.clone().unwrap_or_default(),
+        format!("{} {}", owner.first_name, owner.last_name),
+        owner.username.clone(),
+    ];
+
+    candidates
+        .iter()
+        .map(|candidate| slugify(candidate))
+        .find(|slug| !slug.is_empty())
+        .unwrap_or_else(|| "persona".to_string())
+}
+
+fn slugify(value: &str) -> String {
+    let mut slug = String::new();
+
+    for c in value.chars() {
+        if c.is_ascii_alphanumeric() {
+            slug.push(c.to_ascii_lowercase());
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+
+        if slug.len() >= SLUG_BASE_MAX_LENGTH {
+            break;
+        }
+    }
+
+    slug.trim_end_matches('-').to_string()
+}
+
+fn random_suffix() -> String {
+    rand::thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(SLUG_SUFFIX_LENGTH)
+        .map(|c| char::from(c).to_ascii_lowercase())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDateTime;
+
+    use super::*;
+    use crate::domain::entities::user::{Role, UserStatus};
+
+    fn owner(display_name: Option<&str>, first_name: &str, last_name: &str, username: &str) -> UserEntity {
+        let now = NaiveDateTime::default();
+        UserEntity {
+            id: Uuid::new_v4(),
+            username: username.to_string(),
+            password_hash: String::new(),
+            display_name: display_name.map(str::to_string),
+            role: Role::PersonaUser,
+            status: UserStatus::Active,
+            created_at: now,
+            updated_at: now,
+            first_name: first_name.to_string(),
+            last_name: last_name.to_string(),
+            failed_login_attempts: 0,
+            locked_until: None,
+            email: None,
+            erasure_scheduled_at: None,
+        }
+    }
+
+    #[test]
+    fn lowercases_and_joins_words_with_dashes() {
+        assert_eq!(slugify("Jane Doe"), "jane-doe");
+        assert_eq!(slugify("Data Engineer 2"), "data-engineer-2");
+    }
+
+    #[test]
+    fn collapses_punctuation_and_trims_dashes() {
+        assert_eq!(slugify("  --Jane!!  O'Neil--  "), "jane-o-neil");
+        assert_eq!(slugify("a___b...c"), "a-b-c");
+    }
+
+    #[test]
+    fn non_ascii_letters_become_separators() {
+        assert_eq!(slugify("Ñoño Café"), "o-o-caf");
+        assert_eq!(slugify("สมชาย ใจดี"), "");
+    }
+
+    #[test]
+    fn empty_and_all_symbol_names_give_an_empty_slug() {
+        assert_eq!(slugify(""), "");
+        assert_eq!(slugify("!@#$ %^&*"), "");
+    }
+
+    #[test]
+    fn caps_the_length_without_a_trailing_dash() {
+        assert_eq!(slugify(&"a".repeat(100)), "a".repeat(SLUG_BASE_MAX_LENGTH));
+        let cut_at_separator = format!("{} bcd", "a".repeat(SLUG_BASE_MAX_LENGTH - 1));
+        assert_eq!(slugify(&cut_at_separator), "a".repeat(SLUG_BASE_MAX_LENGTH - 1));
+    }
+
+    #[test]
+    fn suffix_is_short_lowercase_alphanumeric() {
+        for _ in 0..50 {
+            let suffix = random_suffix();
+            assert_eq!(suffix.len(), SLUG_SUFFIX_LENGTH);
+            assert!(suffix.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit()), "{suffix}");
+        }
+    }
+
+    #[test]
+    fn base_falls_back_from_display_name_to_full_name_to_username() {
+        assert_eq!(slug_base(&owner(Some("The Builder"), "Bob", "Smith", "bob1")), "the-builder");
+        assert_eq!(slug_base(&owner(Some("!!!"), "Bob", "Smith", "bob1")), "bob-smith");
+        assert_eq!(slug_base(&owner(None, "สมชาย", "ใจดี", "somchai")), "somchai");
+        assert_eq!(slug_base(&owner(None, "สมชาย", "ใจดี", "---")), "persona");
+    }
+}
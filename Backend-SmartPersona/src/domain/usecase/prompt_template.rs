@@ -0,0 +1,209 @@
+use std::sync::Arc;
+
+use uuid::Uuid;
+
+use crate::domain::{
+    error::{AppError, AppResult},
+    entities::prompt_template::PromptTemplateEntity,
+    repo::prompt_template::PromptTemplateRepository,
+    value_object::prompt_template::{
+        validate_template, CreatePromptTemplateModel, EditPromptTemplateModel, PromptTemplateModel,
+        PromptTemplateVersionModel,
+    },
+};
+
+pub struct PromptTemplateUseCase<T>
+where
+    T: PromptTemplateRepository + Send + Sync,
+{
+    prompt_template_repository: Arc<T>,
+}
+
+impl<T> PromptTemplateUseCase<T>
+where
+    T: PromptTemplateRepository + Send + Sync,
+{
+    pub fn new(prompt_template_repository: Arc<T>) -> Self {
+        Self { prompt_template_repository }
+    }
+
+    pub async fn list(&self) -> AppResult<Vec<PromptTemplateModel>> {
+        let templates = self.prompt_template_repository.find_all().await?;
+
+        let mut models = Vec::with_capacity(templates.len());
+        for template in templates {
+            models.push(self.to_model(template).await?);
+        }
+        Ok(models)
+    }
+
+    pub async fn get(&self, template_id: Uuid) -> AppResult<PromptTemplateModel> {
+        let template = self.prompt_template_repository.find_by_id(template_id).await?;
+        self.to_model(template).await
+    }
+
+    pub async fn create(&self, create_prompt_template_model: CreatePromptTemplateModel) -> AppResult<PromptTemplateModel> {
+        if create_prompt_template_model.name.trim().is_empty() {
+            return Err(AppError::validation("Template name must not be empty"));
+        }
+        validate_template(&create_prompt_template_model.template_text)?;
+
+        let template = self
+            .prompt_template_repository
+            .create(
+                create_prompt_template_model.to_entity(),
+                create_prompt_template_model.template_text,
+            )
+            .await?;
+        self.to_model(template).await
+    }
+
+    pub async fn update(
+        &self,
+        template_id: Uuid,
+        edit_prompt_template_model: EditPromptTemplateModel,
+    ) -> AppResult<PromptTemplateModel> {
+        if let Some(name) = &edit_prompt_template_model.name
+            && name.trim().is_empty()
+        {
+            return Err(AppError::validation("Template name must not be empty"));
+        }
+        if let Some(template_text) = &edit_prompt_template_model.template_text {
+            validate_template(template_text)?;
+        }
+
+        let template = self
+            .prompt_template_repository
+            .update(
+                template_id,
+                edit_prompt_template_model.to_entity(),
+                edit_prompt_template_model.template_text,
+            )
+            .await?;
+        self.to_model(template).await
+    }
+
+    // Templates are archived rather than deleted so past jobs keep their prompt history.
+    pub async fn delete(&self, template_id: Uuid) -> AppResult<()> {
+        self.prompt_template_repository.archive(template_id).await
+    }
+
+    pub async fn list_versions(&self, template_id: Uuid) -> AppResult<Vec<PromptTemplateVersionModel>> {
+        let versions = self.prompt_template_repository.find_versions(template_id).await?;
+        Ok(versions.into_iter().map(PromptTemplateVersionModel::from).collect())
+    }
+
+    pub async fn get_version(&self, template_id: Uuid, version: i32) -> AppResult<PromptTemplateVersionModel> {
+        let version = self
+            .prompt_template_repository
+            .find_version(template_id, version)
+            .await?;
+        Ok(version.into())
+    }
+
+    // Rolling back only moves the active pointer, so later versions stay available.
+    pub async fn rollback(&self, template_id: Uuid, version: i32) -> AppResult<PromptTemplateModel> {
+        let version = self
+            .prompt_template_repository
+            .find_version(template_id, version)
+            .await
+            .map_err(|e| match e {
+                AppError::NotFound(_) => AppError::not_found("Template version not found"),
+                e => e,
+            })?;
+
+        let template = self
+            .prompt_template_repository
+            .set_active_version(template_id, version.id)
+            .await?;
+        self.to_model(template).await
+    }
+
+    async fn to_model(&self, template: PromptTemplateEntity) -> AppResult<PromptTemplateModel> {
+        let active_version = match template.active_version_id {
+            Some(version_id) => Some(self.prompt_template_repository.find_version_by_id(version_id).await?),
+            None => None,
+        };
+        Ok(PromptTemplateModel::new(template, active_version))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+
+    use crate::domain::{
+        entities::prompt_template::PromptTemplateVersionEntity, repo::prompt_template::MockPromptTemplateRepository,
+    };
+
+    use super::*;
+
+    fn template(active_version_id: Uuid) -> PromptTemplateEntity {
+        let now = Utc::now().naive_utc();
+        PromptTemplateEntity {
+            id: Uuid::new_v4(),
+            name: "personality_analysis".to_string(),
+            active_version_id: Some(active_version_id),
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    fn version(template_id: Uuid, version: i32) -> PromptTemplateVersionEntity {
+        PromptTemplateVersionEntity {
+            id: Uuid::new_v4(),
+            template_id,
+            version,
+            template_text: format!("Version {} for {{{{posts}}}}", version),
+            created_at: Utc::now().naive_utc(),
+        }
+    }
+
+    #[tokio::test]
+    async fn rollback_activates_the_requested_version() {
+        let template_id = Uuid::new_v4();
+        let second = version(template_id, 2);
+        let second_id = second.id;
+        let mut prompt_template_repository = MockPromptTemplateRepository::new();
+        let found = second.clone();
+        prompt_template_repository
+            .expect_find_version()
+            .withf(move |id, version| *id == template_id && *version == 2)
+            .times(1)
+            .return_once(move |_, _| Ok(found));
+        prompt_template_repository
+            .expect_set_active_version()
+            .withf(move |id, version_id| *id == template_id && *version_id == second_id)
+            .times(1)
+            .returning(|_, version_id| Ok(template(version_id)));
+        prompt_template_repository
+            .expect_find_version_by_id()
+            .withf(move |version_id| *version_id == second_id)
+            .return_once(move |_| Ok(second));
+
+        let model = PromptTemplateUseCase::new(Arc::new(prompt_template_repository))
+            .rollback(template_id, 2)
+            .await
+            .unwrap();
+
+        let active_version = model.active_version.unwrap();
+        assert_eq!(active_version.id, second_id);
+        assert_eq!(active_version.version, 2);
+        assert_eq!(active_version.placeholders, vec!["posts"]);
+    }
+
+    #[tokio::test]
+    async fn rollback_to_an_unknown_version_changes_nothing() {
+        let mut prompt_template_repository = MockPromptTemplateRepository::new();
+        prompt_template_repository
+            .expect_find_version()
+            .returning(|_, _| Err(AppError::not_found("Resource not found")));
+        prompt_template_repository.expect_set_active_version().never();
+
+        let result = PromptTemplateUseCase::new(Arc::new(prompt_template_repository))
+            .rollback(Uuid::new_v4(), 9)
+            .await;
+
+        assert!(matches!(result, Err(AppError::NotFound(message)) if message == "Template version not found"));
+    }
+}
@@ -0,0 +1,63 @@
+use std::sync::Arc;
+
+use uuid::Uuid;
+
+use crate::domain::{
+    error::{AppError, AppResult},
+    repo::social_connection::SocialConnectionRepository,
+    value_object::social_connection::{normalize_platform, LinkSocialConnectionModel, SocialConnectionModel},
+};
+
+const MAX_PLATFORM_LENGTH: usize = 50;
+
+pub struct SocialConnectionUseCase<T>
+where
+    T: SocialConnectionRepository + Send + Sync,
+{
+    social_connection_repository: Arc<T>,
+}
+
+impl<T> SocialConnectionUseCase<T>
+where
+    T: SocialConnectionRepository + Send + Sync,
+{
+    pub fn new(social_connection_repository: Arc<T>) -> Self {
+        Self { social_connection_repository }
+    }
+
+    pub async fn list(&self, user_id: Uuid) -> AppResult<Vec<SocialConnectionModel>> {
+        let connections = self.social_connection_repository.find_by_user(user_id).await?;
+        Ok(connections.into_iter().map(SocialConnectionModel::from).collect())
+    }
+
+    pub async fn link(&self, user_id: Uuid, link_social_connection_model: LinkSocialConnectionModel) -> AppResult<SocialConnectionModel> {
+        let platform = normalize_platform(&link_social_connection_model.platform);
+        if platform.is_empty() || platform.len() > MAX_PLATFORM_LENGTH {
+            return Err(AppError::validation(format!("platform must be between 1 and {} characters", MAX_PLATFORM_LENGTH)));
+        }
+        if link_social_connection_model.platform_user_id.trim().is_empty() {
+            return Err(AppError::validation("platform_user_id must not be empty"));
+        }
+        if link_social_connection_model.access_token.is_empty() {
+            return Err(AppError::validation("access_token must not be empty"));
+        }
+
+        let connection = self
+            .social_connection_repository
+            .upsert(link_social_connection_model.to_entity(user_id))
+            .await?;
+        Ok(connection.into())
+    }
+
+    pub async fn unlink(&self, user_id: Uuid, platform: String) -> AppResult<()> {
+        let deleted = self
+            .social_connection_repository
+            .delete_by_user_and_platform(user_id, normalize_platform(&platform))
+            .await?;
+
+        if deleted == 0 {
+            return Err(AppError::not_found("Social connection not found"));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,104 @@
+use std::sync::Arc;
+
+use chrono::{Duration, Utc};
+use tracing::{error, warn};
+
+use crate::domain::{
+    error::AppResult,
+    entities::social_connection::SocialConnectionEntity,
+    repo::{social_connection::SocialConnectionRepository, token_refresher::TokenRefresher},
+};
+
+pub type SharedTokenRefresher = Arc<dyn TokenRefresher + Send + Sync>;
+
+pub struct SocialConnectionRefreshUseCase<T>
+where
+    T: SocialConnectionRepository + Send + Sync,
+{
+    social_connection_repository: Arc<T>,
+    token_refreshers: Vec<SharedTokenRefresher>,
+    max_failures: i32,
+}
+
+impl<T> SocialConnectionRefreshUseCase<T>
+where
+    T: SocialConnectionRepository + Send + Sync,
+{
+    pub fn new(social_connection_repository: Arc<T>, token_refreshers: Vec<SharedTokenRefresher>, max_failures: i32) -> Self {
+        Self {
+            social_connection_repository,
+            token_refreshers,
+            max_failures,
+        }
+    }
+
+    /// Refreshes up to `batch_size` connections expiring within `window`. A connection that
+    /// failed is retried after `retry_after` and marked broken after `max_failures`
+    /// consecutive failures. Returns the number of connections refreshed.
+    pub async fn refresh_expiring(&self, window: Duration, retry_after: Duration, batch_size: i64) -> AppResult<usize> {
+        let now = Utc::now();
+        let connections = self
+            .social_connection_repository
+            .claim_due_for_refresh((now + window).naive_utc(), (now - retry_after).naive_utc(), batch_size)
+            .await?;
+
+        let mut refreshed = 0;
+        for connection in connections {
+            let connection_id = connection.id;
+            let platform = connection.platform.clone();
+
+            match self.refresh(connection).await {
+                Ok(true) => refreshed += 1,
+                // No refresher for this platform; the token simply expires.
+                Ok(false) => {}
+                Err(e) => {
+                    warn!("failed to refresh {} connection {}: {}", platform, connection_id, e);
+                    // A database error here only affects this connection; the rest of the batch still runs.
+                    let failures = match self.social_connection_repository.record_refresh_failure(connection_id).await {
+                        Ok(failures) => failures,
+                        Err(e) => {
+                            error!("failed to record refresh failure of {} connection {}: {}", platform, connection_id, e);
+                            continue;
+                        }
+                    };
+                    if failures >= self.max_failures {
+                        warn!("marking {} connection {} as broken after {} failures", platform, connection_id, failures);
+                        if let Err(e) = self.social_connection_repository.mark_broken(connection_id).await {
+                            error!("failed to mark {} connection {} as broken: {}", platform, connection_id, e);
+                        }
+                    }
+                }
+            }
+        }
+
+        Ok(refreshed)
+    }
+
+    async fn refresh(&self, connection: SocialConnectionEntity) -> AppResult<bool> {
+        let Some(refresher) = self
+            .token_refreshers
+            .iter()
+            .find(|refresher| refresher.platform() == connection.platform)
+        else {
+            return Ok(false);
+        };
+        let Some(refresh_token) = connection.refresh_token.as_deref() else {
+            return Ok(false);
+        };
+
+        let token = refresher.refresh(refresh_token).await?;
+        let expires_at = token
+            .expires_in
+            .map(|expires_in| (Utc::now() + Duration::seconds(expires_in)).naive_utc());
+
+        self.social_connection_repository
+            .update_tokens(SocialConnectionEntity {
+                access_token: token.access_token,
+                refresh_token: token.refresh_token.or(connection.refresh_token.clone()),
+                expires_at,
+                ..connection
+            })
+            .await?;
+        Ok(true)
+    }
+}
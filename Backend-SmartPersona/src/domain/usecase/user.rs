@@ -1,27 +1,437 @@
-use std::sync::Arc;
+use std::{sync::Arc, time::Duration};
 
-use crate::{domain::{entities::user, repo::user::UserRepository, value_object::user::RegisterUserModel}, infrastructure::hashingpassword};
-use anyhow::{Ok, Result};
+use chrono::{TimeDelta, Utc};
+use serde_json::json;
+use tracing::{info, warn};
+
+use crate::{
+    domain::{
+        entities::user::{UserEntity, UserStatus},
+        error::{AppError, AppResult},
+        repo::{mailer::{EmailMessage, SharedMailer}, session::SessionRepository, user::UserRepository},
+        service::audit_logger::{self, AuditAction, AuditContext, AuditEvent, AuditLogger},
+        value_object::user::{
+            ChangePasswordModel, RegisterUserModel, ResendVerificationModel, UpdateUserModel,
+            UserModel, VerifyEmailModel,
+        },
+    },
+    infrastructure::{
+        hashingpassword,
+        password_policy::PasswordValidator,
+        jwt_authentication::{self, jwt_keys::{JwtKeys, TokenKind}, jwt_model::EmailVerificationClaims},
+    },
+};
 use uuid::Uuid;
-pub struct UserUseCase<T>
+
+pub struct EmailVerificationSettings {
+    // Frontend page that receives `?token=...` and posts it back to /users/verify-email.
+    pub verify_url: String,
+    pub token_lifetime: Duration,
+}
+
+pub struct UserUseCase<T1, T2>
 where
-    T: UserRepository + Send + Sync,
+    T1: UserRepository + Send + Sync,
+    T2: SessionRepository + Send + Sync,
 {
-    user_repository: Arc<T>,
+    user_repository: Arc<T1>,
+    session_repository: Arc<T2>,
+    jwt_keys: Arc<JwtKeys>,
+    mailer: SharedMailer,
+    password_validator: Arc<PasswordValidator>,
+    audit_logger: Arc<AuditLogger>,
+    email_verification: EmailVerificationSettings,
 }
-impl<T> UserUseCase<T>
+impl<T1, T2> UserUseCase<T1, T2>
 where
-    T: UserRepository + Send + Sync,
+    T1: UserRepository + Send + Sync,
+    T2: SessionRepository + Send + Sync,
 {
-    pub fn new(user_repository: Arc<T>) -> Self {
-        Self { user_repository }
+    pub fn new(
+        user_repository: Arc<T1>,
+        session_repository: Arc<T2>,
+        jwt_keys: Arc<JwtKeys>,
+        mailer: SharedMailer,
+        password_validator: Arc<PasswordValidator>,
+        audit_logger: Arc<AuditLogger>,
+        email_verification: EmailVerificationSettings,
+    ) -> Self {
+        Self { user_repository, session_repository, jwt_keys, mailer, password_validator, audit_logger, email_verification }
     }
-    pub async fn register(&self, mut register_user_model: RegisterUserModel) -> anyhow::Result<Uuid> {
+    // New accounts stay pending until the link in the verification email is followed.
+    // An email that already has an account gets a notice instead of a second account, and
+    // the caller sees the same response either way so registering can't reveal which
+    // emails are in use.
+    pub async fn register(&self, mut register_user_model: RegisterUserModel, context: &AuditContext) -> AppResult<()> {
+        register_user_model.validate(&self.password_validator)?;
+        self.ensure_username_available(&register_user_model.username).await?;
+        // Hashed before the email lookup so both outcomes take as long.
         let hashed_password = hashingpassword::hash(register_user_model.password.clone())?;
+        if self.notify_if_email_registered(&register_user_model.email).await? {
+            return Ok(());
+        }
     
         register_user_model.password = hashed_password;
         let register_user_entity = register_user_model.to_entity();
-        let user_id = self.user_repository.register(register_user_entity).await?;
-        Ok(user_id)
+        // Lost a race with another registration for the same username or email.
+        let user_id = match self.user_repository.register(register_user_entity).await {
+            Ok(user_id) => user_id,
+            Err(AppError::Conflict(_)) => {
+                if self.notify_if_email_registered(&register_user_model.email).await? {
+                    return Ok(());
+                }
+                return Err(AppError::conflict("Username is already taken"));
+            }
+            Err(e) => return Err(e),
+        };
+
+        let user = self.user_repository.find_by_id(user_id).await?;
+        let event = AuditEvent::new(AuditAction::UserRegistered)
+            .actor(user.id)
+            .target_user(user.id)
+            .changes(json!({ "status": user.status }));
+        self.audit_logger.record(context, event).await;
+        self.send_verification_email(&user)?;
+        Ok(())
+    }
+
+    // Following the link again after it worked is harmless and succeeds.
+    pub async fn verify_email(&self, verify_email_model: VerifyEmailModel, context: &AuditContext) -> AppResult<()> {
+        let claims = jwt_authentication::verify_email_verification_token(&self.jwt_keys, verify_email_model.token.trim())
+            .map_err(|_| AppError::validation("Invalid or expired verification link"))?;
+        let user_id = Uuid::parse_str(&claims.sub)
+            .map_err(|_| AppError::validation("Invalid or expired verification link"))?;
+
+        let user = self.user_repository.find_by_id(user_id).await?;
+        if user.email.as_deref() != Some(claims.email.as_str()) {
+            return Err(AppError::validation("Invalid or expired verification link"));
+        }
+
+        match user.status {
+            UserStatus::Pending => {
+                if self.user_repository.verify_email(user.id).await? {
+                    let event = AuditEvent::new(AuditAction::EmailVerified)
+                        .actor(user.id)
+                        .target_user(user.id)
+                        .changes(json!({ "status": { "from": UserStatus::Pending, "to": UserStatus::Active } }));
+                    self.audit_logger.record(context, event).await;
+                }
+                Ok(())
+            }
+            UserStatus::Active => Ok(()),
+            status => Err(AppError::AccountInactive(status)),
+        }
+    }
+
+    // Always succeeds so the response does not reveal which emails have an account.
+    pub async fn resend_verification(&self, resend_verification_model: ResendVerificationModel) -> AppResult<()> {
+        let email = resend_verification_model.email.trim().to_lowercase();
+        match self.user_repository.find_by_email(email).await {
+            Ok(user) if user.status == UserStatus::Pending => self.send_verification_email(&user),
+            Ok(_) => Ok(()),
+            Err(AppError::NotFound(_)) => {
+                info!("verification email requested for an unknown email");
+                Ok(())
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    pub async fn get_me(&self, user_id: Uuid) -> AppResult<UserModel> {
+        let user = self.user_repository.find_by_id(user_id).await?;
+        Ok(UserModel::from(user))
+    }
+
+    pub async fn update_me(&self, user_id: Uuid, update_user_model: UpdateUserModel, context: &AuditContext) -> AppResult<UserModel> {
+        update_user_model.validate()?;
+        let before = UserModel::from(self.user_repository.find_by_id(user_id).await?);
+        let after = UserModel::from(self.user_repository.update(user_id, update_user_model.to_entity()).await?);
+
+        let event = AuditEvent::new(AuditAction::AccountUpdated)
+            .target_user(user_id)
+            .changes(audit_logger::diff(&before, &after));
+        self.audit_logger.record(context, event).await;
+        Ok(after)
+    }
+
+    // Other devices are signed out; the session making the change stays signed in.
+    pub async fn change_password(
+        &self,
+        user_id: Uuid,
+        session_id: Uuid,
+        change_password_model: ChangePasswordModel,
+        context: &AuditContext,
+    ) -> AppResult<usize> {
+        let user = self.user_repository.find_by_id(user_id).await?;
+        if !hashingpassword::verify(change_password_model.current_password, user.password_hash.clone())? {
+            return Err(AppError::invalid_fields(
+                "Current password is incorrect",
+                json!({ "current_password": ["is incorrect"] }),
+            ));
+        }
+
+        let password_problems = self.password_validator.check(&change_password_model.new_password, Some(&user.username));
+        if !password_problems.is_empty() {
+            return Err(AppError::invalid_fields(
+                "Invalid new password",
+                json!({ "new_password": password_problems }),
+            ));
+        }
+
+        let password_hash = hashingpassword::hash(change_password_model.new_password)?;
+        self.user_repository.update_password(user.id, password_hash).await?;
+        let revoked = self.session_repository.revoke_others_for_user(user.id, session_id).await?;
+
+        let event = AuditEvent::new(AuditAction::PasswordChanged)
+            .target_user(user.id)
+            .changes(json!({ "revoked_sessions": revoked }));
+        self.audit_logger.record(context, event).await;
+        Ok(revoked)
+    }
+
+    async fn ensure_username_available(&self, username: &str) -> AppResult<()> {
+        match self.user_repository.find_by_username(username.to_string()).await {
+            Ok(_) => Err(AppError::conflict("Username is already taken")),
+            Err(AppError::NotFound(_)) => Ok(()),
+            Err(e) => Err(e),
+        }
+    }
+
+    // Tells the owner of an already registered email that someone tried to sign up with it.
+    async fn notify_if_email_registered(&self, email: &str) -> AppResult<bool> {
+        let user = match self.user_repository.find_by_email(email.trim().to_lowercase()).await {
+            Ok(user) => user,
+            Err(AppError::NotFound(_)) => return Ok(false),
+            Err(e) => return Err(e),
+        };
+        let Some(email) = user.email else {
+            return Ok(true);
+        };
+
+        info!("registration attempted with the email of user {}", user.id);
+        self.send_in_background(EmailMessage {
+            to: email,
+            subject: "Someone tried to sign up with your email".to_string(),
+            body: format!(
+                "Hi {},\n\nSomeone tried to create a new account with this email address, which already belongs to your account \"{}\". No new account was created.\n\nIf it was you, log in instead or reset your password if you have forgotten it. Otherwise you can ignore this email.\n",
+                user.first_name, user.username,
+            ),
+        });
+        Ok(true)
+    }
+
+    // The email goes out in the background; a failure is logged and the user can ask again.
+    fn send_verification_email(&self, user: &UserEntity) -> AppResult<()> {
+        let Some(email) = user.email.clone() else {
+            return Ok(());
+        };
+
+        let now = Utc::now();
+        let lifetime = TimeDelta::from_std(self.email_verification.token_lifetime)
+            .map_err(|_| anyhow::anyhow!("Verification token lifetime {:?} is out of range", self.email_verification.token_lifetime))?;
+        let claims = EmailVerificationClaims {
+            sub: user.id.to_string(),
+            iss: self.jwt_keys.issuer.clone(),
+            aud: self.jwt_keys.audience(TokenKind::EmailVerification).to_string(),
+            exp: (now + lifetime).timestamp() as usize,
+            iat: now.timestamp() as usize,
+            email: email.clone(),
+        };
+        let token = jwt_authentication::generate_email_verification_token(&self.jwt_keys, &claims)?;
+
+        let message = EmailMessage {
+            to: email,
+            subject: "Verify your email address".to_string(),
+            body: format!(
+                "Hi {},\n\nPlease confirm your email address to activate your account. The link expires in {}.\n\n{}?token={}\n",
+                user.first_name,
+                humantime::format_duration(self.email_verification.token_lifetime),
+                self.email_verification.verify_url,
+                token,
+            ),
+        };
+
+        self.send_in_background(message);
+        Ok(())
+    }
+
+    fn send_in_background(&self, message: EmailMessage) {
+        let mailer = Arc::clone(&self.mailer);
+        tokio::spawn(async move {
+            let subject = message.subject.clone();
+            if let Err(e) = mailer.send(message).await {
+                warn!("failed to send \"{}\" email: {}", subject, e);
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::{Path, PathBuf};
+
+    use chrono::NaiveDateTime;
+
+    use super::*;
+    use crate::{
+        config::config_model::PasswordPolicy,
+        domain::{
+            entities::user::Role,
+            repo::{audit_event::MockAuditEventRepository, session::MockSessionRepository, user::MockUserRepository},
+        },
+        infrastructure::{jwt_authentication::jwt_keys::tests::load_keys, mailer::file::FileMailer},
+    };
+
+    fn use_case(
+        user_repository: MockUserRepository,
+        audit_event_repository: MockAuditEventRepository,
+        mail_dir: &Path,
+    ) -> UserUseCase<MockUserRepository, MockSessionRepository> {
+        UserUseCase::new(
+            Arc::new(user_repository),
+            Arc::new(MockSessionRepository::new()),
+            Arc::new(load_keys("EdDSA", "ed25519", &["ed25519"])),
+            Arc::new(FileMailer::new(Some(mail_dir.to_path_buf()))),
+            Arc::new(
+                PasswordValidator::new(&PasswordPolicy {
+                    min_length: 12,
+                    max_length: 128,
+                    require_uppercase: false,
+                    require_lowercase: false,
+                    require_digit: false,
+                    require_symbol: false,
+                    breached_list_path: None,
+                })
+                .unwrap(),
+            ),
+            Arc::new(AuditLogger::new(Arc::new(audit_event_repository))),
+            EmailVerificationSettings {
+                verify_url: "http://localhost:3000/verify-email".to_string(),
+                token_lifetime: Duration::from_secs(24 * 60 * 60),
+            },
+        )
+    }
+
+    fn registration() -> RegisterUserModel {
+        RegisterUserModel {
+            username: "alice".to_string(),
+            email: " Alice@Example.com ".to_string(),
+            firstname: "Alice".to_string(),
+            lastname: "Example".to_string(),
+            password: "correct horse battery".to_string(),
+        }
+    }
+
+    fn user(username: &str) -> UserEntity {
+        let now = NaiveDateTime::default();
+        UserEntity {
+            id: Uuid::new_v4(),
+            username: username.to_string(),
+            password_hash: String::new(),
+            display_name: None,
+            role: Role::PersonaUser,
+            status: UserStatus::Pending,
+            created_at: now,
+            updated_at: now,
+            first_name: "Alice".to_string(),
+            last_name: "Example".to_string(),
+            failed_login_attempts: 0,
+            locked_until: None,
+            email: Some("alice@example.com".to_string()),
+            erasure_scheduled_at: None,
+        }
+    }
+
+    fn mail_dir() -> PathBuf {
+        std::env::temp_dir().join(format!("register-test-{}", Uuid::new_v4()))
+    }
+
+    // The email goes out from a spawned task, so wait for it to land.
+    async fn read_single_email(dir: &Path) -> String {
+        for _ in 0..100 {
+            let email = std::fs::read_dir(dir)
+                .ok()
+                .and_then(|mut entries| entries.next())
+                .and_then(|entry| std::fs::read_to_string(entry.ok()?.path()).ok());
+            if let Some(email) = email.filter(|email| email.ends_with('\n')) {
+                return email;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        panic!("no email was written to {}", dir.display());
+    }
+
+    #[tokio::test]
+    async fn registers_a_new_account_and_sends_the_verification_email() {
+        let dir = mail_dir();
+        let registered = user("alice");
+        let user_id = registered.id;
+        let mut user_repository = MockUserRepository::new();
+        user_repository
+            .expect_find_by_username()
+            .returning(|_| Err(AppError::not_found("Resource not found")));
+        user_repository
+            .expect_find_by_email()
+            .withf(|email| email == "alice@example.com")
+            .returning(|_| Err(AppError::not_found("Resource not found")));
+        user_repository
+            .expect_register()
+            .withf(|entity| entity.email == "alice@example.com" && entity.password_hash != "correct horse battery")
+            .times(1)
+            .returning(move |_| Ok(user_id));
+        user_repository.expect_find_by_id().return_once(move |_| Ok(registered));
+        let mut audit_event_repository = MockAuditEventRepository::new();
+        audit_event_repository
+            .expect_create()
+            .times(1)
+            .returning(|_| Err(AppError::not_found("not stored in tests")));
+
+        use_case(user_repository, audit_event_repository, &dir)
+            .register(registration(), &AuditContext::default())
+            .await
+            .unwrap();
+
+        let email = read_single_email(&dir).await;
+        assert!(email.starts_with("To: alice@example.com\nSubject: Verify your email address\n"));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn a_registered_email_gets_a_notice_instead_of_a_second_account() {
+        let dir = mail_dir();
+        let mut user_repository = MockUserRepository::new();
+        user_repository
+            .expect_find_by_username()
+            .returning(|_| Err(AppError::not_found("Resource not found")));
+        user_repository
+            .expect_find_by_email()
+            .returning(|_| Ok(user("alice_original")));
+        user_repository.expect_register().never();
+
+        let result = use_case(user_repository, MockAuditEventRepository::new(), &dir)
+            .register(registration(), &AuditContext::default())
+            .await;
+
+        assert!(result.is_ok());
+        let email = read_single_email(&dir).await;
+        assert!(email.starts_with("To: alice@example.com\nSubject: Someone tried to sign up with your email\n"));
+        assert!(email.contains("\"alice_original\""));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn a_taken_username_is_refused() {
+        let mut user_repository = MockUserRepository::new();
+        user_repository
+            .expect_find_by_username()
+            .returning(|username| Ok(user(&username)));
+        user_repository.expect_find_by_email().never();
+        user_repository.expect_register().never();
+
+        let result = use_case(user_repository, MockAuditEventRepository::new(), &mail_dir())
+            .register(registration(), &AuditContext::default())
+            .await;
+
+        assert!(matches!(result, Err(AppError::Conflict(message)) if message == "Username is already taken"));
     }
 }
@@ -0,0 +1,102 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::domain::{
+    entities::user::{Role, UserSearchFilter, UserStatus},
+    error::{AppError, AppResult},
+    value_object::user::UserModel,
+};
+
+const DEFAULT_PER_PAGE: i64 = 20;
+const MAX_PER_PAGE: i64 = 100;
+
+// Query string for GET /admin/users; dates are inclusive-exclusive, e.g. created_from=2025-01-01T00:00:00.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct UserListQuery {
+    pub q: Option<String>,
+    pub role: Option<Role>,
+    pub status: Option<UserStatus>,
+    pub created_from: Option<NaiveDateTime>,
+    pub created_to: Option<NaiveDateTime>,
+    // Not flattened from `PageQuery`: serde_urlencoded cannot parse numbers through `flatten`.
+    pub page: Option<i64>,
+    pub per_page: Option<i64>,
+}
+
+impl UserListQuery {
+    pub fn page_query(&self) -> PageQuery {
+        PageQuery { page: self.page, per_page: self.per_page }
+    }
+
+    pub fn to_filter(&self) -> AppResult<UserSearchFilter> {
+        if matches!((self.created_from, self.created_to), (Some(from), Some(to)) if from > to) {
+            return Err(AppError::validation("created_from must not be after created_to"));
+        }
+        let (limit, offset) = self.page_query().limit_offset()?;
+        Ok(UserSearchFilter {
+            query: self.q.clone(),
+            role: self.role,
+            status: self.status,
+            created_from: self.created_from,
+            created_to: self.created_to,
+            limit,
+            offset,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct PageQuery {
+    pub page: Option<i64>,
+    pub per_page: Option<i64>,
+}
+
+impl PageQuery {
+    pub fn page(&self) -> i64 {
+        self.page.unwrap_or(1)
+    }
+
+    pub fn per_page(&self) -> i64 {
+        self.per_page.unwrap_or(DEFAULT_PER_PAGE)
+    }
+
+    pub fn limit_offset(&self) -> AppResult<(i64, i64)> {
+        let page = self.page();
+        let per_page = self.per_page();
+        if page < 1 {
+            return Err(AppError::validation("page must be at least 1"));
+        }
+        if !(1..=MAX_PER_PAGE).contains(&per_page) {
+            return Err(AppError::validation(format!("per_page must be between 1 and {}", MAX_PER_PAGE)));
+        }
+        Ok((per_page, (page - 1) * per_page))
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PageModel<T> {
+    pub items: Vec<T>,
+    pub page: i64,
+    pub per_page: i64,
+    pub total: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChangeRoleModel {
+    pub role: Role,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ForceLogoutModel {
+    pub revoked_sessions: usize,
+}
+
+// Returned when an admin starts impersonating; the token itself is only sent as the `act` cookie.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImpersonationModel {
+    pub user: UserModel,
+    pub impersonator_id: Uuid,
+    pub expires_at: NaiveDateTime,
+    pub expires_in: i64,
+}
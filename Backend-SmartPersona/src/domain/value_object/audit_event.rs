@@ -0,0 +1,74 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::domain::{
+    entities::audit_event::{AuditEventEntity, AuditEventFilter},
+    error::{AppError, AppResult},
+    value_object::admin_user::PageQuery,
+};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditEventModel {
+    pub id: Uuid,
+    pub action: String,
+    pub actor_id: Option<Uuid>,
+    pub target_type: Option<String>,
+    pub target_id: Option<Uuid>,
+    pub ip_address: Option<String>,
+    pub user_agent: Option<String>,
+    pub request_id: Option<String>,
+    pub changes: serde_json::Value,
+    pub created_at: NaiveDateTime,
+}
+
+impl From<AuditEventEntity> for AuditEventModel {
+    fn from(entity: AuditEventEntity) -> Self {
+        Self {
+            id: entity.id,
+            action: entity.action,
+            actor_id: entity.actor_id,
+            target_type: entity.target_type,
+            target_id: entity.target_id,
+            ip_address: entity.ip_address,
+            user_agent: entity.user_agent,
+            request_id: entity.request_id,
+            changes: entity.changes,
+            created_at: entity.created_at,
+        }
+    }
+}
+
+// Query string for GET /admin/audit-events; `from` is inclusive and `to` exclusive.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct AuditEventQuery {
+    pub actor_id: Option<Uuid>,
+    pub target_id: Option<Uuid>,
+    pub action: Option<String>,
+    pub from: Option<NaiveDateTime>,
+    pub to: Option<NaiveDateTime>,
+    pub page: Option<i64>,
+    pub per_page: Option<i64>,
+}
+
+impl AuditEventQuery {
+    pub fn page_query(&self) -> PageQuery {
+        PageQuery { page: self.page, per_page: self.per_page }
+    }
+
+    pub fn to_filter(&self) -> AppResult<AuditEventFilter> {
+        if matches!((self.from, self.to), (Some(from), Some(to)) if from > to) {
+            return Err(AppError::validation("from must not be after to"));
+        }
+        let (limit, offset) = self.page_query().limit_offset()?;
+        Ok(AuditEventFilter {
+            actor_id: self.actor_id,
+            target_id: self.target_id,
+            action: self.action.clone().filter(|action| !action.trim().is_empty()),
+            from: self.from,
+            to: self.to,
+            limit,
+            offset,
+        })
+    }
+}
@@ -0,0 +1,34 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::domain::entities::generation_job::{GenerationJobEntity, JobStatus};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GenerationJobModel {
+    pub id: Uuid,
+    pub status: JobStatus,
+    pub result: Option<serde_json::Value>,
+    pub error: Option<String>,
+    pub attempts: i32,
+    pub prompt_template_version_id: Option<Uuid>,
+    pub created_at: NaiveDateTime,
+    pub started_at: Option<NaiveDateTime>,
+    pub completed_at: Option<NaiveDateTime>,
+}
+
+impl From<GenerationJobEntity> for GenerationJobModel {
+    fn from(entity: GenerationJobEntity) -> Self {
+        Self {
+            id: entity.id,
+            status: entity.status,
+            result: entity.result,
+            error: entity.error,
+            attempts: entity.attempts,
+            prompt_template_version_id: entity.prompt_template_version_id,
+            created_at: entity.created_at,
+            started_at: entity.started_at,
+            completed_at: entity.completed_at,
+        }
+    }
+}
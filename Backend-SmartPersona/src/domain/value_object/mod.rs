@@ -1 +1,9 @@
-pub mod user;
\ No newline at end of file
+pub mod user;
+pub mod profile;
+pub mod generation_job;
+pub mod prompt_template;
+pub mod social_connection;
+pub mod permission;
+pub mod personal_data;
+pub mod admin_user;
+pub mod audit_event;
\ No newline at end of file
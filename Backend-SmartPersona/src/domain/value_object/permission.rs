@@ -0,0 +1,69 @@
+use crate::domain::entities::user::Role;
+
+/// Actions a role may perform. Handlers ask for a permission instead of a role so
+/// granting something new to a role only touches `Permission::granted_to`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Permission {
+    ProfileWriteOwn,
+    ProfileReadAny,
+    AiAnalyze,
+    SocialConnect,
+    TemplateRead,
+    TemplateWrite,
+    UserReadAny,
+    UserManageRoles,
+    UserBan,
+    UserUnlock,
+    UserRevokeSessions,
+    AuditRead,
+    UserImpersonate,
+}
+
+const MEMBER_PERMISSIONS: &[Permission] = &[
+    Permission::ProfileWriteOwn,
+    Permission::AiAnalyze,
+    Permission::SocialConnect,
+];
+
+const ADMIN_PERMISSIONS: &[Permission] = &[
+    Permission::ProfileWriteOwn,
+    Permission::ProfileReadAny,
+    Permission::AiAnalyze,
+    Permission::SocialConnect,
+    Permission::TemplateRead,
+    Permission::TemplateWrite,
+    Permission::UserReadAny,
+    Permission::UserManageRoles,
+    Permission::UserBan,
+    Permission::UserUnlock,
+    Permission::UserRevokeSessions,
+    Permission::AuditRead,
+    Permission::UserImpersonate,
+];
+
+impl Permission {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::ProfileWriteOwn => "profile:write_own",
+            Self::ProfileReadAny => "profile:read_any",
+            Self::AiAnalyze => "ai:analyze",
+            Self::SocialConnect => "social:connect",
+            Self::TemplateRead => "template:read",
+            Self::TemplateWrite => "template:write",
+            Self::UserReadAny => "user:read_any",
+            Self::UserManageRoles => "user:manage_roles",
+            Self::UserBan => "user:ban",
+            Self::UserUnlock => "user:unlock",
+            Self::UserRevokeSessions => "user:revoke_sessions",
+            Self::AuditRead => "audit:read",
+            Self::UserImpersonate => "user:impersonate",
+        }
+    }
+
+    pub fn granted_to(role: Role) -> &'static [Permission] {
+        match role {
+            Role::PersonaUser | Role::CompanyUser => MEMBER_PERMISSIONS,
+            Role::Admin => ADMIN_PERMISSIONS,
+        }
+    }
+}
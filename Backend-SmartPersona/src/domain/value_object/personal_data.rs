@@ -0,0 +1,127 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::domain::{
+    entities::{
+        data_export::DataExportEntity,
+        generation_job::{GenerationJobEntity, JobStatus},
+        personal_data::PersonalDataEntity,
+        session::SessionEntity,
+    },
+    value_object::{
+        generation_job::GenerationJobModel, profile::ProfileModel, social_connection::SocialConnectionModel,
+        user::UserModel,
+    },
+};
+
+// Status of an export request; the data itself is fetched from the download endpoint.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DataExportModel {
+    pub id: Uuid,
+    pub status: JobStatus,
+    pub error: Option<String>,
+    pub created_at: NaiveDateTime,
+    pub completed_at: Option<NaiveDateTime>,
+    pub expires_at: Option<NaiveDateTime>,
+}
+
+impl From<DataExportEntity> for DataExportModel {
+    fn from(entity: DataExportEntity) -> Self {
+        Self {
+            id: entity.id,
+            status: entity.status,
+            error: entity.error,
+            created_at: entity.created_at,
+            completed_at: entity.completed_at,
+            expires_at: entity.expires_at,
+        }
+    }
+}
+
+// The archive handed to the user. Each top-level field becomes one file in the ZIP.
+// Chat messages are not stored server-side, so there is no chat history to include.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PersonalDataExportModel {
+    pub exported_at: NaiveDateTime,
+    pub account: ExportedAccountModel,
+    pub profiles: Vec<ProfileModel>,
+    pub social_connections: Vec<SocialConnectionModel>,
+    pub generation_jobs: Vec<ExportedGenerationJobModel>,
+    pub sessions: Vec<ExportedSessionModel>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportedAccountModel {
+    #[serde(flatten)]
+    pub user: UserModel,
+    pub mfa_confirmed_at: Option<NaiveDateTime>,
+}
+
+// Unlike `GenerationJobModel` this includes the prompt and payload the user submitted.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportedGenerationJobModel {
+    #[serde(flatten)]
+    pub job: GenerationJobModel,
+    pub prompt: Option<String>,
+    pub payload: serde_json::Value,
+}
+
+impl From<GenerationJobEntity> for ExportedGenerationJobModel {
+    fn from(entity: GenerationJobEntity) -> Self {
+        Self {
+            prompt: entity.prompt.clone(),
+            payload: entity.payload.clone(),
+            job: GenerationJobModel::from(entity),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportedSessionModel {
+    pub id: Uuid,
+    pub device: Option<String>,
+    pub created_at: NaiveDateTime,
+    pub last_used_at: NaiveDateTime,
+    pub expires_at: NaiveDateTime,
+    pub revoked_at: Option<NaiveDateTime>,
+}
+
+impl From<SessionEntity> for ExportedSessionModel {
+    fn from(entity: SessionEntity) -> Self {
+        Self {
+            id: entity.id,
+            device: entity.device,
+            created_at: entity.created_at,
+            last_used_at: entity.last_used_at,
+            expires_at: entity.expires_at,
+            revoked_at: entity.revoked_at,
+        }
+    }
+}
+
+impl PersonalDataExportModel {
+    pub fn new(entity: PersonalDataEntity, exported_at: NaiveDateTime) -> Self {
+        Self {
+            exported_at,
+            account: ExportedAccountModel {
+                user: UserModel::from(entity.user),
+                mfa_confirmed_at: entity.mfa_confirmed_at,
+            },
+            profiles: entity.profiles.into_iter().map(ProfileModel::from).collect(),
+            social_connections: entity.social_connections.into_iter().map(SocialConnectionModel::from).collect(),
+            generation_jobs: entity.generation_jobs.into_iter().map(ExportedGenerationJobModel::from).collect(),
+            sessions: entity.sessions.into_iter().map(ExportedSessionModel::from).collect(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RequestErasureModel {
+    pub password: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ErasureModel {
+    pub erasure_scheduled_at: NaiveDateTime,
+}
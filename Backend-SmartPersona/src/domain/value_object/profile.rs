@@ -0,0 +1,81 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::domain::entities::profile::{
+    EditProfileEntity, InsertProfileEntity, ProfileEntity, ProfileStatus,
+};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProfileModel {
+    pub id: Uuid,
+    pub owner_id: Uuid,
+    pub status: ProfileStatus,
+    pub content: Option<serde_json::Value>,
+    pub layout_config: Option<serde_json::Value>,
+    pub shareable_link_slug: Option<String>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+impl From<ProfileEntity> for ProfileModel {
+    fn from(entity: ProfileEntity) -> Self {
+        Self {
+            id: entity.id,
+            owner_id: entity.owner_id,
+            status: entity.status,
+            content: entity.content,
+            layout_config: entity.layout_config,
+            shareable_link_slug: entity.shareable_link_slug,
+            created_at: entity.created_at,
+            updated_at: entity.updated_at,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateProfileModel {
+    pub status: Option<ProfileStatus>,
+    pub content: Option<serde_json::Value>,
+    pub layout_config: Option<serde_json::Value>,
+}
+
+impl CreateProfileModel {
+    pub fn to_entity(&self, owner_id: Uuid) -> InsertProfileEntity {
+        InsertProfileEntity {
+            owner_id,
+            status: self.status.unwrap_or(ProfileStatus::Private),
+            content: self.content.clone(),
+            layout_config: self.layout_config.clone(),
+            created_at: chrono::Utc::now().naive_utc(),
+            updated_at: chrono::Utc::now().naive_utc(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EditProfileModel {
+    pub status: Option<ProfileStatus>,
+    pub content: Option<serde_json::Value>,
+    pub layout_config: Option<serde_json::Value>,
+}
+
+impl EditProfileModel {
+    pub fn to_entity(&self) -> EditProfileEntity {
+        EditProfileEntity {
+            status: self.status,
+            content: self.content.clone(),
+            layout_config: self.layout_config.clone(),
+            updated_at: chrono::Utc::now().naive_utc(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PublicProfileModel {
+    pub shareable_link_slug: String,
+    pub display_name: Option<String>,
+    pub content: Option<serde_json::Value>,
+    pub layout_config: Option<serde_json::Value>,
+    pub updated_at: NaiveDateTime,
+}
@@ -0,0 +1,326 @@
+use crate::domain::error::{AppError, AppResult};
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::domain::entities::prompt_template::{
+    EditPromptTemplateEntity, InsertPromptTemplateEntity, PromptTemplateEntity, PromptTemplateVersionEntity,
+};
+
+pub const PERSONALITY_ANALYSIS_TEMPLATE: &str = "personality_analysis";
+
+const PLACEHOLDER_OPEN: &str = "{{";
+const PLACEHOLDER_CLOSE: &str = "}}";
+const MAX_TAG_COUNT: u32 = 20;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PromptTemplateModel {
+    pub id: Uuid,
+    pub name: String,
+    pub active_version: Option<PromptTemplateVersionModel>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+impl PromptTemplateModel {
+    pub fn new(entity: PromptTemplateEntity, active_version: Option<PromptTemplateVersionEntity>) -> Self {
+        Self {
+            id: entity.id,
+            name: entity.name,
+            active_version: active_version.map(PromptTemplateVersionModel::from),
+            created_at: entity.created_at,
+            updated_at: entity.updated_at,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PromptTemplateVersionModel {
+    pub id: Uuid,
+    pub template_id: Uuid,
+    pub version: i32,
+    pub template_text: String,
+    pub placeholders: Vec<String>,
+    pub created_at: NaiveDateTime,
+}
+
+impl From<PromptTemplateVersionEntity> for PromptTemplateVersionModel {
+    fn from(entity: PromptTemplateVersionEntity) -> Self {
+        Self {
+            placeholders: parse_placeholders(&entity.template_text).unwrap_or_default(),
+            id: entity.id,
+            template_id: entity.template_id,
+            version: entity.version,
+            template_text: entity.template_text,
+            created_at: entity.created_at,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreatePromptTemplateModel {
+    pub name: String,
+    pub template_text: String,
+}
+
+impl CreatePromptTemplateModel {
+    pub fn to_entity(&self) -> InsertPromptTemplateEntity {
+        InsertPromptTemplateEntity {
+            name: self.name.clone(),
+            created_at: chrono::Utc::now().naive_utc(),
+            updated_at: chrono::Utc::now().naive_utc(),
+        }
+    }
+}
+
+// A new `template_text` is stored as the next version rather than overwriting the current one.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EditPromptTemplateModel {
+    pub name: Option<String>,
+    pub template_text: Option<String>,
+}
+
+impl EditPromptTemplateModel {
+    pub fn to_entity(&self) -> EditPromptTemplateEntity {
+        EditPromptTemplateEntity {
+            name: self.name.clone(),
+            updated_at: chrono::Utc::now().naive_utc(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RollbackPromptTemplateModel {
+    pub version: i32,
+}
+
+#[derive(Debug, Clone)]
+pub struct RenderedPrompt {
+    pub template_version_id: Uuid,
+    pub text: String,
+}
+
+/// The variables a template may reference as `{{posts}}`, `{{language}}` and `{{tag_count}}`.
+/// A variable left as `None` is missing, and rendering a template that uses it fails.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PromptVariables {
+    pub posts: Option<Vec<String>>,
+    pub language: Option<String>,
+    pub tag_count: Option<u32>,
+}
+
+impl PromptVariables {
+    pub const NAMES: [&'static str; 3] = ["posts", "language", "tag_count"];
+
+    pub fn render(&self, template_text: &str) -> AppResult<String> {
+        let placeholders = validate_template(template_text)?;
+
+        let missing: Vec<&str> = placeholders
+            .iter()
+            .map(String::as_str)
+            .filter(|name| self.value_of(name).is_none())
+            .collect();
+        if !missing.is_empty() {
+            return Err(AppError::validation(format!("Missing prompt variables: {}", missing.join(", "))));
+        }
+
+        self.validate_values()?;
+
+        let mut rendered = String::with_capacity(template_text.len());
+        let mut rest = template_text;
+        while let Some(start) = rest.find(PLACEHOLDER_OPEN) {
+            let after_open = &rest[start + PLACEHOLDER_OPEN.len()..];
+            let end = after_open
+                .find(PLACEHOLDER_CLOSE)
+                .ok_or_else(|| AppError::validation("Unclosed placeholder in prompt template"))?;
+            let name = after_open[..end].trim();
+
+            rendered.push_str(&rest[..start]);
+            rendered.push_str(&self.value_of(name).unwrap_or_default());
+            rest = &after_open[end + PLACEHOLDER_CLOSE.len()..];
+        }
+        rendered.push_str(rest);
+
+        Ok(rendered)
+    }
+
+    fn value_of(&self, name: &str) -> Option<String> {
+        match name {
+            "posts" => self.posts.as_ref().map(|posts| posts.join("\n")),
+            "language" => self.language.clone(),
+            "tag_count" => self.tag_count.map(|tag_count| tag_count.to_string()),
+            _ => None,
+        }
+    }
+
+    fn validate_values(&self) -> AppResult<()> {
+        if let Some(posts) = &self.posts
+            && posts.iter().all(|post| post.trim().is_empty())
+        {
+            return Err(AppError::validation("posts must contain at least one non-empty post"));
+        }
+        if let Some(language) = &self.language
+            && language.trim().is_empty()
+        {
+            return Err(AppError::validation("language must not be empty"));
+        }
+        if let Some(tag_count) = self.tag_count
+            && !(1..=MAX_TAG_COUNT).contains(&tag_count)
+        {
+            return Err(AppError::validation(format!("tag_count must be between 1 and {}", MAX_TAG_COUNT)));
+        }
+        Ok(())
+    }
+}
+
+/// Checks that every placeholder is closed and names a known variable, returning the
+/// distinct placeholder names in order of first use.
+pub fn validate_template(template_text: &str) -> AppResult<Vec<String>> {
+    let placeholders = parse_placeholders(template_text)?;
+
+    let unknown: Vec<&str> = placeholders
+        .iter()
+        .map(String::as_str)
+        .filter(|name| !PromptVariables::NAMES.contains(name))
+        .collect();
+    if !unknown.is_empty() {
+        return Err(AppError::validation(format!("Unknown prompt placeholders: {}", unknown.join(", "))));
+    }
+
+    Ok(placeholders)
+}
+
+fn parse_placeholders(template_text: &str) -> AppResult<Vec<String>> {
+    let mut placeholders: Vec<String> = Vec::new();
+    let mut rest = template_text;
+
+    while let Some(start) = rest.find(PLACEHOLDER_OPEN) {
+        let after_open = &rest[start + PLACEHOLDER_OPEN.len()..];
+        let end = after_open
+            .find(PLACEHOLDER_CLOSE)
+            .ok_or_else(|| AppError::validation("Unclosed placeholder in prompt template"))?;
+        let name = after_open[..end].trim().to_string();
+
+        if name.is_empty() {
+            return Err(AppError::validation("Empty placeholder in prompt template"));
+        }
+        if !placeholders.contains(&name) {
+            placeholders.push(name);
+        }
+        rest = &after_open[end + PLACEHOLDER_CLOSE.len()..];
+    }
+
+    Ok(placeholders)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn variables() -> PromptVariables {
+        PromptVariables {
+            posts: Some(vec!["Went diving again".to_string(), "New camera!".to_string()]),
+            language: Some("Thai".to_string()),
+            tag_count: Some(5),
+        }
+    }
+
+    fn validation_message(result: AppResult<impl std::fmt::Debug>) -> String {
+        match result {
+            Err(AppError::Validation { message, .. }) => message,
+            other => panic!("expected a validation error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn renders_every_placeholder() {
+        let rendered = variables()
+            .render("Give {{ tag_count }} tags in {{language}} for:\n{{posts}}")
+            .unwrap();
+
+        assert_eq!(rendered, "Give 5 tags in Thai for:\nWent diving again\nNew camera!");
+    }
+
+    #[test]
+    fn rejects_missing_variables() {
+        let variables = PromptVariables {
+            language: None,
+            tag_count: None,
+            ..variables()
+        };
+
+        let message = validation_message(variables.render("{{posts}} {{language}} {{tag_count}}"));
+
+        assert_eq!(message, "Missing prompt variables: language, tag_count");
+    }
+
+    #[test]
+    fn rejects_unknown_placeholders() {
+        let message = validation_message(validate_template("Hello {{name}}, {{posts}} {{ mood }}"));
+        assert_eq!(message, "Unknown prompt placeholders: name, mood");
+
+        let message = validation_message(variables().render("Hello {{name}}"));
+        assert_eq!(message, "Unknown prompt placeholders: name");
+    }
+
+    #[test]
+    fn rejects_unclosed_and_empty_placeholders() {
+        assert_eq!(
+            validation_message(validate_template("Describe {{posts")),
+            "Unclosed placeholder in prompt template"
+        );
+        assert_eq!(
+            validation_message(validate_template("Describe {{}} and {{posts}}")),
+            "Empty placeholder in prompt template"
+        );
+        assert_eq!(
+            validation_message(validate_template("Describe {{  }}")),
+            "Empty placeholder in prompt template"
+        );
+    }
+
+    #[test]
+    fn does_not_expand_placeholders_inside_values() {
+        let variables = PromptVariables {
+            posts: Some(vec!["I typed {{language}} and {{tag_count}} on purpose".to_string()]),
+            ..variables()
+        };
+
+        let rendered = variables.render("{{language}}: {{posts}}").unwrap();
+
+        assert_eq!(rendered, "Thai: I typed {{language}} and {{tag_count}} on purpose");
+    }
+
+    #[test]
+    fn renders_what_validation_accepts() {
+        let template_text = "{{posts}}\n---\nAnswer in {{language}} with {{tag_count}} tags. {{posts}}";
+
+        let placeholders = validate_template(template_text).unwrap();
+        assert_eq!(placeholders, vec!["posts", "language", "tag_count"]);
+
+        let rendered = variables().render(template_text).unwrap();
+        assert!(!rendered.contains(PLACEHOLDER_OPEN));
+        assert!(validate_template(&rendered).unwrap().is_empty());
+    }
+
+    #[test]
+    fn rejects_values_outside_their_limits() {
+        let blank_posts = PromptVariables {
+            posts: Some(vec!["  ".to_string()]),
+            ..variables()
+        };
+        assert_eq!(
+            validation_message(blank_posts.render("{{posts}}")),
+            "posts must contain at least one non-empty post"
+        );
+
+        let too_many_tags = PromptVariables {
+            tag_count: Some(MAX_TAG_COUNT + 1),
+            ..variables()
+        };
+        assert_eq!(
+            validation_message(too_many_tags.render("{{tag_count}}")),
+            format!("tag_count must be between 1 and {}", MAX_TAG_COUNT)
+        );
+    }
+}
@@ -0,0 +1,57 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::domain::entities::social_connection::{InsertSocialConnectionEntity, SocialConnectionEntity};
+
+// Tokens are write-only through the API and never echoed back.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SocialConnectionModel {
+    pub id: Uuid,
+    pub platform: String,
+    pub platform_user_id: String,
+    pub expires_at: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+    // When set the token can no longer be refreshed and the account must be linked again.
+    pub broken_at: Option<NaiveDateTime>,
+}
+
+impl From<SocialConnectionEntity> for SocialConnectionModel {
+    fn from(entity: SocialConnectionEntity) -> Self {
+        Self {
+            id: entity.id,
+            platform: entity.platform,
+            platform_user_id: entity.platform_user_id,
+            expires_at: entity.expires_at,
+            created_at: entity.created_at,
+            broken_at: entity.broken_at,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LinkSocialConnectionModel {
+    pub platform: String,
+    pub platform_user_id: String,
+    pub access_token: String,
+    pub refresh_token: Option<String>,
+    pub expires_at: Option<NaiveDateTime>,
+}
+
+impl LinkSocialConnectionModel {
+    pub fn to_entity(&self, user_id: Uuid) -> InsertSocialConnectionEntity {
+        InsertSocialConnectionEntity {
+            user_id,
+            platform: normalize_platform(&self.platform),
+            platform_user_id: self.platform_user_id.clone(),
+            access_token: self.access_token.clone(),
+            refresh_token: self.refresh_token.clone(),
+            expires_at: self.expires_at,
+            created_at: chrono::Utc::now().naive_utc(),
+        }
+    }
+}
+
+pub fn normalize_platform(platform: &str) -> String {
+    platform.trim().to_lowercase()
+}
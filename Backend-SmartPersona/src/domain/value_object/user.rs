@@ -1,23 +1,260 @@
+use std::collections::BTreeMap;
+
+use chrono::NaiveDateTime;
 use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{
+    domain::{
+        entities::user::{RegisterUserEntity, Role, UpdateUserEntity, UserEntity, UserStatus},
+        error::{AppError, AppResult},
+    },
+    infrastructure::password_policy::PasswordValidator,
+};
+
+const USERNAME_MIN_LENGTH: usize = 3;
+const USERNAME_MAX_LENGTH: usize = 32;
+const NAME_MAX_LENGTH: usize = 100;
+const EMAIL_MAX_LENGTH: usize = 255;
+
+// What a user sees about their own account; never includes the password hash.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UserModel {
+    pub id: Uuid,
+    pub username: String,
+    pub email: Option<String>,
+    pub display_name: Option<String>,
+    pub firstname: String,
+    pub lastname: String,
+    pub role: Role,
+    pub status: UserStatus,
+    // Set while a requested account erasure can still be cancelled.
+    pub erasure_scheduled_at: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
 
-use crate::domain::entities::user::RegisterUserEntity;
+impl From<UserEntity> for UserModel {
+    fn from(entity: UserEntity) -> Self {
+        Self {
+            id: entity.id,
+            username: entity.username,
+            email: entity.email,
+            display_name: entity.display_name,
+            firstname: entity.first_name,
+            lastname: entity.last_name,
+            role: entity.role,
+            status: entity.status,
+            erasure_scheduled_at: entity.erasure_scheduled_at,
+            created_at: entity.created_at,
+            updated_at: entity.updated_at,
+        }
+    }
+}
 
 #[derive(Debug, Clone, Serialize,Deserialize)]
 pub struct  RegisterUserModel {
     pub username : String,
+    pub email : String,
     pub firstname : String,
     pub lastname : String,
     pub password : String,
 }
 impl RegisterUserModel {
+    // Collects every problem per field so the client can show them all at once.
+    pub fn validate(&self, password_validator: &PasswordValidator) -> AppResult<()> {
+        let mut errors: BTreeMap<&'static str, Vec<String>> = BTreeMap::new();
+
+        let username_problems = username_problems(&self.username);
+        if !username_problems.is_empty() {
+            errors.insert("username", username_problems);
+        }
+        if !is_valid_email(self.email.trim()) {
+            errors.insert("email", vec!["must be a valid email address".to_string()]);
+        }
+        for (field, name) in [("firstname", &self.firstname), ("lastname", &self.lastname)] {
+            let length = name.trim().chars().count();
+            if length == 0 {
+                errors.insert(field, vec!["is required".to_string()]);
+            } else if length > NAME_MAX_LENGTH {
+                errors.insert(field, vec![format!("must be at most {} characters", NAME_MAX_LENGTH)]);
+            }
+        }
+        let password_problems = password_validator.check(&self.password, Some(&self.username));
+        if !password_problems.is_empty() {
+            errors.insert("password", password_problems);
+        }
+
+        if errors.is_empty() {
+            return Ok(());
+        }
+        Err(AppError::invalid_fields("Invalid registration details", serde_json::to_value(errors)?))
+    }
+
     pub fn to_entity(&self) -> RegisterUserEntity {
         RegisterUserEntity {
             username: self.username.clone(),
-            first_name: self.firstname.clone(),
-            last_name: self.lastname.clone(),
+            email: self.email.trim().to_lowercase(),
+            first_name: self.firstname.trim().to_string(),
+            last_name: self.lastname.trim().to_string(),
             password_hash: self.password.clone(),
             created_at: chrono::Utc::now().naive_utc(),
             updated_at: chrono::Utc::now().naive_utc(),
         }
     }
-}
\ No newline at end of file
+}
+// Omitted fields are left unchanged; an empty `display_name` clears it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpdateUserModel {
+    pub display_name: Option<String>,
+    pub firstname: Option<String>,
+    pub lastname: Option<String>,
+}
+
+impl UpdateUserModel {
+    pub fn validate(&self) -> AppResult<()> {
+        let mut errors: BTreeMap<&'static str, Vec<String>> = BTreeMap::new();
+
+        if let Some(display_name) = &self.display_name
+            && display_name.trim().chars().count() > NAME_MAX_LENGTH
+        {
+            errors.insert("display_name", vec![format!("must be at most {} characters", NAME_MAX_LENGTH)]);
+        }
+        for (field, name) in [("firstname", &self.firstname), ("lastname", &self.lastname)] {
+            let Some(name) = name else { continue };
+            let length = name.trim().chars().count();
+            if length == 0 {
+                errors.insert(field, vec!["must not be empty".to_string()]);
+            } else if length > NAME_MAX_LENGTH {
+                errors.insert(field, vec![format!("must be at most {} characters", NAME_MAX_LENGTH)]);
+            }
+        }
+
+        if errors.is_empty() {
+            return Ok(());
+        }
+        Err(AppError::invalid_fields("Invalid account details", serde_json::to_value(errors)?))
+    }
+
+    pub fn to_entity(&self) -> UpdateUserEntity {
+        UpdateUserEntity {
+            display_name: self.display_name.as_ref().map(|display_name| {
+                Some(display_name.trim().to_string()).filter(|display_name| !display_name.is_empty())
+            }),
+            first_name: self.firstname.as_ref().map(|name| name.trim().to_string()),
+            last_name: self.lastname.as_ref().map(|name| name.trim().to_string()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChangePasswordModel {
+    pub current_password: String,
+    pub new_password: String,
+}
+
+// Letters, digits, `_`, `.` and `-`, starting with a letter or digit.
+fn username_problems(username: &str) -> Vec<String> {
+    let mut problems = Vec::new();
+    let length = username.chars().count();
+    if !(USERNAME_MIN_LENGTH..=USERNAME_MAX_LENGTH).contains(&length) {
+        problems.push(format!("must be {} to {} characters", USERNAME_MIN_LENGTH, USERNAME_MAX_LENGTH));
+    }
+    if !username.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '.' | '-')) {
+        problems.push("may only contain letters, digits, '_', '.' and '-'".to_string());
+    }
+    if !username.starts_with(|c: char| c.is_ascii_alphanumeric()) {
+        problems.push("must start with a letter or digit".to_string());
+    }
+    problems
+}
+
+fn is_valid_email(email: &str) -> bool {
+    let Some((local, domain)) = email.split_once('@') else {
+        return false;
+    };
+    email.len() <= EMAIL_MAX_LENGTH
+        && !local.is_empty()
+        && !domain.contains('@')
+        && !email.chars().any(char::is_whitespace)
+        && domain.split('.').count() >= 2
+        && domain.split('.').all(|label| !label.is_empty())
+}
+
+// `token` comes from the link in the verification email.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VerifyEmailModel {
+    pub token: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResendVerificationModel {
+    pub email: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn accepts_usernames_within_the_rules() {
+        for username in ["abc", "alice", "Alice_01", "a.b-c", "1st", &"a".repeat(USERNAME_MAX_LENGTH)] {
+            assert!(username_problems(username).is_empty(), "{username}");
+        }
+    }
+
+    #[test]
+    fn rejects_usernames_outside_the_length_limits() {
+        let expected = vec![format!("must be {} to {} characters", USERNAME_MIN_LENGTH, USERNAME_MAX_LENGTH)];
+        assert_eq!(username_problems("ab"), expected);
+        assert_eq!(username_problems(&"a".repeat(USERNAME_MAX_LENGTH + 1)), expected);
+    }
+
+    #[test]
+    fn rejects_usernames_with_other_characters() {
+        assert_eq!(
+            username_problems("alice smith"),
+            vec!["may only contain letters, digits, '_', '.' and '-'"]
+        );
+        assert_eq!(
+            username_problems("ålice"),
+            vec!["may only contain letters, digits, '_', '.' and '-'", "must start with a letter or digit"]
+        );
+        assert_eq!(username_problems("_alice"), vec!["must start with a letter or digit"]);
+        assert_eq!(username_problems("").len(), 2);
+    }
+
+    #[test]
+    fn accepts_ordinary_emails() {
+        for email in ["alice@example.com", "a.b+tag@mail.example.co.th", "x@y.z"] {
+            assert!(is_valid_email(email), "{email}");
+        }
+    }
+
+    #[test]
+    fn rejects_malformed_emails() {
+        for email in [
+            "",
+            "alice",
+            "@example.com",
+            "alice@",
+            "alice@localhost",
+            "alice@example..com",
+            "alice@.example.com",
+            "alice@example.com.",
+            "alice@@example.com",
+            "alice@ex@ample.com",
+            "alice smith@example.com",
+        ] {
+            assert!(!is_valid_email(email), "{email}");
+        }
+    }
+
+    #[test]
+    fn rejects_emails_over_the_length_limit() {
+        let domain = "example.com";
+        let local = "a".repeat(EMAIL_MAX_LENGTH - domain.len());
+        assert!(is_valid_email(&format!("{}@{}", &local[1..], domain)));
+        assert!(!is_valid_email(&format!("{}@{}", local, domain)));
+    }
+}
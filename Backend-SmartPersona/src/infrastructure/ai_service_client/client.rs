@@ -1,8 +1,8 @@
 use async_trait::async_trait;
-use anyhow::{Result, Context};
 use reqwest::Client;
 
 use crate::domain::{
+    error::{AppError, AppResult},
     entities::ai_analysis::{AIAnalysisRequest, AIAnalysisResponse, ChatRequest, ChatResponse},
     repo::ai_service::AIServiceRepository,
 };
@@ -25,7 +25,7 @@ impl AIServiceClient {
 
 #[async_trait]
 impl AIServiceRepository for AIServiceClient {
-    async fn analyze_personality(&self, request: AIAnalysisRequest) -> Result<AIAnalysisResponse> {
+    async fn analyze_personality(&self, request: AIAnalysisRequest) -> AppResult<AIAnalysisResponse> {
         let url = format!("{}/analyze-personality", self.base_url);
 
         let response = self.client
@@ -33,21 +33,21 @@ impl AIServiceRepository for AIServiceClient {
             .json(&request)
             .send()
             .await
-            .context("Failed to send request to AI service")?;
+            .map_err(|e| AppError::upstream(format!("Failed to send request to AI service: {}", e)))?;
 
         if response.status().is_success() {
             let result = response
                 .json::<AIAnalysisResponse>()
                 .await
-                .context("Failed to deserialize AI service success response")?;
+                .map_err(|e| AppError::upstream(format!("Failed to deserialize AI service success response: {}", e)))?;
             Ok(result)
         } else {
-            let error_body = response.text().await.context("Failed to read AI service error body")?;
-            Err(anyhow::anyhow!("AI service returned an error: {}", error_body))
+            let error_body = response.text().await.map_err(|e| AppError::upstream(format!("Failed to read AI service error body: {}", e)))?;
+            Err(AppError::upstream(format!("AI service returned an error: {}", error_body)))
         }
     }
 
-    async fn chat(&self, request: ChatRequest) -> Result<ChatResponse> {
+    async fn chat(&self, request: ChatRequest) -> AppResult<ChatResponse> {
         let url = format!("{}/chat", self.base_url);
 
         let response = self.client
@@ -55,17 +55,17 @@ impl AIServiceRepository for AIServiceClient {
             .json(&request)
             .send()
             .await
-            .context("Failed to send chat request to AI service")?;
+            .map_err(|e| AppError::upstream(format!("Failed to send chat request to AI service: {}", e)))?;
 
         if response.status().is_success() {
             let result = response
                 .json::<ChatResponse>()
                 .await
-                .context("Failed to deserialize chat service success response")?;
+                .map_err(|e| AppError::upstream(format!("Failed to deserialize chat service success response: {}", e)))?;
             Ok(result)
         } else {
-            let error_body = response.text().await.context("Failed to read chat service error body")?;
-            Err(anyhow::anyhow!("Chat service returned an error: {}", error_body))
+            let error_body = response.text().await.map_err(|e| AppError::upstream(format!("Failed to read chat service error body: {}", e)))?;
+            Err(AppError::upstream(format!("Chat service returned an error: {}", error_body)))
         }
     }
 }
@@ -0,0 +1,39 @@
+use axum_extra::extract::cookie::{Cookie, SameSite};
+use cookie::time::Duration;
+
+use crate::config::{config_loader::get_stage, stage::Stage};
+
+/// An HTTP-only, site-wide cookie for a token, marked `Secure` in production. An empty value
+/// with a zero `max_age` clears the cookie. Send it through a `CookieJar`, which encodes the
+/// value into a valid `Set-Cookie` header.
+pub fn token_cookie(name: &'static str, value: String, max_age: Duration) -> Cookie<'static> {
+    Cookie::build((name, value))
+        .path("/")
+        .same_site(SameSite::Lax)
+        .http_only(true)
+        .max_age(max_age)
+        .secure(get_stage() == Stage::Production)
+        .build()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn token_cookies_are_http_only_and_site_wide() {
+        let cookie = token_cookie("act", "token".to_string(), Duration::seconds(60));
+        assert_eq!(cookie.name(), "act");
+        assert_eq!(cookie.value(), "token");
+        assert_eq!(cookie.path(), Some("/"));
+        assert_eq!(cookie.same_site(), Some(SameSite::Lax));
+        assert_eq!(cookie.http_only(), Some(true));
+        assert_eq!(cookie.max_age(), Some(Duration::seconds(60)));
+    }
+
+    #[test]
+    fn values_are_encoded_for_the_header() {
+        let cookie = token_cookie("act", "a b\n".to_string(), Duration::ZERO);
+        assert_eq!(cookie.encoded().to_string().split(';').next(), Some("act=a%20b%0A"));
+    }
+}
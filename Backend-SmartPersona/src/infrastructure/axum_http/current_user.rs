@@ -0,0 +1,107 @@
+use std::marker::PhantomData;
+
+use axum::{async_trait, extract::FromRequestParts, http::request::Parts};
+use uuid::Uuid;
+
+use crate::domain::{entities::user::Role, error::AppError, value_object::permission::Permission};
+
+/// The authenticated caller, put into the request by `user_authorization` or
+/// `admin_authorization`.
+#[derive(Debug, Clone)]
+pub struct CurrentUser {
+    pub id: Uuid,
+    pub role: Role,
+    pub session_id: Uuid,
+    // The admin acting as this user, when the request carries an impersonation token.
+    pub impersonator_id: Option<Uuid>,
+}
+
+impl CurrentUser {
+    pub fn is_impersonated(&self) -> bool {
+        self.impersonator_id.is_some()
+    }
+
+    pub fn has_permission(&self, permission: Permission) -> bool {
+        Permission::granted_to(self.role).contains(&permission)
+    }
+
+    pub fn require(&self, permission: Permission) -> Result<(), AppError> {
+        if self.has_permission(permission) {
+            Ok(())
+        } else {
+            Err(AppError::forbidden(format!("Missing permission {}", permission.as_str())))
+        }
+    }
+}
+
+#[async_trait]
+impl<S> FromRequestParts<S> for CurrentUser
+where
+    S: Send + Sync,
+{
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        parts
+            .extensions
+            .get::<CurrentUser>()
+            .cloned()
+            .ok_or_else(|| AppError::unauthorized("Authentication required"))
+    }
+}
+
+/// Type-level permission for `RequirePermission`, see the markers in `permissions`.
+pub trait PermissionMarker {
+    const PERMISSION: Permission;
+}
+
+/// Extracts the current user and rejects with 403 unless their role grants `P`.
+pub struct RequirePermission<P: PermissionMarker>(pub CurrentUser, PhantomData<P>);
+
+#[async_trait]
+impl<S, P> FromRequestParts<S> for RequirePermission<P>
+where
+    S: Send + Sync,
+    P: PermissionMarker,
+{
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let current_user = CurrentUser::from_request_parts(parts, state).await?;
+        current_user.require(P::PERMISSION)?;
+        Ok(Self(current_user, PhantomData))
+    }
+}
+
+macro_rules! permission_markers {
+    ($($marker:ident),* $(,)?) => {
+        pub mod permissions {
+            use super::PermissionMarker;
+            use crate::domain::value_object::permission::Permission;
+
+            $(
+                pub struct $marker;
+
+                impl PermissionMarker for $marker {
+                    const PERMISSION: Permission = Permission::$marker;
+                }
+            )*
+        }
+    };
+}
+
+permission_markers!(
+    ProfileWriteOwn,
+    ProfileReadAny,
+    AiAnalyze,
+    SocialConnect,
+    TemplateRead,
+    TemplateWrite,
+    UserReadAny,
+    UserManageRoles,
+    UserBan,
+    UserUnlock,
+    UserRevokeSessions,
+    AuditRead,
+    UserImpersonate,
+);
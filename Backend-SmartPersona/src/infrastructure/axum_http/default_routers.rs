@@ -1,6 +1,9 @@
 use axum::{http::StatusCode, response::IntoResponse};
+
+use crate::domain::error::AppError;
+
 pub async fn not_found() -> impl IntoResponse {
-    (StatusCode::NOT_FOUND,"NOT_FOUND".into_response())
+    AppError::not_found("Route not found")
 }
 pub async fn health_check() -> impl IntoResponse {
     (StatusCode::OK,"OK".into_response())
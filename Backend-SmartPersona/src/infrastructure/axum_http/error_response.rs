@@ -0,0 +1,151 @@
+use axum::{
+    http::{header, HeaderValue, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde::Serialize;
+use serde_json::Value;
+use tracing::error;
+
+use crate::{domain::error::AppError, infrastructure::axum_http::middleware::current_request_id};
+
+#[derive(Serialize)]
+struct ErrorBody {
+    code: &'static str,
+    message: String,
+    details: Option<Value>,
+    request_id: Option<String>,
+}
+
+impl IntoResponse for AppError {
+    fn into_response(self) -> Response {
+        let request_id = current_request_id();
+        let status = match &self {
+            AppError::NotFound(_) => StatusCode::NOT_FOUND,
+            AppError::Conflict(_) => StatusCode::CONFLICT,
+            AppError::Validation { .. } => StatusCode::UNPROCESSABLE_ENTITY,
+            AppError::Unauthorized(_) => StatusCode::UNAUTHORIZED,
+            AppError::Forbidden(_) | AppError::AccountInactive(_) => StatusCode::FORBIDDEN,
+            AppError::TooManyRequests { .. } => StatusCode::TOO_MANY_REQUESTS,
+            AppError::Upstream(_) => StatusCode::BAD_GATEWAY,
+            AppError::Internal(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+
+        let code = self.code();
+        let retry_after = match &self {
+            AppError::TooManyRequests { retry_after, .. } => *retry_after,
+            _ => None,
+        };
+        let (message, details) = match self {
+            AppError::Validation { message, details } => (message, details),
+            AppError::Internal(e) => {
+                error!(request_id = request_id.as_deref().unwrap_or("-"), "internal error: {:#}", e);
+                ("Internal server error".to_string(), None)
+            }
+            AppError::Upstream(message) => {
+                error!(request_id = request_id.as_deref().unwrap_or("-"), "upstream error: {}", message);
+                ("Upstream service failed".to_string(), None)
+            }
+            other => (other.to_string(), None),
+        };
+
+        let mut response = (
+            status,
+            Json(ErrorBody {
+                code,
+                message,
+                details,
+                request_id,
+            }),
+        )
+            .into_response();
+        if let Some(retry_after) = retry_after {
+            response
+                .headers_mut()
+                .insert(header::RETRY_AFTER, HeaderValue::from(retry_after));
+        }
+        response
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{middleware, routing::get, Router};
+    use diesel::result::{DatabaseErrorKind, Error as DieselError};
+    use tokio::net::TcpListener;
+
+    use super::*;
+    use crate::infrastructure::axum_http::middleware::request_id;
+
+    async fn body(error: AppError) -> (StatusCode, Value) {
+        let response = error.into_response();
+        let status = response.status();
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (status, serde_json::from_slice(&bytes).unwrap())
+    }
+
+    fn database_error(kind: DatabaseErrorKind) -> AppError {
+        DieselError::DatabaseError(kind, Box::new("violates constraint profiles_owner_id_fkey".to_string())).into()
+    }
+
+    #[tokio::test]
+    async fn reports_a_missing_resource_as_not_found() {
+        let (status, body) = body(DieselError::NotFound.into()).await;
+
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert_eq!(body["code"], "not_found");
+        assert_eq!(body["message"], "Resource not found");
+    }
+
+    #[tokio::test]
+    async fn reports_constraint_violations_as_conflicts() {
+        for kind in [DatabaseErrorKind::UniqueViolation, DatabaseErrorKind::ForeignKeyViolation] {
+            let (status, body) = body(database_error(kind)).await;
+
+            assert_eq!(status, StatusCode::CONFLICT);
+            assert_eq!(body["code"], "conflict");
+            assert!(!body["message"].as_str().unwrap().contains("profiles_owner_id_fkey"));
+        }
+    }
+
+    #[tokio::test]
+    async fn hides_the_details_of_internal_errors() {
+        let (status, body) = body(database_error(DatabaseErrorKind::CheckViolation)).await;
+
+        assert_eq!(status, StatusCode::INTERNAL_SERVER_ERROR);
+        assert_eq!(body["code"], "internal_error");
+        assert_eq!(body["message"], "Internal server error");
+        assert_eq!(body["details"], Value::Null);
+        assert!(!body.to_string().contains("profiles_owner_id_fkey"));
+    }
+
+    #[tokio::test]
+    async fn leaves_the_request_id_empty_outside_a_request() {
+        let (_, body) = body(AppError::not_found("Job not found")).await;
+
+        assert_eq!(body["request_id"], Value::Null);
+    }
+
+    #[tokio::test]
+    async fn includes_the_request_id_of_the_request() {
+        let app = Router::new()
+            .route("/jobs", get(|| async { Err::<(), _>(AppError::not_found("Job not found")) }))
+            .layer(middleware::from_fn(request_id));
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        let response = reqwest::Client::new()
+            .get(format!("http://{}/jobs", address))
+            .header("x-request-id", "req-42")
+            .send()
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), reqwest::StatusCode::NOT_FOUND);
+        assert_eq!(response.headers()["x-request-id"], "req-42");
+        let body: Value = response.json().await.unwrap();
+        assert_eq!(body["code"], "not_found");
+        assert_eq!(body["request_id"], "req-42");
+    }
+}
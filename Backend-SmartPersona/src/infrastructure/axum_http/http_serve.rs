@@ -1,31 +1,140 @@
-use std::{net::SocketAddr, sync::Arc, time::Duration};
+use std::{net::SocketAddr, path::PathBuf, sync::Arc, time::Duration};
 
 use anyhow::Result;
-use axum::{http::{self, Method}, routing::{get, post}, Router};
+use axum::{http::{self, Method}, middleware, routing::get, Extension, Router};
 use tokio::net::TcpListener;
-use tower_http::{cors::{Any, CorsLayer}, limit::RequestBodyLimitLayer, timeout::TimeoutLayer, trace::{ TraceLayer}};
+use tower_http::{cors::CorsLayer, limit::RequestBodyLimitLayer, timeout::TimeoutLayer, trace::{ TraceLayer}};
 use tracing::info;
 
 use crate::{
-    config::config_model::Config as DotEnvyConfig,
-    domain::usecase::ai_analysis::AIAnalysisUseCase,
+    config::config_model::{Config as DotEnvyConfig, SocialImport},
+    domain::{
+        service::audit_logger::AuditLogger,
+        usecase::{
+            ai_analysis::{AIAnalysisUseCase, SharedSocialPostSource},
+            generation_job::GenerationJobUseCase,
+            privacy::{PrivacySettings, PrivacyUseCase},
+            social_connection_refresh::{SharedTokenRefresher, SocialConnectionRefreshUseCase},
+        },
+    },
     infrastructure::{
         ai_service_client::client::AIServiceClient,
-        axum_http::{ default_routers, routers::{self, ai_handlers}},
-        postgres::postgres_connection::DbPool,
+        axum_http::{ default_routers, middleware::request_id, request_context::ClientIpPolicy, routers::{self, ai_handlers}},
+        job_worker,
+        jwt_authentication::jwt_keys::JwtKeys,
+        mailer,
+        password_policy::PasswordValidator,
+        oauth_token_refresher::OAuthTokenRefresher,
+        social_post_sources::{facebook::FacebookPostSource, local_file::LocalFilePostSource, x::XPostSource},
+        token_encryption::EnvelopeCipher,
+        postgres::{
+            job_events::GenerationJobEvents,
+            postgres_connection::DbPool,
+            repositories::{
+                audit_event::AuditEventPostgres, data_export::DataExportPostgres, generation_job::GenerationJobPostgres,
+                personal_data::PersonalDataPostgres, prompt_template::PromptTemplatePostgres,
+                session::SessionPostgres, social_connection::SocialConnectionPostgres, user::UserPostgres,
+            },
+        },
     },
 };
 
 pub async fn start(config: Arc<DotEnvyConfig>, db_pool: Arc<DbPool>) -> Result<()> {
+    let token_cipher = Arc::new(EnvelopeCipher::new(&config.token_encryption)?);
+    let jwt_keys = Arc::new(JwtKeys::load(&config.jwt)?);
+    let mailer = mailer::from_config(&config.mail)?;
+    let password_validator = Arc::new(PasswordValidator::new(&config.password_policy)?);
+    let audit_logger = Arc::new(AuditLogger::new(Arc::new(AuditEventPostgres::new(Arc::clone(&db_pool)))));
     let ai_service_client = Arc::new(AIServiceClient::new("http://localhost:8001".to_string()));
-    let ai_analysis_use_case = Arc::new(AIAnalysisUseCase::new(ai_service_client));
+    let prompt_template_repository = Arc::new(PromptTemplatePostgres::new(Arc::clone(&db_pool)));
+    let social_connection_repository = Arc::new(SocialConnectionPostgres::new(Arc::clone(&db_pool), Arc::clone(&token_cipher)));
+    let encrypted = social_connection_repository.encrypt_plaintext_tokens().await?;
+    if encrypted > 0 {
+        info!("Encrypted tokens of {} social connections linked before encryption", encrypted);
+    }
+    let ai_analysis_use_case = Arc::new(AIAnalysisUseCase::new(
+        ai_service_client,
+        prompt_template_repository,
+        Arc::clone(&social_connection_repository),
+        social_post_sources(&config.social_import),
+        config.social_import.max_posts,
+    ));
+
+    let generation_job_repository = Arc::new(GenerationJobPostgres::new(Arc::clone(&db_pool)));
+    let generation_job_use_case = Arc::new(GenerationJobUseCase::new(
+        generation_job_repository,
+        Arc::clone(&ai_analysis_use_case),
+    ));
+    job_worker::spawn(Arc::clone(&generation_job_use_case), config.worker.clone());
+    let job_events = Arc::new(GenerationJobEvents::listen(config.database.url.clone()));
+
+    let token_refreshers = config
+        .oauth
+        .providers
+        .iter()
+        .map(|provider| Arc::new(OAuthTokenRefresher::new(provider.clone())) as SharedTokenRefresher)
+        .collect();
+    let social_connection_refresh_use_case = Arc::new(SocialConnectionRefreshUseCase::new(
+        social_connection_repository,
+        token_refreshers,
+        config.token_refresh.max_failures,
+    ));
+    job_worker::spawn_token_refresh(social_connection_refresh_use_case, config.token_refresh.clone());
+
+    let privacy_use_case = Arc::new(PrivacyUseCase::new(
+        Arc::new(UserPostgres::new(Arc::clone(&db_pool))),
+        Arc::new(SessionPostgres::new(Arc::clone(&db_pool))),
+        Arc::new(DataExportPostgres::new(Arc::clone(&db_pool))),
+        Arc::new(PersonalDataPostgres::new(Arc::clone(&db_pool))),
+        Arc::clone(&audit_logger),
+        PrivacySettings {
+            export_retention: config.privacy.export_retention,
+            export_lease: config.privacy.export_lease,
+            erasure_grace_period: config.privacy.erasure_grace_period,
+        },
+    ));
+    job_worker::spawn_privacy(Arc::clone(&privacy_use_case), config.privacy.clone());
 
     let app = Router::new()
         .fallback(default_routers::not_found)
-        .nest("/users", routers::user::routes(Arc::clone(&db_pool)))
-        .nest("/authentication", routers::authentication::routes(Arc::clone(&db_pool)))
+        .nest(
+            "/users",
+            routers::user::routes(
+                Arc::clone(&db_pool),
+                Arc::clone(&jwt_keys),
+                Arc::clone(&mailer),
+                Arc::clone(&password_validator),
+                Arc::clone(&audit_logger),
+                Arc::clone(&privacy_use_case),
+                &config,
+            ),
+        )
+        .nest(
+            "/authentication",
+            routers::authentication::routes(
+                Arc::clone(&db_pool),
+                Arc::clone(&jwt_keys),
+                Arc::clone(&token_cipher),
+                Arc::clone(&mailer),
+                Arc::clone(&password_validator),
+                Arc::clone(&audit_logger),
+                &config,
+            ),
+        )
+        .nest("/privacy", routers::privacy::routes(privacy_use_case))
+        .nest("/profiles", routers::profile::routes(Arc::clone(&db_pool), Arc::clone(&audit_logger)))
+        .nest("/p", routers::profile::public_routes(Arc::clone(&db_pool), Arc::clone(&audit_logger)))
+        .nest("/admin", routers::admin::routes(Arc::clone(&db_pool), Arc::clone(&jwt_keys), audit_logger, &config))
+        .nest("/social-connections", routers::social_connection::routes(Arc::clone(&db_pool), Arc::clone(&token_cipher)))
+        .nest("/.well-known", routers::well_known::routes(Arc::clone(&jwt_keys)))
         .route("/health-check", get(default_routers::health_check))
-        .nest("/api/ai", ai_handlers::routes(ai_analysis_use_case))
+        .nest("/api/ai", ai_handlers::routes(ai_analysis_use_case, Arc::clone(&generation_job_use_case)))
+        .nest("/jobs", routers::generation_job::routes(generation_job_use_case, job_events))
+        // Read by the authorization middleware to reject tokens of revoked sessions.
+        .layer(Extension(Arc::new(SessionPostgres::new(Arc::clone(&db_pool)))))
+        .layer(Extension(jwt_keys))
+        // Read by the `AuditContext` extractor to record client addresses.
+        .layer(Extension(ClientIpPolicy { trust_forwarded_for: config.login_protection.trust_forwarded_for }))
         .layer(TimeoutLayer::new(Duration::from_secs(config.server.timeout)))
         .layer(RequestBodyLimitLayer::new(
             (config.server.body_limit * 1024 * 1024).try_into()?
@@ -43,6 +152,7 @@ pub async fn start(config: Arc<DotEnvyConfig>, db_pool: Arc<DbPool>) -> Result<(
                 .allow_credentials(true)
                 .allow_headers([axum::http::header::AUTHORIZATION,axum::http::header::CONTENT_TYPE])
         )
+        .layer(middleware::from_fn(request_id))
         .layer(TraceLayer::new_for_http());
 
     let addr = SocketAddr::from(([0, 0, 0, 0], config.server.port));
@@ -50,12 +160,29 @@ pub async fn start(config: Arc<DotEnvyConfig>, db_pool: Arc<DbPool>) -> Result<(
     let listener = TcpListener::bind(addr).await?;
     info!("Server running on {}", config.server.port);
 
-    axum::serve(listener, app)
+    // Client addresses are needed for per-IP rate limiting.
+    axum::serve(listener, app.into_make_service_with_connect_info::<SocketAddr>())
         .with_graceful_shutdown(shutdown_signal())
         .await?;
     Ok(())
 }
 
+// Each platform is served by its real API unless a local posts directory is configured.
+fn social_post_sources(config: &SocialImport) -> Vec<SharedSocialPostSource> {
+    match &config.local_posts_dir {
+        Some(dir) => ["facebook", "x"]
+            .into_iter()
+            .map(|platform| {
+                Arc::new(LocalFilePostSource::new(PathBuf::from(dir), platform.to_string())) as SharedSocialPostSource
+            })
+            .collect(),
+        None => vec![
+            Arc::new(FacebookPostSource::new(config.facebook_api_url.clone())),
+            Arc::new(XPostSource::new(config.x_api_url.clone())),
+        ],
+    }
+}
+
 async fn shutdown_signal() {
     let ctrl_c = async {
         tokio::signal::ctrl_c()
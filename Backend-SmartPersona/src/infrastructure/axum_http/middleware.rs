@@ -1,75 +1,173 @@
+use std::sync::Arc;
+
 use axum::{
     extract::Request,
-    http::{header, StatusCode},
+    http::{header, HeaderName, HeaderValue, Method},
     middleware::Next,
     response::Response,
 };
 
 use crate::{
-    config::config_loader::{get_admin_secret, get_user_secret},
-    infrastructure::jwt_authentication::{self, jwt_model::{Claims, Roles}},
+    domain::{error::AppError, repo::session::SessionRepository},
+    infrastructure::{
+        axum_http::current_user::CurrentUser,
+        jwt_authentication::{self, jwt_keys::{JwtKeys, TokenKind}, jwt_model::Claims},
+        postgres::repositories::session::SessionPostgres,
+    },
 };
+use chrono::Utc;
+use tracing::info;
 use uuid::Uuid;
 
+const REQUEST_ID_HEADER: HeaderName = HeaderName::from_static("x-request-id");
+const IMPERSONATED_BY_HEADER: HeaderName = HeaderName::from_static("x-impersonated-by");
+const MAX_REQUEST_ID_LENGTH: usize = 128;
+
+tokio::task_local! {
+    static REQUEST_ID: String;
+}
+
+// Reuses the caller's `x-request-id` when it looks sane, otherwise generates one, and
+// echoes it back so error bodies and logs can be matched to a request.
+pub async fn request_id(req: Request, next: Next) -> Response {
+    let request_id = req
+        .headers()
+        .get(&REQUEST_ID_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .filter(|value| !value.is_empty() && value.len() <= MAX_REQUEST_ID_LENGTH)
+        .map(str::to_string)
+        .unwrap_or_else(|| Uuid::new_v4().to_string());
+
+    let mut response = REQUEST_ID.scope(request_id.clone(), next.run(req)).await;
+    if let Ok(value) = HeaderValue::from_str(&request_id) {
+        response.headers_mut().insert(REQUEST_ID_HEADER, value);
+    }
+    response
+}
+
+pub fn current_request_id() -> Option<String> {
+    REQUEST_ID.try_with(|request_id| request_id.clone()).ok()
+}
+
 pub async fn user_authorization(
     mut req: Request,
     next: Next,
-) -> Result<Response, StatusCode> {
-    if let Some(cookie_header) = req.headers().get(header::COOKIE) {
-        if let Ok(cookie_str) = cookie_header.to_str() {
-            if let Some(token) = get_cookie_value(cookie_str, "act") {
-                if let Ok(user_secret) = get_user_secret() {
-                    if let Ok(claims) = jwt_authentication::verify_token(user_secret.user_secret, token.clone()) {
-                        if claims.role == Roles::UserAndCompany || claims.role == Roles::Admin {
-                            if let Ok(user_id) = Uuid::parse_str(&claims.sub) {
-                                req.extensions_mut().insert(user_id);
-                            }
-                            req.extensions_mut().insert::<Claims>(claims);
-                            return Ok(next.run(req).await);
-                        }
-                    }
-                }
-                if let Ok(admin_secret) = get_admin_secret() {
-                    if let Ok(claims) = jwt_authentication::verify_token(admin_secret.admin_secret, token) {
-                        if claims.role == Roles::Admin {
-                            if let Ok(user_id) = Uuid::parse_str(&claims.sub) {
-                                req.extensions_mut().insert(user_id);
-                            }
-                            req.extensions_mut().insert::<Claims>(claims);
-                            return Ok(next.run(req).await);
-                        }
-                    }
-                }
+) -> Result<Response, AppError> {
+    let jwt_keys = req.extensions().get::<Arc<JwtKeys>>().cloned();
+    let session_repository = req.extensions().get::<Arc<SessionPostgres>>().cloned();
+
+    if let Some(jwt_keys) = jwt_keys
+        && let Some(cookie_header) = req.headers().get(header::COOKIE)
+        && let Ok(cookie_str) = cookie_header.to_str()
+        && let Some(token) = get_cookie_value(cookie_str, "act")
+    {
+        let claims = jwt_authentication::verify_token(&jwt_keys, TokenKind::UserAccess, token.clone())
+            .or_else(|_| jwt_authentication::verify_token(&jwt_keys, TokenKind::AdminAccess, token))
+            .ok();
+
+        if let Some(claims) = claims {
+            let current_user = ensure_session_active(session_repository, &claims).await?;
+            if let Some(impersonator_id) = current_user.impersonator_id {
+                return run_impersonated(req, next, current_user, impersonator_id).await;
             }
+            req.extensions_mut().insert(current_user);
+            return Ok(next.run(req).await);
         }
     }
 
-    Err(StatusCode::UNAUTHORIZED)
+    Err(AppError::unauthorized("Authentication required"))
 }
 
 pub async fn admin_authorization(
     mut req: Request,
     next: Next,
-) -> Result<Response, StatusCode> {
-    if let Some(cookie_header) = req.headers().get(header::COOKIE) {
-        if let Ok(cookie_str) = cookie_header.to_str() {
-            if let Some(token) = get_cookie_value(cookie_str, "act") {
-                if let Ok(admin_secret) = get_admin_secret() {
-                    if let Ok(claims) = jwt_authentication::verify_token(admin_secret.admin_secret, token) {
-                        if claims.role == Roles::Admin {
-                            if let Ok(admin_id) = Uuid::parse_str(&claims.sub) {
-                                req.extensions_mut().insert(admin_id);
-                            }
-                            req.extensions_mut().insert::<Claims>(claims);
-                            return Ok(next.run(req).await);
-                        }
-                    }
-                }
-            }
-        }
+) -> Result<Response, AppError> {
+    let jwt_keys = req.extensions().get::<Arc<JwtKeys>>().cloned();
+    let session_repository = req.extensions().get::<Arc<SessionPostgres>>().cloned();
+
+    if let Some(jwt_keys) = jwt_keys
+        && let Some(cookie_header) = req.headers().get(header::COOKIE)
+        && let Ok(cookie_str) = cookie_header.to_str()
+        && let Some(token) = get_cookie_value(cookie_str, "act")
+        && let Ok(claims) = jwt_authentication::verify_token(&jwt_keys, TokenKind::AdminAccess, token)
+        && claims.act.is_none()
+    {
+        let current_user = ensure_session_active(session_repository, &claims).await?;
+        req.extensions_mut().insert(current_user);
+        return Ok(next.run(req).await);
     }
 
-    Err(StatusCode::UNAUTHORIZED)
+    Err(AppError::unauthorized("Authentication required"))
+}
+
+// For routes that hand out the user's personal data in bulk, which support staff must not
+// take even read-only. Goes inside `user_authorization`, which puts the `CurrentUser` there.
+pub async fn deny_impersonation(req: Request, next: Next) -> Result<Response, AppError> {
+    if req.extensions().get::<CurrentUser>().is_some_and(CurrentUser::is_impersonated) {
+        return Err(AppError::forbidden("This action is not allowed while impersonating a user"));
+    }
+    Ok(next.run(req).await)
+}
+
+// Impersonation is read-only: anything but a safe method is refused so support staff can look
+// without changing the account. Every request is logged and the response is marked.
+async fn run_impersonated(
+    mut req: Request,
+    next: Next,
+    current_user: CurrentUser,
+    impersonator_id: Uuid,
+) -> Result<Response, AppError> {
+    info!(
+        impersonator_id = %impersonator_id,
+        user_id = %current_user.id,
+        method = %req.method(),
+        path = %req.uri().path(),
+        "impersonated request"
+    );
+    if !matches!(*req.method(), Method::GET | Method::HEAD | Method::OPTIONS) {
+        return Err(AppError::forbidden("This action is not allowed while impersonating a user"));
+    }
+
+    req.extensions_mut().insert(current_user);
+    let mut response = next.run(req).await;
+    if let Ok(value) = HeaderValue::from_str(&impersonator_id.to_string()) {
+        response.headers_mut().insert(IMPERSONATED_BY_HEADER, value);
+    }
+    Ok(response)
+}
+
+// Access tokens carry their session id in `sid`; a logged out session rejects them
+// immediately instead of when they expire. Impersonation tokens are tied to the admin's
+// session, so they end when the admin logs out or is signed out.
+async fn ensure_session_active(
+    session_repository: Option<Arc<SessionPostgres>>,
+    claims: &Claims,
+) -> Result<CurrentUser, AppError> {
+    let session_repository = session_repository
+        .ok_or_else(|| AppError::Internal(anyhow::anyhow!("Session store is not configured")))?;
+
+    let revoked = || AppError::unauthorized("Session has been revoked");
+    let session_id = Uuid::parse_str(&claims.sid).map_err(|_| revoked())?;
+    let session = match session_repository.find_by_id(session_id).await {
+        Ok(session) => session,
+        Err(AppError::NotFound(_)) => return Err(revoked()),
+        Err(e) => return Err(e),
+    };
+
+    let impersonator_id = match &claims.act {
+        Some(act) => Some(Uuid::parse_str(&act.sub).map_err(|_| revoked())?),
+        None => None,
+    };
+    let session_owner = impersonator_id.map(|id| id.to_string()).unwrap_or_else(|| claims.sub.clone());
+    if session.user_id.to_string() != session_owner || !session.is_active(Utc::now().naive_utc()) {
+        return Err(revoked());
+    }
+    Ok(CurrentUser {
+        id: Uuid::parse_str(&claims.sub).map_err(|_| revoked())?,
+        role: claims.role,
+        session_id: session.id,
+        impersonator_id,
+    })
 }
 
 fn get_cookie_value(cookie_header: &str, key: &str) -> Option<String> {
@@ -83,4 +181,47 @@ fn get_cookie_value(cookie_header: &str, key: &str) -> Option<String> {
             None
         }
     })
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use axum::{middleware, routing::get, Router};
+    use tokio::net::TcpListener;
+
+    use super::*;
+    use crate::domain::entities::user::Role;
+
+    // Stands in for `user_authorization` with a fixed caller.
+    async fn serve(impersonator_id: Option<Uuid>) -> String {
+        let current_user = CurrentUser {
+            id: Uuid::new_v4(),
+            role: Role::PersonaUser,
+            session_id: Uuid::new_v4(),
+            impersonator_id,
+        };
+        let app = Router::new()
+            .route("/exports", get(|| async { "export" }))
+            .route_layer(middleware::from_fn(deny_impersonation))
+            .route_layer(middleware::from_fn(move |mut req: Request, next: Next| {
+                req.extensions_mut().insert(current_user.clone());
+                next.run(req)
+            }));
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        format!("http://{}/exports", address)
+    }
+
+    #[tokio::test]
+    async fn lets_the_user_through() {
+        let response = reqwest::get(serve(None).await).await.unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::OK);
+        assert_eq!(response.text().await.unwrap(), "export");
+    }
+
+    #[tokio::test]
+    async fn refuses_an_impersonating_admin() {
+        let response = reqwest::get(serve(Some(Uuid::new_v4())).await).await.unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::FORBIDDEN);
+    }
+}
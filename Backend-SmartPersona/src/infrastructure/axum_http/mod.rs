@@ -1,4 +1,9 @@
 pub mod routers;
 pub mod http_serve;
 pub mod default_routers;
-pub mod middleware;
\ No newline at end of file
+pub mod middleware;
+pub mod current_user;
+pub mod cookies;
+pub mod rate_limit;
+pub mod error_response;
+pub mod request_context;
\ No newline at end of file
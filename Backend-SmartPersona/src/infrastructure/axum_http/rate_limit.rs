@@ -0,0 +1,183 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use axum::{
+    body::{to_bytes, Body},
+    extract::{Request, State},
+    http::Method,
+    middleware::Next,
+    response::Response,
+};
+use serde::Deserialize;
+
+use crate::{
+    config::config_model::LoginProtection,
+    domain::error::AppError,
+    infrastructure::axum_http::request_context::client_ip,
+};
+
+const MAX_LOGIN_BODY_BYTES: usize = 16 * 1024;
+// Expired windows are dropped once this many keys are tracked.
+const PRUNE_THRESHOLD: usize = 10_000;
+
+/// Fixed-window counters kept in memory, so every server instance limits on its own.
+pub struct RateLimiter {
+    window: Duration,
+    windows: Mutex<HashMap<String, Window>>,
+}
+
+struct Window {
+    started_at: Instant,
+    hits: u32,
+}
+
+impl RateLimiter {
+    pub fn new(window: Duration) -> Self {
+        Self {
+            window,
+            windows: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Counts a hit for `key`. Over `max` it returns the seconds until the window resets.
+    pub fn hit(&self, key: &str, max: u32) -> Result<(), u64> {
+        self.hit_at(key, max, Instant::now())
+    }
+
+    fn hit_at(&self, key: &str, max: u32, now: Instant) -> Result<(), u64> {
+        let mut windows = self.windows.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+
+        if windows.len() >= PRUNE_THRESHOLD {
+            windows.retain(|_, window| now.duration_since(window.started_at) < self.window);
+        }
+
+        let window = windows.entry(key.to_string()).or_insert(Window { started_at: now, hits: 0 });
+        if now.duration_since(window.started_at) >= self.window {
+            *window = Window { started_at: now, hits: 0 };
+        }
+
+        window.hits += 1;
+        if window.hits > max {
+            let reset_in = self.window.saturating_sub(now.duration_since(window.started_at));
+            return Err(reset_in.as_secs().max(1));
+        }
+        Ok(())
+    }
+}
+
+pub struct LoginRateLimit {
+    limiter: RateLimiter,
+    ip_max_requests: u32,
+    username_max_attempts: u32,
+    trust_forwarded_for: bool,
+}
+
+impl LoginRateLimit {
+    pub fn new(config: &LoginProtection) -> Self {
+        Self {
+            limiter: RateLimiter::new(config.rate_limit_window),
+            ip_max_requests: config.ip_max_requests,
+            username_max_attempts: config.username_max_attempts,
+            trust_forwarded_for: config.trust_forwarded_for,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct LoginUsername {
+    username: String,
+}
+
+// Limits every request by client IP, and login attempts by username as well so one
+// account can't be guessed at from many addresses.
+pub async fn login_rate_limit(
+    State(rate_limit): State<Arc<LoginRateLimit>>,
+    req: Request,
+    next: Next,
+) -> Result<Response, AppError> {
+    let ip = client_ip(req.headers(), req.extensions(), rate_limit.trust_forwarded_for)
+        .unwrap_or_else(|| "unknown".to_string());
+    rate_limit
+        .limiter
+        .hit(&format!("ip:{}", ip), rate_limit.ip_max_requests)
+        .map_err(too_many_requests)?;
+
+    let is_login = req.method() == Method::POST && matches!(req.uri().path(), "/login" | "/admin/login");
+    if !is_login {
+        return Ok(next.run(req).await);
+    }
+
+    // The body has to be buffered to read the username; it is passed on unchanged.
+    let (parts, body) = req.into_parts();
+    let bytes = to_bytes(body, MAX_LOGIN_BODY_BYTES)
+        .await
+        .map_err(|_| AppError::validation("Request body is too large"))?;
+    if let Ok(login) = serde_json::from_slice::<LoginUsername>(&bytes) {
+        rate_limit
+            .limiter
+            .hit(
+                &format!("username:{}", login.username.trim().to_lowercase()),
+                rate_limit.username_max_attempts,
+            )
+            .map_err(too_many_requests)?;
+    }
+
+    Ok(next.run(Request::from_parts(parts, Body::from(bytes))).await)
+}
+
+fn too_many_requests(retry_after: u64) -> AppError {
+    AppError::too_many_requests("Too many requests, please try again later", retry_after)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WINDOW: Duration = Duration::from_secs(60);
+
+    #[test]
+    fn allows_up_to_max_hits_per_window() {
+        let limiter = RateLimiter::new(WINDOW);
+        let start = Instant::now();
+
+        for _ in 0..3 {
+            assert_eq!(limiter.hit_at("ip:1", 3, start), Ok(()));
+        }
+        assert_eq!(limiter.hit_at("ip:1", 3, start), Err(60));
+    }
+
+    #[test]
+    fn retry_after_counts_down_to_the_window_reset() {
+        let limiter = RateLimiter::new(WINDOW);
+        let start = Instant::now();
+
+        assert_eq!(limiter.hit_at("ip:1", 1, start), Ok(()));
+        assert_eq!(limiter.hit_at("ip:1", 1, start + Duration::from_secs(45)), Err(15));
+        // Never tells the client to retry immediately while still limited.
+        assert_eq!(limiter.hit_at("ip:1", 1, start + Duration::from_millis(59_900)), Err(1));
+    }
+
+    #[test]
+    fn window_rolls_over_after_it_expires() {
+        let limiter = RateLimiter::new(WINDOW);
+        let start = Instant::now();
+
+        assert_eq!(limiter.hit_at("ip:1", 1, start), Ok(()));
+        assert!(limiter.hit_at("ip:1", 1, start + Duration::from_secs(59)).is_err());
+        assert_eq!(limiter.hit_at("ip:1", 1, start + WINDOW), Ok(()));
+        assert!(limiter.hit_at("ip:1", 1, start + WINDOW).is_err());
+    }
+
+    #[test]
+    fn keys_are_counted_separately() {
+        let limiter = RateLimiter::new(WINDOW);
+        let start = Instant::now();
+
+        assert_eq!(limiter.hit_at("ip:1", 1, start), Ok(()));
+        assert_eq!(limiter.hit_at("ip:2", 1, start), Ok(()));
+        assert!(limiter.hit_at("ip:1", 1, start).is_err());
+    }
+}
@@ -0,0 +1,100 @@
+use std::{convert::Infallible, net::SocketAddr};
+
+use axum::{
+    async_trait,
+    extract::{ConnectInfo, FromRequestParts},
+    http::{header, request::Parts, Extensions, HeaderMap},
+};
+
+use crate::{
+    domain::service::audit_logger::AuditContext,
+    infrastructure::axum_http::{current_user::CurrentUser, middleware::current_request_id},
+};
+
+const MAX_USER_AGENT_LENGTH: usize = 512;
+
+/// Whether `X-Forwarded-For` can be trusted; added as an extension in `http_serve`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ClientIpPolicy {
+    pub trust_forwarded_for: bool,
+}
+
+// Never rejects: anything that can't be determined is simply left out of the audit record.
+#[async_trait]
+impl<S> FromRequestParts<S> for AuditContext
+where
+    S: Send + Sync,
+{
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let policy = parts.extensions.get::<ClientIpPolicy>().copied().unwrap_or_default();
+        // While impersonating, the admin behind the token is the one acting.
+        let actor_id = parts
+            .extensions
+            .get::<CurrentUser>()
+            .map(|current_user| current_user.impersonator_id.unwrap_or(current_user.id));
+        Ok(AuditContext {
+            actor_id,
+            ip_address: client_ip(&parts.headers, &parts.extensions, policy.trust_forwarded_for),
+            user_agent: parts
+                .headers
+                .get(header::USER_AGENT)
+                .and_then(|value| value.to_str().ok())
+                .map(|user_agent| user_agent.chars().take(MAX_USER_AGENT_LENGTH).collect()),
+            request_id: current_request_id(),
+        })
+    }
+}
+
+// Clients can send their own `X-Forwarded-For`, and proxies append to it, so only the
+// rightmost entry, the one added by the trusted proxy in front of us, is reliable.
+pub fn client_ip(headers: &HeaderMap, extensions: &Extensions, trust_forwarded_for: bool) -> Option<String> {
+    if trust_forwarded_for
+        && let Some(forwarded_for) = headers.get("x-forwarded-for")
+        && let Ok(forwarded_for) = forwarded_for.to_str()
+        && let Some(ip) = forwarded_for.rsplit(',').next().map(str::trim).filter(|ip| !ip.is_empty())
+    {
+        return Some(ip.to_string());
+    }
+
+    extensions
+        .get::<ConnectInfo<SocketAddr>>()
+        .map(|ConnectInfo(addr)| addr.ip().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request_parts(forwarded_for: Option<&str>) -> (HeaderMap, Extensions) {
+        let mut headers = HeaderMap::new();
+        if let Some(forwarded_for) = forwarded_for {
+            headers.insert("x-forwarded-for", forwarded_for.parse().unwrap());
+        }
+        let mut extensions = Extensions::new();
+        extensions.insert(ConnectInfo(SocketAddr::from(([10, 0, 0, 1], 443))));
+        (headers, extensions)
+    }
+
+    #[test]
+    fn uses_the_entry_appended_by_the_proxy() {
+        let (headers, extensions) = request_parts(Some("6.6.6.6, 203.0.113.7"));
+        assert_eq!(client_ip(&headers, &extensions, true).as_deref(), Some("203.0.113.7"));
+    }
+
+    #[test]
+    fn ignores_forwarded_for_unless_trusted() {
+        let (headers, extensions) = request_parts(Some("203.0.113.7"));
+        assert_eq!(client_ip(&headers, &extensions, false).as_deref(), Some("10.0.0.1"));
+    }
+
+    #[test]
+    fn falls_back_to_the_peer_address() {
+        let (headers, extensions) = request_parts(None);
+        assert_eq!(client_ip(&headers, &extensions, true).as_deref(), Some("10.0.0.1"));
+
+        let (headers, extensions) = request_parts(Some("203.0.113.7, "));
+        assert_eq!(client_ip(&headers, &extensions, true).as_deref(), Some("10.0.0.1"));
+    }
+}
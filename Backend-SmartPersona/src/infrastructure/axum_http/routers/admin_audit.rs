@@ -0,0 +1,36 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+    routing::get,
+    Json, Router,
+};
+
+use crate::{
+    domain::{
+        service::audit_logger::AuditLogger, usecase::audit::AuditUseCase,
+        value_object::audit_event::AuditEventQuery,
+    },
+    infrastructure::axum_http::current_user::{permissions::AuditRead, RequirePermission},
+};
+
+pub fn routes(audit_logger: Arc<AuditLogger>) -> Router {
+    let audit_use_case = AuditUseCase::new(audit_logger);
+
+    Router::new()
+        .route("/", get(list_audit_events))
+        .with_state(Arc::new(audit_use_case))
+}
+
+pub async fn list_audit_events(
+    State(audit_use_case): State<Arc<AuditUseCase>>,
+    _: RequirePermission<AuditRead>,
+    Query(query): Query<AuditEventQuery>,
+) -> impl IntoResponse {
+    match audit_use_case.list_events(query).await {
+        Ok(page) => (StatusCode::OK, Json(page)).into_response(),
+        Err(e) => e.into_response(),
+    }
+}
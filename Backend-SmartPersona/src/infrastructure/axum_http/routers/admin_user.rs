@@ -0,0 +1,290 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+    routing::{get, post, put},
+    Json, Router,
+};
+use axum_extra::extract::cookie::CookieJar;
+use cookie::time::Duration;
+use uuid::Uuid;
+
+use crate::{
+    config::config_model::Config,
+    domain::{
+        repo::{
+            generation_job::GenerationJobRepository, profile::ProfileRepository, session::SessionRepository,
+            user::UserRepository,
+        },
+        service::audit_logger::{AuditContext, AuditLogger},
+        usecase::admin_user::AdminUserUseCase,
+        value_object::admin_user::{ChangeRoleModel, PageQuery, UserListQuery},
+    },
+    infrastructure::{
+        axum_http::{
+            cookies::token_cookie,
+            current_user::{
+                permissions::{
+                    ProfileReadAny, UserBan, UserImpersonate, UserManageRoles, UserReadAny, UserRevokeSessions,
+                    UserUnlock,
+                },
+                CurrentUser, RequirePermission,
+            },
+        },
+        jwt_authentication::jwt_keys::JwtKeys,
+        postgres::{
+            postgres_connection::DbPool,
+            repositories::{
+                generation_job::GenerationJobPostgres, profile::ProfilePostgres, session::SessionPostgres,
+                user::UserPostgres,
+            },
+        },
+    },
+};
+
+pub fn routes(db_pool: Arc<DbPool>, jwt_keys: Arc<JwtKeys>, audit_logger: Arc<AuditLogger>, config: &Config) -> Router {
+    let user_repository = UserPostgres::new(Arc::clone(&db_pool));
+    let session_repository = SessionPostgres::new(Arc::clone(&db_pool));
+    let profile_repository = ProfilePostgres::new(Arc::clone(&db_pool));
+    let generation_job_repository = GenerationJobPostgres::new(db_pool);
+    let admin_user_use_case = AdminUserUseCase::new(
+        Arc::new(user_repository),
+        Arc::new(session_repository),
+        Arc::new(profile_repository),
+        Arc::new(generation_job_repository),
+        audit_logger,
+        jwt_keys,
+        config.impersonation.token_lifetime,
+    );
+
+    Router::new()
+        .route("/", get(list_users::<UserPostgres, SessionPostgres, ProfilePostgres, GenerationJobPostgres>))
+        .route("/:user_id", get(get_user::<UserPostgres, SessionPostgres, ProfilePostgres, GenerationJobPostgres>))
+        .route(
+            "/:user_id/profiles",
+            get(user_profiles::<UserPostgres, SessionPostgres, ProfilePostgres, GenerationJobPostgres>),
+        )
+        .route("/:user_id/jobs", get(user_jobs::<UserPostgres, SessionPostgres, ProfilePostgres, GenerationJobPostgres>))
+        .route("/:user_id/role", put(change_role::<UserPostgres, SessionPostgres, ProfilePostgres, GenerationJobPostgres>))
+        .route("/:user_id/suspend", post(suspend_user::<UserPostgres, SessionPostgres, ProfilePostgres, GenerationJobPostgres>))
+        .route("/:user_id/ban", post(ban_user::<UserPostgres, SessionPostgres, ProfilePostgres, GenerationJobPostgres>))
+        .route(
+            "/:user_id/reactivate",
+            post(reactivate_user::<UserPostgres, SessionPostgres, ProfilePostgres, GenerationJobPostgres>),
+        )
+        .route("/:user_id/logout", post(force_logout::<UserPostgres, SessionPostgres, ProfilePostgres, GenerationJobPostgres>))
+        .route(
+            "/:user_id/impersonate",
+            post(impersonate_user::<UserPostgres, SessionPostgres, ProfilePostgres, GenerationJobPostgres>),
+        )
+        .route("/:user_id/unlock", post(unlock_user::<UserPostgres, SessionPostgres, ProfilePostgres, GenerationJobPostgres>))
+        .with_state(Arc::new(admin_user_use_case))
+}
+
+pub async fn list_users<T1, T2, T3, T4>(
+    State(admin_user_use_case): State<Arc<AdminUserUseCase<T1, T2, T3, T4>>>,
+    _: RequirePermission<UserReadAny>,
+    Query(query): Query<UserListQuery>,
+) -> impl IntoResponse
+where
+    T1: UserRepository + Send + Sync,
+    T2: SessionRepository + Send + Sync,
+    T3: ProfileRepository + Send + Sync,
+    T4: GenerationJobRepository + Send + Sync,
+{
+    match admin_user_use_case.list_users(query).await {
+        Ok(page) => (StatusCode::OK, Json(page)).into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+pub async fn get_user<T1, T2, T3, T4>(
+    State(admin_user_use_case): State<Arc<AdminUserUseCase<T1, T2, T3, T4>>>,
+    _: RequirePermission<UserReadAny>,
+    Path(user_id): Path<Uuid>,
+) -> impl IntoResponse
+where
+    T1: UserRepository + Send + Sync,
+    T2: SessionRepository + Send + Sync,
+    T3: ProfileRepository + Send + Sync,
+    T4: GenerationJobRepository + Send + Sync,
+{
+    match admin_user_use_case.get_user(user_id).await {
+        Ok(user) => (StatusCode::OK, Json(user)).into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+pub async fn user_profiles<T1, T2, T3, T4>(
+    State(admin_user_use_case): State<Arc<AdminUserUseCase<T1, T2, T3, T4>>>,
+    _: RequirePermission<ProfileReadAny>,
+    Path(user_id): Path<Uuid>,
+) -> impl IntoResponse
+where
+    T1: UserRepository + Send + Sync,
+    T2: SessionRepository + Send + Sync,
+    T3: ProfileRepository + Send + Sync,
+    T4: GenerationJobRepository + Send + Sync,
+{
+    match admin_user_use_case.user_profiles(user_id).await {
+        Ok(profiles) => (StatusCode::OK, Json(profiles)).into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+pub async fn user_jobs<T1, T2, T3, T4>(
+    State(admin_user_use_case): State<Arc<AdminUserUseCase<T1, T2, T3, T4>>>,
+    _: RequirePermission<UserReadAny>,
+    Path(user_id): Path<Uuid>,
+    Query(page_query): Query<PageQuery>,
+) -> impl IntoResponse
+where
+    T1: UserRepository + Send + Sync,
+    T2: SessionRepository + Send + Sync,
+    T3: ProfileRepository + Send + Sync,
+    T4: GenerationJobRepository + Send + Sync,
+{
+    match admin_user_use_case.user_jobs(user_id, page_query).await {
+        Ok(jobs) => (StatusCode::OK, Json(jobs)).into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+pub async fn change_role<T1, T2, T3, T4>(
+    State(admin_user_use_case): State<Arc<AdminUserUseCase<T1, T2, T3, T4>>>,
+    _: RequirePermission<UserManageRoles>,
+    CurrentUser { id: admin_id, .. }: CurrentUser,
+    Path(user_id): Path<Uuid>,
+    context: AuditContext,
+    Json(change_role_model): Json<ChangeRoleModel>,
+) -> impl IntoResponse
+where
+    T1: UserRepository + Send + Sync,
+    T2: SessionRepository + Send + Sync,
+    T3: ProfileRepository + Send + Sync,
+    T4: GenerationJobRepository + Send + Sync,
+{
+    match admin_user_use_case.change_role(admin_id, user_id, change_role_model.role, &context).await {
+        Ok(user) => (StatusCode::OK, Json(user)).into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+pub async fn suspend_user<T1, T2, T3, T4>(
+    State(admin_user_use_case): State<Arc<AdminUserUseCase<T1, T2, T3, T4>>>,
+    _: RequirePermission<UserBan>,
+    CurrentUser { id: admin_id, .. }: CurrentUser,
+    Path(user_id): Path<Uuid>,
+    context: AuditContext,
+) -> impl IntoResponse
+where
+    T1: UserRepository + Send + Sync,
+    T2: SessionRepository + Send + Sync,
+    T3: ProfileRepository + Send + Sync,
+    T4: GenerationJobRepository + Send + Sync,
+{
+    match admin_user_use_case.suspend(admin_id, user_id, &context).await {
+        Ok(user) => (StatusCode::OK, Json(user)).into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+pub async fn ban_user<T1, T2, T3, T4>(
+    State(admin_user_use_case): State<Arc<AdminUserUseCase<T1, T2, T3, T4>>>,
+    _: RequirePermission<UserBan>,
+    CurrentUser { id: admin_id, .. }: CurrentUser,
+    Path(user_id): Path<Uuid>,
+    context: AuditContext,
+) -> impl IntoResponse
+where
+    T1: UserRepository + Send + Sync,
+    T2: SessionRepository + Send + Sync,
+    T3: ProfileRepository + Send + Sync,
+    T4: GenerationJobRepository + Send + Sync,
+{
+    match admin_user_use_case.ban(admin_id, user_id, &context).await {
+        Ok(user) => (StatusCode::OK, Json(user)).into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+pub async fn reactivate_user<T1, T2, T3, T4>(
+    State(admin_user_use_case): State<Arc<AdminUserUseCase<T1, T2, T3, T4>>>,
+    _: RequirePermission<UserBan>,
+    CurrentUser { id: admin_id, .. }: CurrentUser,
+    Path(user_id): Path<Uuid>,
+    context: AuditContext,
+) -> impl IntoResponse
+where
+    T1: UserRepository + Send + Sync,
+    T2: SessionRepository + Send + Sync,
+    T3: ProfileRepository + Send + Sync,
+    T4: GenerationJobRepository + Send + Sync,
+{
+    match admin_user_use_case.reactivate(admin_id, user_id, &context).await {
+        Ok(user) => (StatusCode::OK, Json(user)).into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+pub async fn force_logout<T1, T2, T3, T4>(
+    State(admin_user_use_case): State<Arc<AdminUserUseCase<T1, T2, T3, T4>>>,
+    _: RequirePermission<UserRevokeSessions>,
+    Path(user_id): Path<Uuid>,
+    context: AuditContext,
+) -> impl IntoResponse
+where
+    T1: UserRepository + Send + Sync,
+    T2: SessionRepository + Send + Sync,
+    T3: ProfileRepository + Send + Sync,
+    T4: GenerationJobRepository + Send + Sync,
+{
+    match admin_user_use_case.force_logout(user_id, &context).await {
+        Ok(result) => (StatusCode::OK, Json(result)).into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+pub async fn unlock_user<T1, T2, T3, T4>(
+    State(admin_user_use_case): State<Arc<AdminUserUseCase<T1, T2, T3, T4>>>,
+    _: RequirePermission<UserUnlock>,
+    Path(user_id): Path<Uuid>,
+    context: AuditContext,
+) -> impl IntoResponse
+where
+    T1: UserRepository + Send + Sync,
+    T2: SessionRepository + Send + Sync,
+    T3: ProfileRepository + Send + Sync,
+    T4: GenerationJobRepository + Send + Sync,
+{
+    match admin_user_use_case.unlock(user_id, &context).await {
+        Ok(()) => StatusCode::NO_CONTENT.into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+// Replaces the admin's `act` cookie with the impersonation token. The admin's refresh cookie
+// is left alone, so refreshing the admin session ends the impersonation.
+pub async fn impersonate_user<T1, T2, T3, T4>(
+    State(admin_user_use_case): State<Arc<AdminUserUseCase<T1, T2, T3, T4>>>,
+    _: RequirePermission<UserImpersonate>,
+    CurrentUser { id: admin_id, session_id, .. }: CurrentUser,
+    Path(user_id): Path<Uuid>,
+    context: AuditContext,
+) -> impl IntoResponse
+where
+    T1: UserRepository + Send + Sync,
+    T2: SessionRepository + Send + Sync,
+    T3: ProfileRepository + Send + Sync,
+    T4: GenerationJobRepository + Send + Sync,
+{
+    match admin_user_use_case.impersonate(admin_id, session_id, user_id, &context).await {
+        Ok((access_token, impersonation)) => {
+            let cookies = CookieJar::new().add(token_cookie("act", access_token, Duration::seconds(impersonation.expires_in)));
+            (StatusCode::OK, cookies, Json(impersonation)).into_response()
+        }
+        Err(e) => e.into_response(),
+    }
+}
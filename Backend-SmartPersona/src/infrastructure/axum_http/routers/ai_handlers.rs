@@ -1,4 +1,4 @@
-use axum::{routing::post, Router};
+use axum::{middleware, routing::post, Router};
 use axum::{
     extract::State,
     http::StatusCode,
@@ -9,26 +9,43 @@ use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 
 use crate::{
-    domain::usecase::ai_analysis::AIAnalysisUseCase,
-    infrastructure::ai_service_client::client::AIServiceClient,
+    domain::usecase::{ai_analysis::AIAnalysisUseCase, generation_job::GenerationJobUseCase},
+    infrastructure::{
+        ai_service_client::client::AIServiceClient,
+        axum_http::{
+            current_user::{permissions::AiAnalyze, CurrentUser, RequirePermission},
+            middleware::user_authorization,
+        },
+        postgres::repositories::{
+            generation_job::GenerationJobPostgres, prompt_template::PromptTemplatePostgres,
+            social_connection::SocialConnectionPostgres,
+        },
+    },
 };
 
-pub fn routes(ai_use_case: Arc<AIAnalysisUseCase<AIServiceClient>>) -> Router {
+pub fn routes(
+    ai_use_case: Arc<AIAnalysisUseCase<AIServiceClient, PromptTemplatePostgres, SocialConnectionPostgres>>,
+    generation_job_use_case: Arc<GenerationJobUseCase<GenerationJobPostgres, AIServiceClient, PromptTemplatePostgres, SocialConnectionPostgres>>,
+) -> Router {
     Router::new()
-        .route("/analyze-personality", post(analyze_personality_handler).with_state(Arc::clone(&ai_use_case)))
+        .route(
+            "/analyze-personality",
+            post(analyze_personality_handler)
+                .with_state(generation_job_use_case)
+                .route_layer(middleware::from_fn(user_authorization)),
+        )
         .route("/chat", post(chat_handler).with_state(ai_use_case))
 }
 
 #[derive(Deserialize)]
 pub struct AnalyzePersonalityPayload {
-    pub user_id: String,
-    pub posts: Vec<String>,
-}
-
-#[derive(Serialize)]
-pub struct AnalyzePersonalityResponse {
-    pub personality_tags: Vec<String>,
-    pub suggested_theme: String,
+    // Deprecated: ignored, the analysis always runs for the authenticated caller.
+    // Still accepted so existing clients that send it keep working.
+    pub user_id: Option<String>,
+    // Omit to analyze posts imported from the caller's linked social accounts.
+    pub posts: Option<Vec<String>>,
+    pub language: Option<String>,
+    pub tag_count: Option<u32>,
 }
 
 #[derive(Deserialize)]
@@ -41,26 +58,24 @@ pub struct ChatHandlerResponse {
     pub reply: String,
 }
 
+// The analysis runs on the background worker; poll `GET /jobs/{id}` for the result.
 pub async fn analyze_personality_handler(
-    State(ai_use_case): State<Arc<AIAnalysisUseCase<AIServiceClient>>>,
+    State(generation_job_use_case): State<Arc<GenerationJobUseCase<GenerationJobPostgres, AIServiceClient, PromptTemplatePostgres, SocialConnectionPostgres>>>,
+    _: RequirePermission<AiAnalyze>,
+    CurrentUser { id: user_id, .. }: CurrentUser,
     Json(payload): Json<AnalyzePersonalityPayload>,
 ) -> impl IntoResponse {
-    match ai_use_case.analyze_user_personality(payload.user_id, payload.posts).await {
-        Ok(result) => {
-            let response = AnalyzePersonalityResponse {
-                personality_tags: result.personality_tags,
-                suggested_theme: result.suggested_theme,
-            };
-            (StatusCode::OK, Json(response)).into_response()
-        }
-        Err(e) => {
-            (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response()
-        }
+    match generation_job_use_case
+        .submit_personality_analysis(user_id, payload.posts, payload.language, payload.tag_count)
+        .await
+    {
+        Ok(job) => (StatusCode::ACCEPTED, Json(job)).into_response(),
+        Err(e) => e.into_response(),
     }
 }
 
 pub async fn chat_handler(
-    State(ai_use_case): State<Arc<AIAnalysisUseCase<AIServiceClient>>>,
+    State(ai_use_case): State<Arc<AIAnalysisUseCase<AIServiceClient, PromptTemplatePostgres, SocialConnectionPostgres>>>,
     Json(payload): Json<ChatPayload>,
 ) -> impl IntoResponse {
     match ai_use_case.chat_with_bot(payload.message).await {
@@ -70,8 +85,6 @@ pub async fn chat_handler(
             };
             (StatusCode::OK, Json(response)).into_response()
         }
-        Err(e) => {
-            (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response()
-        }
+        Err(e) => e.into_response(),
     }
 }
@@ -2,226 +2,448 @@ use std::sync::Arc;
 
 use axum::{
     extract::State,
-    http::{header, HeaderMap, HeaderValue, StatusCode},
-    response::IntoResponse,
+    http::{header, HeaderMap, StatusCode},
+    middleware,
+    response::{IntoResponse, Response},
     routing::post,
     Json, Router,
 };
-use axum_extra::extract::cookie::{Cookie, CookieJar};
+use axum_extra::extract::cookie::CookieJar;
 use cookie::time::Duration;
+use serde_json::json;
 
 use crate::{
-    config::{config_loader::get_stage, stage::Stage},
-    domain::repo::user::UserRepository,
+    config::config_model::Config,
+    domain::{
+        error::AppError,
+        repo::{
+            mailer::SharedMailer, mfa::MfaRepository, password_reset::PasswordResetRepository,
+            session::SessionRepository, user::UserRepository,
+        },
+        service::audit_logger::{AuditContext, AuditLogger},
+    },
     infrastructure::{
-        jwt_authentication::authentication_model::LoginModel,
+        axum_http::{
+            cookies::token_cookie,
+            current_user::CurrentUser,
+            middleware::user_authorization,
+            rate_limit::{login_rate_limit, LoginRateLimit},
+        },
+        jwt_authentication::{
+            authentication_model::{
+                ForgotPasswordModel, LoginModel, MfaCodeModel, MfaTokenModel, MfaVerifyModel, RecoveryCodesModel,
+                ResetPasswordModel,
+            },
+            jwt_keys::JwtKeys,
+            jwt_model::{LoginOutcome, Passport},
+        },
         postgres::{
             postgres_connection::DbPool,
-            repositories::user::UserPostgres,
+            repositories::{
+                mfa::MfaPostgres, password_reset::PasswordResetPostgres, session::SessionPostgres, user::UserPostgres,
+            },
         },
+        password_policy::PasswordValidator,
+        token_encryption::EnvelopeCipher,
     },
 };
 
-use crate::domain::usecase::authentication::AuthenticationUseCase;
+use crate::domain::usecase::{
+    authentication::{AuthenticationSettings, AuthenticationUseCase, LockoutPolicy},
+    password_reset::PasswordResetUseCase,
+};
+
+const MAX_DEVICE_LENGTH: usize = 255;
 
-pub fn routes(db_pool: Arc<DbPool>) -> Router {
+pub fn routes(
+    db_pool: Arc<DbPool>,
+    jwt_keys: Arc<JwtKeys>,
+    cipher: Arc<EnvelopeCipher>,
+    mailer: SharedMailer,
+    password_validator: Arc<PasswordValidator>,
+    audit_logger: Arc<AuditLogger>,
+    config: &Config,
+) -> Router {
     let user_repository = UserPostgres::new(Arc::clone(&db_pool));
-    let authentication_use_case = AuthenticationUseCase::new(Arc::new(user_repository));
+    let session_repository = SessionPostgres::new(Arc::clone(&db_pool));
+    let mfa_repository = MfaPostgres::new(Arc::clone(&db_pool), cipher);
+    let authentication_use_case = AuthenticationUseCase::new(
+        Arc::new(user_repository),
+        Arc::new(session_repository),
+        Arc::new(mfa_repository),
+        jwt_keys,
+        audit_logger,
+        AuthenticationSettings {
+            access_token_lifetime: config.jwt.access_token_expiration,
+            refresh_token_lifetime: config.jwt.refresh_token_expiration,
+            mfa_issuer: config.app.name.clone(),
+            lockout: LockoutPolicy {
+                backoff_after: config.login_protection.backoff_after,
+                backoff_base: config.login_protection.backoff_base,
+                lockout_after: config.login_protection.lockout_after,
+                lockout_duration: config.login_protection.lockout_duration,
+            },
+        },
+    );
+    let password_reset_use_case = PasswordResetUseCase::new(
+        Arc::new(UserPostgres::new(Arc::clone(&db_pool))),
+        Arc::new(PasswordResetPostgres::new(Arc::clone(&db_pool))),
+        mailer,
+        password_validator,
+        format!("{}/reset-password", config.app.frontend_url.trim_end_matches('/')),
+        config.password_reset.token_lifetime,
+    );
+    let login_rate_limit_state = Arc::new(LoginRateLimit::new(&config.login_protection));
+
+    let password_reset_routes = Router::new()
+        .route("/forgot-password", post(forgot_password::<UserPostgres, PasswordResetPostgres>))
+        .route("/reset-password", post(reset_password::<UserPostgres, PasswordResetPostgres>))
+        .with_state(Arc::new(password_reset_use_case));
 
     Router::new()
-        .route("/login", post(user_login::<UserPostgres>))
-        .route("/refresh-token", post(user_refresh_token::<UserPostgres>))
-        .route("/admin/login", post(admin_login::<UserPostgres>))
-        .route("/admin/refresh-token", post(admin_refresh_token::<UserPostgres>))
+        .route("/login", post(user_login::<UserPostgres, SessionPostgres, MfaPostgres>))
+        .route("/refresh-token", post(user_refresh_token::<UserPostgres, SessionPostgres, MfaPostgres>))
+        .route("/admin/login", post(admin_login::<UserPostgres, SessionPostgres, MfaPostgres>))
+        .route("/admin/refresh-token", post(admin_refresh_token::<UserPostgres, SessionPostgres, MfaPostgres>))
+        .route("/logout", post(logout::<UserPostgres, SessionPostgres, MfaPostgres>))
+        .route(
+            "/logout-all",
+            post(logout_all::<UserPostgres, SessionPostgres, MfaPostgres>).route_layer(middleware::from_fn(user_authorization)),
+        )
+        .route("/mfa/verify", post(verify_mfa::<UserPostgres, SessionPostgres, MfaPostgres>))
+        .route("/mfa/setup", post(start_login_mfa_enrolment::<UserPostgres, SessionPostgres, MfaPostgres>))
+        .route(
+            "/mfa/enroll",
+            post(start_mfa_enrolment::<UserPostgres, SessionPostgres, MfaPostgres>)
+                .route_layer(middleware::from_fn(user_authorization)),
+        )
+        .route(
+            "/mfa/confirm",
+            post(confirm_mfa_enrolment::<UserPostgres, SessionPostgres, MfaPostgres>)
+                .route_layer(middleware::from_fn(user_authorization)),
+        )
+        .route(
+            "/mfa/recovery-codes",
+            post(regenerate_recovery_codes::<UserPostgres, SessionPostgres, MfaPostgres>)
+                .route_layer(middleware::from_fn(user_authorization)),
+        )
+        .route(
+            "/mfa/disable",
+            post(disable_mfa::<UserPostgres, SessionPostgres, MfaPostgres>)
+                .route_layer(middleware::from_fn(user_authorization)),
+        )
         .with_state(Arc::new(authentication_use_case))
+        .merge(password_reset_routes)
+        .layer(middleware::from_fn_with_state(login_rate_limit_state, login_rate_limit))
 }
 
-pub async fn user_login<T>(
-    State(authentication_use_case): State<Arc<AuthenticationUseCase<T>>>,
+pub async fn user_login<T1, T2, T3>(
+    State(authentication_use_case): State<Arc<AuthenticationUseCase<T1, T2, T3>>>,
+    headers: HeaderMap,
+    context: AuditContext,
     Json(login_model): Json<LoginModel>,
 ) -> impl IntoResponse
 where
-    T: UserRepository + Send + Sync,
+    T1: UserRepository + Send + Sync,
+    T2: SessionRepository + Send + Sync,
+    T3: MfaRepository + Send + Sync,
 {
-    match authentication_use_case.user_login(login_model).await {
-        Ok(passport) => {
-            let mut act_cookie = Cookie::build(("act", passport.access_token.clone()))
-                .path("/")
-                .same_site(cookie::SameSite::Lax)
-                .http_only(true)
-                .max_age(Duration::days(14));
-
-            let mut rft_cookie = Cookie::build(("rft", passport.refresh_token.clone()))
-                .path("/")
-                .same_site(cookie::SameSite::Lax)
-                .http_only(true)
-                .max_age(Duration::days(14));
-
-            if get_stage() == Stage::Production {
-                rft_cookie = rft_cookie.secure(true);
-                act_cookie = act_cookie.secure(true);
-            }
-
-            let mut headers = HeaderMap::new();
-            headers.append(
-                header::SET_COOKIE,
-                HeaderValue::from_str(&act_cookie.to_string()).unwrap(),
-            );
-            headers.append(
-                header::SET_COOKIE,
-                HeaderValue::from_str(&rft_cookie.to_string()).unwrap(),
-            );
-
-            (StatusCode::OK, headers, "Login successfully").into_response()
-        }
-        Err(e) => (StatusCode::UNAUTHORIZED, e.to_string()).into_response(),
+    match authentication_use_case.user_login(login_model, device(&headers), &context).await {
+        Ok(login_outcome) => login_response(login_outcome, "Login successfully"),
+        Err(e) => e.into_response(),
     }
 }
 
-pub async fn user_refresh_token<T>(
-    State(authentication_use_case): State<Arc<AuthenticationUseCase<T>>>,
+pub async fn user_refresh_token<T1, T2, T3>(
+    State(authentication_use_case): State<Arc<AuthenticationUseCase<T1, T2, T3>>>,
+    context: AuditContext,
     jar: CookieJar,
 ) -> impl IntoResponse
 where
-    T: UserRepository + Send + Sync,
+    T1: UserRepository + Send + Sync,
+    T2: SessionRepository + Send + Sync,
+    T3: MfaRepository + Send + Sync,
 {
     if let Some(rft) = jar.get("rft") {
         let refresh_token = rft.value().to_string();
 
         let response = match authentication_use_case
-            .user_refresh_token(refresh_token)
+            .user_refresh_token(refresh_token, &context)
             .await
         {
             Ok(passport) => {
-                let mut act_cookie = Cookie::build(("act", passport.access_token.clone()))
-                    .path("/")
-                    .same_site(cookie::SameSite::Lax)
-                    .http_only(true)
-                    .max_age(Duration::days(14));
-
-                let mut rft_cookie = Cookie::build(("rft", passport.refresh_token.clone()))
-                    .path("/")
-                    .same_site(cookie::SameSite::Lax)
-                    .http_only(true)
-                    .max_age(Duration::days(14));
-
-                if get_stage() == Stage::Production {
-                    rft_cookie = rft_cookie.secure(true);
-                    act_cookie = act_cookie.secure(true);
-                }
-
-                let mut headers = HeaderMap::new();
-                headers.append(
-                    header::SET_COOKIE,
-                    HeaderValue::from_str(&act_cookie.to_string()).unwrap(),
-                );
-                headers.append(
-                    header::SET_COOKIE,
-                    HeaderValue::from_str(&rft_cookie.to_string()).unwrap(),
-                );
-
-                (StatusCode::OK, headers, "Refresh token successfully").into_response()
+                (StatusCode::OK, passport_cookies(&passport), "Refresh token successfully").into_response()
             }
-            Err(e) => (StatusCode::UNAUTHORIZED, e.to_string()).into_response(),
+            Err(e) => e.into_response(),
         };
 
         return response;
     }
 
-    (StatusCode::BAD_REQUEST, "Refresh token not found").into_response()
+    AppError::unauthorized("Refresh token not found").into_response()
 }
 
-pub async fn admin_login<T>(
-    State(authentication_use_case): State<Arc<AuthenticationUseCase<T>>>,
+pub async fn admin_login<T1, T2, T3>(
+    State(authentication_use_case): State<Arc<AuthenticationUseCase<T1, T2, T3>>>,
+    headers: HeaderMap,
+    context: AuditContext,
     Json(login_model): Json<LoginModel>,
 ) -> impl IntoResponse
 where
-    T: UserRepository + Send + Sync,
+    T1: UserRepository + Send + Sync,
+    T2: SessionRepository + Send + Sync,
+    T3: MfaRepository + Send + Sync,
 {
-    match authentication_use_case.admin_login(login_model).await {
-        Ok(passport) => {
-            let mut act_cookie = Cookie::build(("act", passport.access_token.clone()))
-                .path("/")
-                .same_site(cookie::SameSite::Lax)
-                .http_only(true)
-                .max_age(Duration::days(14));
-
-            let mut rft_cookie = Cookie::build(("rft", passport.refresh_token.clone()))
-                .path("/")
-                .same_site(cookie::SameSite::Lax)
-                .http_only(true)
-                .max_age(Duration::days(14));
-
-            if get_stage() == Stage::Production {
-                rft_cookie = rft_cookie.secure(true);
-                act_cookie = act_cookie.secure(true);
-            }
-
-            let mut headers = HeaderMap::new();
-            headers.append(
-                header::SET_COOKIE,
-                HeaderValue::from_str(&act_cookie.to_string()).unwrap(),
-            );
-            headers.append(
-                header::SET_COOKIE,
-                HeaderValue::from_str(&rft_cookie.to_string()).unwrap(),
-            );
-
-            (StatusCode::OK, headers, "Admin login successfully").into_response()
-        }
-        Err(e) => (StatusCode::UNAUTHORIZED, e.to_string()).into_response(),
+    match authentication_use_case.admin_login(login_model, device(&headers), &context).await {
+        Ok(login_outcome) => login_response(login_outcome, "Admin login successfully"),
+        Err(e) => e.into_response(),
     }
 }
 
-pub async fn admin_refresh_token<T>(
-    State(authentication_use_case): State<Arc<AuthenticationUseCase<T>>>,
+pub async fn admin_refresh_token<T1, T2, T3>(
+    State(authentication_use_case): State<Arc<AuthenticationUseCase<T1, T2, T3>>>,
+    context: AuditContext,
     jar: CookieJar,
 ) -> impl IntoResponse
 where
-    T: UserRepository + Send + Sync,
+    T1: UserRepository + Send + Sync,
+    T2: SessionRepository + Send + Sync,
+    T3: MfaRepository + Send + Sync,
 {
     if let Some(rft) = jar.get("rft") {
         let refresh_token = rft.value().to_string();
 
         let response = match authentication_use_case
-            .admin_refresh_token(refresh_token)
+            .admin_refresh_token(refresh_token, &context)
             .await
         {
             Ok(passport) => {
-                let mut act_cookie = Cookie::build(("act", passport.access_token.clone()))
-                    .path("/")
-                    .same_site(cookie::SameSite::Lax)
-                    .http_only(true)
-                    .max_age(Duration::days(14));
-
-                let mut rft_cookie = Cookie::build(("rft", passport.refresh_token.clone()))
-                    .path("/")
-                    .same_site(cookie::SameSite::Lax)
-                    .http_only(true)
-                    .max_age(Duration::days(14));
-
-                if get_stage() == Stage::Production {
-                    rft_cookie = rft_cookie.secure(true);
-                    act_cookie = act_cookie.secure(true);
-                }
-
-                let mut headers = HeaderMap::new();
-                headers.append(
-                    header::SET_COOKIE,
-                    HeaderValue::from_str(&act_cookie.to_string()).unwrap(),
-                );
-                headers.append(
-                    header::SET_COOKIE,
-                    HeaderValue::from_str(&rft_cookie.to_string()).unwrap(),
-                );
-
-                (StatusCode::OK, headers, "Admin refresh token successfully").into_response()
+                (StatusCode::OK, passport_cookies(&passport), "Admin refresh token successfully").into_response()
             }
-            Err(e) => (StatusCode::UNAUTHORIZED, e.to_string()).into_response(),
+            Err(e) => e.into_response(),
         };
 
         return response;
     }
 
-    (StatusCode::BAD_REQUEST, "Refresh token not found").into_response()
-}
\ No newline at end of file
+    AppError::unauthorized("Refresh token not found").into_response()
+}
+
+// Revokes the current session and clears the cookies even when the token is already invalid.
+pub async fn logout<T1, T2, T3>(
+    State(authentication_use_case): State<Arc<AuthenticationUseCase<T1, T2, T3>>>,
+    jar: CookieJar,
+) -> impl IntoResponse
+where
+    T1: UserRepository + Send + Sync,
+    T2: SessionRepository + Send + Sync,
+    T3: MfaRepository + Send + Sync,
+{
+    if let Some(rft) = jar.get("rft")
+        && let Err(e) = authentication_use_case.logout(rft.value().to_string()).await
+    {
+        return e.into_response();
+    }
+
+    (StatusCode::OK, cleared_cookies(), "Logout successfully").into_response()
+}
+
+pub async fn logout_all<T1, T2, T3>(
+    State(authentication_use_case): State<Arc<AuthenticationUseCase<T1, T2, T3>>>,
+    CurrentUser { id: user_id, .. }: CurrentUser,
+) -> impl IntoResponse
+where
+    T1: UserRepository + Send + Sync,
+    T2: SessionRepository + Send + Sync,
+    T3: MfaRepository + Send + Sync,
+{
+    match authentication_use_case.logout_all(user_id).await {
+        Ok(revoked) => (StatusCode::OK, cleared_cookies(), Json(json!({ "revoked_sessions": revoked }))).into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+pub async fn verify_mfa<T1, T2, T3>(
+    State(authentication_use_case): State<Arc<AuthenticationUseCase<T1, T2, T3>>>,
+    headers: HeaderMap,
+    context: AuditContext,
+    Json(mfa_verify_model): Json<MfaVerifyModel>,
+) -> impl IntoResponse
+where
+    T1: UserRepository + Send + Sync,
+    T2: SessionRepository + Send + Sync,
+    T3: MfaRepository + Send + Sync,
+{
+    match authentication_use_case.verify_mfa(mfa_verify_model, device(&headers), &context).await {
+        Ok((passport, Some(recovery_codes))) => (
+            StatusCode::OK,
+            passport_cookies(&passport),
+            Json(RecoveryCodesModel { recovery_codes }),
+        )
+            .into_response(),
+        Ok((passport, None)) => (StatusCode::OK, passport_cookies(&passport), "Login successfully").into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+pub async fn start_login_mfa_enrolment<T1, T2, T3>(
+    State(authentication_use_case): State<Arc<AuthenticationUseCase<T1, T2, T3>>>,
+    Json(mfa_token_model): Json<MfaTokenModel>,
+) -> impl IntoResponse
+where
+    T1: UserRepository + Send + Sync,
+    T2: SessionRepository + Send + Sync,
+    T3: MfaRepository + Send + Sync,
+{
+    match authentication_use_case.start_login_mfa_enrolment(mfa_token_model.mfa_token).await {
+        Ok(enrolment) => (StatusCode::OK, Json(enrolment)).into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+pub async fn start_mfa_enrolment<T1, T2, T3>(
+    State(authentication_use_case): State<Arc<AuthenticationUseCase<T1, T2, T3>>>,
+    CurrentUser { id: user_id, .. }: CurrentUser,
+) -> impl IntoResponse
+where
+    T1: UserRepository + Send + Sync,
+    T2: SessionRepository + Send + Sync,
+    T3: MfaRepository + Send + Sync,
+{
+    match authentication_use_case.start_mfa_enrolment(user_id).await {
+        Ok(enrolment) => (StatusCode::OK, Json(enrolment)).into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+pub async fn confirm_mfa_enrolment<T1, T2, T3>(
+    State(authentication_use_case): State<Arc<AuthenticationUseCase<T1, T2, T3>>>,
+    CurrentUser { id: user_id, .. }: CurrentUser,
+    Json(mfa_code_model): Json<MfaCodeModel>,
+) -> impl IntoResponse
+where
+    T1: UserRepository + Send + Sync,
+    T2: SessionRepository + Send + Sync,
+    T3: MfaRepository + Send + Sync,
+{
+    match authentication_use_case.confirm_mfa_enrolment(user_id, mfa_code_model.code).await {
+        Ok(recovery_codes) => (StatusCode::OK, Json(RecoveryCodesModel { recovery_codes })).into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+pub async fn regenerate_recovery_codes<T1, T2, T3>(
+    State(authentication_use_case): State<Arc<AuthenticationUseCase<T1, T2, T3>>>,
+    CurrentUser { id: user_id, .. }: CurrentUser,
+    Json(mfa_code_model): Json<MfaCodeModel>,
+) -> impl IntoResponse
+where
+    T1: UserRepository + Send + Sync,
+    T2: SessionRepository + Send + Sync,
+    T3: MfaRepository + Send + Sync,
+{
+    match authentication_use_case.regenerate_recovery_codes(user_id, mfa_code_model.code).await {
+        Ok(recovery_codes) => (StatusCode::OK, Json(RecoveryCodesModel { recovery_codes })).into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+pub async fn disable_mfa<T1, T2, T3>(
+    State(authentication_use_case): State<Arc<AuthenticationUseCase<T1, T2, T3>>>,
+    current_user: CurrentUser,
+    Json(mfa_code_model): Json<MfaCodeModel>,
+) -> impl IntoResponse
+where
+    T1: UserRepository + Send + Sync,
+    T2: SessionRepository + Send + Sync,
+    T3: MfaRepository + Send + Sync,
+{
+    match authentication_use_case
+        .disable_mfa(current_user.id, current_user.role, mfa_code_model.code)
+        .await
+    {
+        Ok(()) => StatusCode::NO_CONTENT.into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+// Answers the same whether or not the email has an account.
+pub async fn forgot_password<T1, T2>(
+    State(password_reset_use_case): State<Arc<PasswordResetUseCase<T1, T2>>>,
+    Json(forgot_password_model): Json<ForgotPasswordModel>,
+) -> impl IntoResponse
+where
+    T1: UserRepository + Send + Sync,
+    T2: PasswordResetRepository + Send + Sync,
+{
+    match password_reset_use_case.forgot_password(forgot_password_model).await {
+        Ok(()) => (StatusCode::ACCEPTED, "If the email has an account, a reset link has been sent").into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+pub async fn reset_password<T1, T2>(
+    State(password_reset_use_case): State<Arc<PasswordResetUseCase<T1, T2>>>,
+    Json(reset_password_model): Json<ResetPasswordModel>,
+) -> impl IntoResponse
+where
+    T1: UserRepository + Send + Sync,
+    T2: PasswordResetRepository + Send + Sync,
+{
+    // Every session was revoked, so the cookies of this browser are stale too.
+    match password_reset_use_case.reset_password(reset_password_model).await {
+        Ok(()) => (StatusCode::OK, cleared_cookies(), "Password reset successfully").into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+// A finished login sets the cookies; a pending one hands back the MFA token for the next step.
+fn login_response(login_outcome: LoginOutcome, message: &'static str) -> Response {
+    match login_outcome {
+        LoginOutcome::Authenticated(passport) => {
+            (StatusCode::OK, passport_cookies(&passport), message).into_response()
+        }
+        LoginOutcome::MfaRequired { mfa_token } => (
+            StatusCode::OK,
+            Json(json!({ "status": "mfa_required", "mfa_token": mfa_token })),
+        )
+            .into_response(),
+        LoginOutcome::MfaEnrolmentRequired { mfa_token } => (
+            StatusCode::OK,
+            Json(json!({ "status": "mfa_enrolment_required", "mfa_token": mfa_token })),
+        )
+            .into_response(),
+    }
+}
+
+fn passport_cookies(passport: &Passport) -> CookieJar {
+    auth_cookies(
+        passport.access_token.clone(),
+        Duration::seconds(passport.access_token_expires_in),
+        passport.refresh_token.clone(),
+        Duration::seconds(passport.refresh_token_expires_in),
+    )
+}
+
+fn cleared_cookies() -> CookieJar {
+    auth_cookies(String::new(), Duration::ZERO, String::new(), Duration::ZERO)
+}
+
+fn auth_cookies(
+    access_token: String,
+    access_max_age: Duration,
+    refresh_token: String,
+    refresh_max_age: Duration,
+) -> CookieJar {
+    CookieJar::new()
+        .add(token_cookie("act", access_token, access_max_age))
+        .add(token_cookie("rft", refresh_token, refresh_max_age))
+}
+
+// The user agent is stored with the session so users can tell their devices apart.
+fn device(headers: &HeaderMap) -> Option<String> {
+    headers
+        .get(header::USER_AGENT)
+        .and_then(|value| value.to_str().ok())
+        .map(|user_agent| user_agent.chars().take(MAX_DEVICE_LENGTH).collect())
+}
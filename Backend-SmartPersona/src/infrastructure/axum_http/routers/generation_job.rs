@@ -0,0 +1,144 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    middleware,
+    response::{
+        sse::{Event, KeepAlive, Sse},
+        IntoResponse,
+    },
+    routing::get,
+    Json, Router,
+};
+use tokio::sync::{broadcast::error::RecvError, mpsc};
+use tokio_stream::wrappers::ReceiverStream;
+use uuid::Uuid;
+
+use crate::{
+    domain::{
+        entities::generation_job::JobStatus,
+        repo::{
+            ai_service::AIServiceRepository, generation_job::GenerationJobRepository,
+            prompt_template::PromptTemplateRepository, social_connection::SocialConnectionRepository,
+        },
+        usecase::generation_job::GenerationJobUseCase,
+    },
+    infrastructure::{
+        ai_service_client::client::AIServiceClient,
+        axum_http::{current_user::CurrentUser, middleware::user_authorization},
+        postgres::{
+            job_events::GenerationJobEvents,
+            repositories::{
+                generation_job::GenerationJobPostgres, prompt_template::PromptTemplatePostgres,
+                social_connection::SocialConnectionPostgres,
+            },
+        },
+    },
+};
+
+type JobEventsState<T1, T2, T3, T4> = (Arc<GenerationJobUseCase<T1, T2, T3, T4>>, Arc<GenerationJobEvents>);
+
+pub fn routes(
+    generation_job_use_case: Arc<GenerationJobUseCase<GenerationJobPostgres, AIServiceClient, PromptTemplatePostgres, SocialConnectionPostgres>>,
+    job_events: Arc<GenerationJobEvents>,
+) -> Router {
+    Router::new()
+        .route(
+            "/:job_id",
+            get(get_job::<GenerationJobPostgres, AIServiceClient, PromptTemplatePostgres, SocialConnectionPostgres>).with_state(Arc::clone(&generation_job_use_case)),
+        )
+        .route(
+            "/:job_id/events",
+            get(job_events_stream::<GenerationJobPostgres, AIServiceClient, PromptTemplatePostgres, SocialConnectionPostgres>)
+                .with_state((generation_job_use_case, job_events)),
+        )
+        .route_layer(middleware::from_fn(user_authorization))
+}
+
+pub async fn get_job<T1, T2, T3, T4>(
+    State(generation_job_use_case): State<Arc<GenerationJobUseCase<T1, T2, T3, T4>>>,
+    CurrentUser { id: user_id, .. }: CurrentUser,
+    Path(job_id): Path<Uuid>,
+) -> impl IntoResponse
+where
+    T1: GenerationJobRepository + Send + Sync,
+    T2: AIServiceRepository + Send + Sync,
+    T3: PromptTemplateRepository + Send + Sync,
+    T4: SocialConnectionRepository + Send + Sync,
+{
+    match generation_job_use_case.get_own(user_id, job_id).await {
+        Ok(job) => (StatusCode::OK, Json(job)).into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+// Streams one event per status change (`queued`, `running`, `succeeded`, `failed`) and
+// closes the stream once the job has finished. The final event carries the job result.
+pub async fn job_events_stream<T1, T2, T3, T4>(
+    State((generation_job_use_case, job_events)): State<JobEventsState<T1, T2, T3, T4>>,
+    CurrentUser { id: user_id, .. }: CurrentUser,
+    Path(job_id): Path<Uuid>,
+) -> impl IntoResponse
+where
+    T1: GenerationJobRepository + Send + Sync + 'static,
+    T2: AIServiceRepository + Send + Sync + 'static,
+    T3: PromptTemplateRepository + Send + Sync + 'static,
+    T4: SocialConnectionRepository + Send + Sync + 'static,
+{
+    // Subscribe before reading the current state so no transition falls in between.
+    let mut receiver = job_events.subscribe();
+
+    let mut job = match generation_job_use_case.get_own(user_id, job_id).await {
+        Ok(job) => job,
+        Err(e) => return e.into_response(),
+    };
+
+    let (sender, events) = mpsc::channel::<Result<Event, axum::Error>>(16);
+
+    tokio::spawn(async move {
+        let mut last_sent: Option<JobStatus> = None;
+
+        loop {
+            if last_sent != Some(job.status) {
+                let event = Event::default().event(event_name(job.status)).json_data(&job);
+                if sender.send(event).await.is_err() {
+                    return;
+                }
+                last_sent = Some(job.status);
+            }
+
+            if job.status.is_finished() {
+                return;
+            }
+
+            tokio::select! {
+                _ = sender.closed() => return,
+                received = receiver.recv() => match received {
+                    Ok(event) if event.job_id != job_id => continue,
+                    // A lagged receiver may have skipped this job's update, so re-read it anyway.
+                    Ok(_) | Err(RecvError::Lagged(_)) => {}
+                    Err(RecvError::Closed) => return,
+                },
+            }
+
+            job = match generation_job_use_case.get_own(user_id, job_id).await {
+                Ok(job) => job,
+                Err(_) => return,
+            };
+        }
+    });
+
+    Sse::new(ReceiverStream::new(events))
+        .keep_alive(KeepAlive::default())
+        .into_response()
+}
+
+fn event_name(status: JobStatus) -> &'static str {
+    match status {
+        JobStatus::Pending => "queued",
+        JobStatus::Running => "running",
+        JobStatus::Completed => "succeeded",
+        JobStatus::Failed => "failed",
+    }
+}
@@ -1,3 +1,12 @@
 pub mod user;
 pub mod authentication;
-pub mod ai_handlers;
\ No newline at end of file
+pub mod ai_handlers;
+pub mod profile;
+pub mod generation_job;
+pub mod prompt_template;
+pub mod admin;
+pub mod social_connection;
+pub mod well_known;
+pub mod admin_user;
+pub mod privacy;
+pub mod admin_audit;
\ No newline at end of file
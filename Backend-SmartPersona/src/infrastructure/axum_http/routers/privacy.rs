@@ -0,0 +1,170 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{Path, Query, State},
+    http::{header, StatusCode},
+    middleware,
+    response::IntoResponse,
+    routing::{get, post},
+    Json, Router,
+};
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::{
+    domain::{
+        error::AppError,
+        repo::{
+            data_export::DataExportRepository, personal_data::PersonalDataRepository, session::SessionRepository,
+            user::UserRepository,
+        },
+        service::audit_logger::AuditContext,
+        usecase::privacy::PrivacyUseCase,
+        value_object::personal_data::RequestErasureModel,
+    },
+    infrastructure::{
+        axum_http::{
+            current_user::CurrentUser,
+            middleware::{deny_impersonation, user_authorization},
+        },
+        export_archive,
+        postgres::repositories::{
+            data_export::DataExportPostgres, personal_data::PersonalDataPostgres, session::SessionPostgres,
+            user::UserPostgres,
+        },
+    },
+};
+
+#[derive(Debug, Deserialize)]
+pub struct DownloadQuery {
+    // "zip" (default) or "json".
+    pub format: Option<String>,
+}
+
+pub fn routes(
+    privacy_use_case: Arc<PrivacyUseCase<UserPostgres, SessionPostgres, DataExportPostgres, PersonalDataPostgres>>,
+) -> Router {
+    Router::new()
+        .route("/exports", post(request_export::<UserPostgres, SessionPostgres, DataExportPostgres, PersonalDataPostgres>))
+        .route("/exports/:export_id", get(get_export::<UserPostgres, SessionPostgres, DataExportPostgres, PersonalDataPostgres>))
+        .route(
+            "/exports/:export_id/download",
+            get(download_export::<UserPostgres, SessionPostgres, DataExportPostgres, PersonalDataPostgres>),
+        )
+        .route(
+            "/erasure",
+            post(request_erasure::<UserPostgres, SessionPostgres, DataExportPostgres, PersonalDataPostgres>)
+                .delete(cancel_erasure::<UserPostgres, SessionPostgres, DataExportPostgres, PersonalDataPostgres>),
+        )
+        // Layers run outside in, so the caller is known before impersonation is checked.
+        .route_layer(middleware::from_fn(deny_impersonation))
+        .route_layer(middleware::from_fn(user_authorization))
+        .with_state(privacy_use_case)
+}
+
+pub async fn request_export<T1, T2, T3, T4>(
+    State(privacy_use_case): State<Arc<PrivacyUseCase<T1, T2, T3, T4>>>,
+    CurrentUser { id: user_id, .. }: CurrentUser,
+) -> impl IntoResponse
+where
+    T1: UserRepository + Send + Sync,
+    T2: SessionRepository + Send + Sync,
+    T3: DataExportRepository + Send + Sync,
+    T4: PersonalDataRepository + Send + Sync,
+{
+    match privacy_use_case.request_export(user_id).await {
+        Ok(data_export) => (StatusCode::ACCEPTED, Json(data_export)).into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+pub async fn get_export<T1, T2, T3, T4>(
+    State(privacy_use_case): State<Arc<PrivacyUseCase<T1, T2, T3, T4>>>,
+    CurrentUser { id: user_id, .. }: CurrentUser,
+    Path(export_id): Path<Uuid>,
+) -> impl IntoResponse
+where
+    T1: UserRepository + Send + Sync,
+    T2: SessionRepository + Send + Sync,
+    T3: DataExportRepository + Send + Sync,
+    T4: PersonalDataRepository + Send + Sync,
+{
+    match privacy_use_case.get_export(user_id, export_id).await {
+        Ok(data_export) => (StatusCode::OK, Json(data_export)).into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+pub async fn download_export<T1, T2, T3, T4>(
+    State(privacy_use_case): State<Arc<PrivacyUseCase<T1, T2, T3, T4>>>,
+    CurrentUser { id: user_id, .. }: CurrentUser,
+    Path(export_id): Path<Uuid>,
+    Query(query): Query<DownloadQuery>,
+) -> impl IntoResponse
+where
+    T1: UserRepository + Send + Sync,
+    T2: SessionRepository + Send + Sync,
+    T3: DataExportRepository + Send + Sync,
+    T4: PersonalDataRepository + Send + Sync,
+{
+    let data = match privacy_use_case.download_export(user_id, export_id).await {
+        Ok(data) => data,
+        Err(e) => return e.into_response(),
+    };
+
+    if query.format.as_deref() == Some("json") {
+        let disposition = format!("attachment; filename=\"personal-data-{}.json\"", export_id);
+        return (StatusCode::OK, [(header::CONTENT_DISPOSITION, disposition)], Json(data)).into_response();
+    }
+
+    match export_archive::to_zip(&data) {
+        Ok(archive) => {
+            let disposition = format!("attachment; filename=\"personal-data-{}.zip\"", export_id);
+            (
+                StatusCode::OK,
+                [(header::CONTENT_TYPE, "application/zip".to_string()), (header::CONTENT_DISPOSITION, disposition)],
+                archive,
+            )
+                .into_response()
+        }
+        Err(e) => AppError::from(e).into_response(),
+    }
+}
+
+// Also serves `DELETE /users/me`, so deleting an account always goes through the grace period.
+pub async fn request_erasure<T1, T2, T3, T4>(
+    State(privacy_use_case): State<Arc<PrivacyUseCase<T1, T2, T3, T4>>>,
+    current_user: CurrentUser,
+    context: AuditContext,
+    Json(request_erasure_model): Json<RequestErasureModel>,
+) -> impl IntoResponse
+where
+    T1: UserRepository + Send + Sync,
+    T2: SessionRepository + Send + Sync,
+    T3: DataExportRepository + Send + Sync,
+    T4: PersonalDataRepository + Send + Sync,
+{
+    match privacy_use_case
+        .request_erasure(current_user.id, current_user.session_id, request_erasure_model, &context)
+        .await
+    {
+        Ok(erasure) => (StatusCode::ACCEPTED, Json(erasure)).into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+pub async fn cancel_erasure<T1, T2, T3, T4>(
+    State(privacy_use_case): State<Arc<PrivacyUseCase<T1, T2, T3, T4>>>,
+    CurrentUser { id: user_id, .. }: CurrentUser,
+) -> impl IntoResponse
+where
+    T1: UserRepository + Send + Sync,
+    T2: SessionRepository + Send + Sync,
+    T3: DataExportRepository + Send + Sync,
+    T4: PersonalDataRepository + Send + Sync,
+{
+    match privacy_use_case.cancel_erasure(user_id).await {
+        Ok(()) => StatusCode::NO_CONTENT.into_response(),
+        Err(e) => e.into_response(),
+    }
+}
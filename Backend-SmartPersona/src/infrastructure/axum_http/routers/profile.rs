@@ -0,0 +1,196 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    middleware,
+    response::IntoResponse,
+    routing::{get, post},
+    Json, Router,
+};
+use uuid::Uuid;
+
+use crate::{
+    domain::{
+        repo::{profile::ProfileRepository, user::UserRepository},
+        service::audit_logger::{AuditContext, AuditLogger},
+        usecase::profile::ProfileUseCase,
+        value_object::profile::{CreateProfileModel, EditProfileModel},
+    },
+    infrastructure::{
+        axum_http::{
+            current_user::{permissions::ProfileWriteOwn, CurrentUser, RequirePermission},
+            middleware::user_authorization,
+        },
+        postgres::{
+            postgres_connection::DbPool,
+            repositories::{profile::ProfilePostgres, user::UserPostgres},
+        },
+    },
+};
+
+pub fn routes(db_pool: Arc<DbPool>, audit_logger: Arc<AuditLogger>) -> Router {
+    let profile_repository = ProfilePostgres::new(Arc::clone(&db_pool));
+    let user_repository = UserPostgres::new(db_pool);
+    let profile_use_case = ProfileUseCase::new(Arc::new(profile_repository), Arc::new(user_repository), audit_logger);
+
+    Router::new()
+        .route(
+            "/",
+            get(list_profiles::<ProfilePostgres, UserPostgres>)
+                .post(create_profile::<ProfilePostgres, UserPostgres>),
+        )
+        .route(
+            "/:profile_id",
+            get(get_profile::<ProfilePostgres, UserPostgres>)
+                .patch(update_profile::<ProfilePostgres, UserPostgres>)
+                .delete(delete_profile::<ProfilePostgres, UserPostgres>),
+        )
+        .route(
+            "/:profile_id/slug",
+            post(regenerate_slug::<ProfilePostgres, UserPostgres>)
+                .delete(revoke_slug::<ProfilePostgres, UserPostgres>),
+        )
+        .route_layer(middleware::from_fn(user_authorization))
+        .with_state(Arc::new(profile_use_case))
+}
+
+pub fn public_routes(db_pool: Arc<DbPool>, audit_logger: Arc<AuditLogger>) -> Router {
+    let profile_repository = ProfilePostgres::new(Arc::clone(&db_pool));
+    let user_repository = UserPostgres::new(db_pool);
+    let profile_use_case = ProfileUseCase::new(Arc::new(profile_repository), Arc::new(user_repository), audit_logger);
+
+    Router::new()
+        .route("/:slug", get(get_public_profile::<ProfilePostgres, UserPostgres>))
+        .with_state(Arc::new(profile_use_case))
+}
+
+pub async fn create_profile<T1, T2>(
+    State(profile_use_case): State<Arc<ProfileUseCase<T1, T2>>>,
+    _: RequirePermission<ProfileWriteOwn>,
+    CurrentUser { id: user_id, .. }: CurrentUser,
+    Json(create_profile_model): Json<CreateProfileModel>,
+) -> impl IntoResponse
+where
+    T1: ProfileRepository + Send + Sync,
+    T2: UserRepository + Send + Sync,
+{
+    match profile_use_case.create(user_id, create_profile_model).await {
+        Ok(profile) => (StatusCode::CREATED, Json(profile)).into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+pub async fn list_profiles<T1, T2>(
+    State(profile_use_case): State<Arc<ProfileUseCase<T1, T2>>>,
+    CurrentUser { id: user_id, .. }: CurrentUser,
+) -> impl IntoResponse
+where
+    T1: ProfileRepository + Send + Sync,
+    T2: UserRepository + Send + Sync,
+{
+    match profile_use_case.list_own(user_id).await {
+        Ok(profiles) => (StatusCode::OK, Json(profiles)).into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+pub async fn get_profile<T1, T2>(
+    State(profile_use_case): State<Arc<ProfileUseCase<T1, T2>>>,
+    CurrentUser { id: user_id, .. }: CurrentUser,
+    Path(profile_id): Path<Uuid>,
+) -> impl IntoResponse
+where
+    T1: ProfileRepository + Send + Sync,
+    T2: UserRepository + Send + Sync,
+{
+    match profile_use_case.get_own(user_id, profile_id).await {
+        Ok(profile) => (StatusCode::OK, Json(profile)).into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+pub async fn update_profile<T1, T2>(
+    State(profile_use_case): State<Arc<ProfileUseCase<T1, T2>>>,
+    _: RequirePermission<ProfileWriteOwn>,
+    CurrentUser { id: user_id, .. }: CurrentUser,
+    Path(profile_id): Path<Uuid>,
+    context: AuditContext,
+    Json(edit_profile_model): Json<EditProfileModel>,
+) -> impl IntoResponse
+where
+    T1: ProfileRepository + Send + Sync,
+    T2: UserRepository + Send + Sync,
+{
+    match profile_use_case
+        .update_own(user_id, profile_id, edit_profile_model, &context)
+        .await
+    {
+        Ok(profile) => (StatusCode::OK, Json(profile)).into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+pub async fn delete_profile<T1, T2>(
+    State(profile_use_case): State<Arc<ProfileUseCase<T1, T2>>>,
+    _: RequirePermission<ProfileWriteOwn>,
+    CurrentUser { id: user_id, .. }: CurrentUser,
+    Path(profile_id): Path<Uuid>,
+) -> impl IntoResponse
+where
+    T1: ProfileRepository + Send + Sync,
+    T2: UserRepository + Send + Sync,
+{
+    match profile_use_case.delete_own(user_id, profile_id).await {
+        Ok(()) => StatusCode::NO_CONTENT.into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+pub async fn regenerate_slug<T1, T2>(
+    State(profile_use_case): State<Arc<ProfileUseCase<T1, T2>>>,
+    _: RequirePermission<ProfileWriteOwn>,
+    CurrentUser { id: user_id, .. }: CurrentUser,
+    Path(profile_id): Path<Uuid>,
+    context: AuditContext,
+) -> impl IntoResponse
+where
+    T1: ProfileRepository + Send + Sync,
+    T2: UserRepository + Send + Sync,
+{
+    match profile_use_case.regenerate_slug(user_id, profile_id, &context).await {
+        Ok(profile) => (StatusCode::OK, Json(profile)).into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+pub async fn revoke_slug<T1, T2>(
+    State(profile_use_case): State<Arc<ProfileUseCase<T1, T2>>>,
+    _: RequirePermission<ProfileWriteOwn>,
+    CurrentUser { id: user_id, .. }: CurrentUser,
+    Path(profile_id): Path<Uuid>,
+    context: AuditContext,
+) -> impl IntoResponse
+where
+    T1: ProfileRepository + Send + Sync,
+    T2: UserRepository + Send + Sync,
+{
+    match profile_use_case.revoke_slug(user_id, profile_id, &context).await {
+        Ok(profile) => (StatusCode::OK, Json(profile)).into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+pub async fn get_public_profile<T1, T2>(
+    State(profile_use_case): State<Arc<ProfileUseCase<T1, T2>>>,
+    Path(slug): Path<String>,
+) -> impl IntoResponse
+where
+    T1: ProfileRepository + Send + Sync,
+    T2: UserRepository + Send + Sync,
+{
+    match profile_use_case.find_public_by_slug(slug).await {
+        Ok(profile) => (StatusCode::OK, Json(profile)).into_response(),
+        Err(e) => e.into_response(),
+    }
+}
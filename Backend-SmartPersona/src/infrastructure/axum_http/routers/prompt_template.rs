@@ -0,0 +1,171 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+    routing::{get, post},
+    Json, Router,
+};
+use uuid::Uuid;
+
+use crate::{
+    domain::{
+        repo::prompt_template::PromptTemplateRepository,
+        usecase::prompt_template::PromptTemplateUseCase,
+        value_object::prompt_template::{
+            CreatePromptTemplateModel, EditPromptTemplateModel, RollbackPromptTemplateModel,
+        },
+    },
+    infrastructure::{
+        axum_http::current_user::{
+            permissions::{TemplateRead, TemplateWrite},
+            RequirePermission,
+        },
+        postgres::{postgres_connection::DbPool, repositories::prompt_template::PromptTemplatePostgres},
+    },
+};
+
+pub fn routes(db_pool: Arc<DbPool>) -> Router {
+    let prompt_template_repository = PromptTemplatePostgres::new(db_pool);
+    let prompt_template_use_case = PromptTemplateUseCase::new(Arc::new(prompt_template_repository));
+
+    Router::new()
+        .route(
+            "/",
+            get(list_prompt_templates::<PromptTemplatePostgres>)
+                .post(create_prompt_template::<PromptTemplatePostgres>),
+        )
+        .route(
+            "/:template_id",
+            get(get_prompt_template::<PromptTemplatePostgres>)
+                .patch(update_prompt_template::<PromptTemplatePostgres>)
+                .delete(delete_prompt_template::<PromptTemplatePostgres>),
+        )
+        .route("/:template_id/versions", get(list_prompt_template_versions::<PromptTemplatePostgres>))
+        .route(
+            "/:template_id/versions/:version",
+            get(get_prompt_template_version::<PromptTemplatePostgres>),
+        )
+        .route("/:template_id/rollback", post(rollback_prompt_template::<PromptTemplatePostgres>))
+        .with_state(Arc::new(prompt_template_use_case))
+}
+
+pub async fn list_prompt_templates<T>(
+    State(prompt_template_use_case): State<Arc<PromptTemplateUseCase<T>>>,
+    _: RequirePermission<TemplateRead>,
+) -> impl IntoResponse
+where
+    T: PromptTemplateRepository + Send + Sync,
+{
+    match prompt_template_use_case.list().await {
+        Ok(templates) => (StatusCode::OK, Json(templates)).into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+pub async fn get_prompt_template<T>(
+    State(prompt_template_use_case): State<Arc<PromptTemplateUseCase<T>>>,
+    _: RequirePermission<TemplateRead>,
+    Path(template_id): Path<Uuid>,
+) -> impl IntoResponse
+where
+    T: PromptTemplateRepository + Send + Sync,
+{
+    match prompt_template_use_case.get(template_id).await {
+        Ok(template) => (StatusCode::OK, Json(template)).into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+pub async fn create_prompt_template<T>(
+    State(prompt_template_use_case): State<Arc<PromptTemplateUseCase<T>>>,
+    _: RequirePermission<TemplateWrite>,
+    Json(create_prompt_template_model): Json<CreatePromptTemplateModel>,
+) -> impl IntoResponse
+where
+    T: PromptTemplateRepository + Send + Sync,
+{
+    match prompt_template_use_case.create(create_prompt_template_model).await {
+        Ok(template) => (StatusCode::CREATED, Json(template)).into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+pub async fn update_prompt_template<T>(
+    State(prompt_template_use_case): State<Arc<PromptTemplateUseCase<T>>>,
+    _: RequirePermission<TemplateWrite>,
+    Path(template_id): Path<Uuid>,
+    Json(edit_prompt_template_model): Json<EditPromptTemplateModel>,
+) -> impl IntoResponse
+where
+    T: PromptTemplateRepository + Send + Sync,
+{
+    match prompt_template_use_case
+        .update(template_id, edit_prompt_template_model)
+        .await
+    {
+        Ok(template) => (StatusCode::OK, Json(template)).into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+pub async fn delete_prompt_template<T>(
+    State(prompt_template_use_case): State<Arc<PromptTemplateUseCase<T>>>,
+    _: RequirePermission<TemplateWrite>,
+    Path(template_id): Path<Uuid>,
+) -> impl IntoResponse
+where
+    T: PromptTemplateRepository + Send + Sync,
+{
+    match prompt_template_use_case.delete(template_id).await {
+        Ok(()) => StatusCode::NO_CONTENT.into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+pub async fn list_prompt_template_versions<T>(
+    State(prompt_template_use_case): State<Arc<PromptTemplateUseCase<T>>>,
+    _: RequirePermission<TemplateRead>,
+    Path(template_id): Path<Uuid>,
+) -> impl IntoResponse
+where
+    T: PromptTemplateRepository + Send + Sync,
+{
+    match prompt_template_use_case.list_versions(template_id).await {
+        Ok(versions) => (StatusCode::OK, Json(versions)).into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+pub async fn get_prompt_template_version<T>(
+    State(prompt_template_use_case): State<Arc<PromptTemplateUseCase<T>>>,
+    _: RequirePermission<TemplateRead>,
+    Path((template_id, version)): Path<(Uuid, i32)>,
+) -> impl IntoResponse
+where
+    T: PromptTemplateRepository + Send + Sync,
+{
+    match prompt_template_use_case.get_version(template_id, version).await {
+        Ok(version) => (StatusCode::OK, Json(version)).into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+pub async fn rollback_prompt_template<T>(
+    State(prompt_template_use_case): State<Arc<PromptTemplateUseCase<T>>>,
+    _: RequirePermission<TemplateWrite>,
+    Path(template_id): Path<Uuid>,
+    Json(rollback_prompt_template_model): Json<RollbackPromptTemplateModel>,
+) -> impl IntoResponse
+where
+    T: PromptTemplateRepository + Send + Sync,
+{
+    match prompt_template_use_case
+        .rollback(template_id, rollback_prompt_template_model.version)
+        .await
+    {
+        Ok(template) => (StatusCode::OK, Json(template)).into_response(),
+        Err(e) => e.into_response(),
+    }
+}
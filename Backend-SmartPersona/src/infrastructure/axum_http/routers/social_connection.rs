@@ -0,0 +1,87 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    middleware,
+    response::IntoResponse,
+    routing::{delete, get},
+    Json, Router,
+};
+
+use crate::{
+    domain::{
+        repo::social_connection::SocialConnectionRepository,
+        usecase::social_connection::SocialConnectionUseCase,
+        value_object::social_connection::LinkSocialConnectionModel,
+    },
+    infrastructure::{
+        axum_http::{
+            current_user::{permissions::SocialConnect, CurrentUser, RequirePermission},
+            middleware::user_authorization,
+        },
+        postgres::{postgres_connection::DbPool, repositories::social_connection::SocialConnectionPostgres},
+        token_encryption::EnvelopeCipher,
+    },
+};
+
+pub fn routes(db_pool: Arc<DbPool>, cipher: Arc<EnvelopeCipher>) -> Router {
+    let social_connection_repository = SocialConnectionPostgres::new(db_pool, cipher);
+    let social_connection_use_case = SocialConnectionUseCase::new(Arc::new(social_connection_repository));
+
+    Router::new()
+        .route(
+            "/",
+            get(list_social_connections::<SocialConnectionPostgres>)
+                .post(link_social_connection::<SocialConnectionPostgres>),
+        )
+        .route("/:platform", delete(unlink_social_connection::<SocialConnectionPostgres>))
+        .route_layer(middleware::from_fn(user_authorization))
+        .with_state(Arc::new(social_connection_use_case))
+}
+
+pub async fn list_social_connections<T>(
+    State(social_connection_use_case): State<Arc<SocialConnectionUseCase<T>>>,
+    CurrentUser { id: user_id, .. }: CurrentUser,
+) -> impl IntoResponse
+where
+    T: SocialConnectionRepository + Send + Sync,
+{
+    match social_connection_use_case.list(user_id).await {
+        Ok(connections) => (StatusCode::OK, Json(connections)).into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+pub async fn link_social_connection<T>(
+    State(social_connection_use_case): State<Arc<SocialConnectionUseCase<T>>>,
+    _: RequirePermission<SocialConnect>,
+    CurrentUser { id: user_id, .. }: CurrentUser,
+    Json(link_social_connection_model): Json<LinkSocialConnectionModel>,
+) -> impl IntoResponse
+where
+    T: SocialConnectionRepository + Send + Sync,
+{
+    match social_connection_use_case
+        .link(user_id, link_social_connection_model)
+        .await
+    {
+        Ok(connection) => (StatusCode::CREATED, Json(connection)).into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+pub async fn unlink_social_connection<T>(
+    State(social_connection_use_case): State<Arc<SocialConnectionUseCase<T>>>,
+    _: RequirePermission<SocialConnect>,
+    CurrentUser { id: user_id, .. }: CurrentUser,
+    Path(platform): Path<String>,
+) -> impl IntoResponse
+where
+    T: SocialConnectionRepository + Send + Sync,
+{
+    match social_connection_use_case.unlink(user_id, platform).await {
+        Ok(()) => StatusCode::NO_CONTENT.into_response(),
+        Err(e) => e.into_response(),
+    }
+}
@@ -1,35 +1,190 @@
 use std::sync::Arc;
 
-use axum::{extract::State, http::StatusCode, response::IntoResponse, routing::post, Json, Router};
+use axum::{
+    extract::State,
+    http::StatusCode,
+    middleware,
+    response::IntoResponse,
+    routing::{delete, get, post},
+    Json, Router,
+};
+use serde_json::json;
 
-use crate::{domain::{repo::user::UserRepository, usecase::user::UserUseCase, value_object::user::RegisterUserModel}, infrastructure::postgres::{postgres_connection::DbPool, repositories::user::UserPostgres}};
+use crate::{
+    config::config_model::Config,
+    domain::{
+        repo::{mailer::SharedMailer, session::SessionRepository, user::UserRepository},
+        service::audit_logger::{AuditContext, AuditLogger},
+        usecase::{
+            privacy::PrivacyUseCase,
+            user::{EmailVerificationSettings, UserUseCase},
+        },
+        value_object::user::{
+            ChangePasswordModel, RegisterUserModel, ResendVerificationModel, UpdateUserModel, VerifyEmailModel,
+        },
+    },
+    infrastructure::{
+        axum_http::{current_user::CurrentUser, middleware::user_authorization, routers::privacy},
+        jwt_authentication::jwt_keys::JwtKeys,
+        password_policy::PasswordValidator,
+        postgres::{
+            postgres_connection::DbPool,
+            repositories::{
+                data_export::DataExportPostgres, personal_data::PersonalDataPostgres, session::SessionPostgres,
+                user::UserPostgres,
+            },
+        },
+    },
+};
 
 
 
-pub fn routes(db_pool: Arc<DbPool>) -> Router {
-    let user_repository = UserPostgres::new(db_pool);
-    let user_use_case = UserUseCase::new(Arc::new(user_repository));
+pub fn routes(
+    db_pool: Arc<DbPool>,
+    jwt_keys: Arc<JwtKeys>,
+    mailer: SharedMailer,
+    password_validator: Arc<PasswordValidator>,
+    audit_logger: Arc<AuditLogger>,
+    privacy_use_case: Arc<PrivacyUseCase<UserPostgres, SessionPostgres, DataExportPostgres, PersonalDataPostgres>>,
+    config: &Config,
+) -> Router {
+    let user_repository = UserPostgres::new(Arc::clone(&db_pool));
+    let session_repository = SessionPostgres::new(db_pool);
+    let user_use_case = UserUseCase::new(
+        Arc::new(user_repository),
+        Arc::new(session_repository),
+        jwt_keys,
+        mailer,
+        password_validator,
+        audit_logger,
+        EmailVerificationSettings {
+            verify_url: format!("{}/verify-email", config.app.frontend_url.trim_end_matches('/')),
+            token_lifetime: config.email_verification.token_lifetime,
+        },
+    );
+
+    // Deleting the account schedules an erasure with a grace period, same as POST /privacy/erasure.
+    let account_deletion_routes = Router::new()
+        .route(
+            "/me",
+            delete(privacy::request_erasure::<UserPostgres, SessionPostgres, DataExportPostgres, PersonalDataPostgres>)
+                .route_layer(middleware::from_fn(user_authorization)),
+        )
+        .with_state(privacy_use_case);
 
     Router::new()
-        .route("/", post(register))
+        .route("/", post(register::<UserPostgres, SessionPostgres>))
+        .route("/verify-email", post(verify_email::<UserPostgres, SessionPostgres>))
+        .route("/verify-email/resend", post(resend_verification::<UserPostgres, SessionPostgres>))
+        .route(
+            "/me",
+            get(get_me::<UserPostgres, SessionPostgres>)
+                .patch(update_me::<UserPostgres, SessionPostgres>)
+                .route_layer(middleware::from_fn(user_authorization)),
+        )
+        .route(
+            "/me/password",
+            post(change_password::<UserPostgres, SessionPostgres>).route_layer(middleware::from_fn(user_authorization)),
+        )
         .with_state(Arc::new(user_use_case))
+        .merge(account_deletion_routes)
 }
 
-pub async fn register<T>(
-    State(user_use_case): State<Arc<UserUseCase<T>>>,
+pub async fn register<T1, T2>(
+    State(user_use_case): State<Arc<UserUseCase<T1, T2>>>,
+    context: AuditContext,
     Json(register_user_model): Json<RegisterUserModel>,
 ) -> impl IntoResponse
 where
-    T: UserRepository + Send + Sync,
+    T1: UserRepository + Send + Sync,
+    T2: SessionRepository + Send + Sync,
 {
-    match user_use_case.register(register_user_model).await {
-        Ok(user_id) => (
-            StatusCode::CREATED,
-            format!("Register user id: {} successfully", user_id),
+    match user_use_case.register(register_user_model, &context).await {
+        Ok(()) => (
+            StatusCode::ACCEPTED,
+            "Registration received, check your email to continue",
         )
             .into_response(),
-        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response(),
+        Err(e) => e.into_response(),
     }
         
        
-}
\ No newline at end of file
+}
+
+pub async fn verify_email<T1, T2>(
+    State(user_use_case): State<Arc<UserUseCase<T1, T2>>>,
+    context: AuditContext,
+    Json(verify_email_model): Json<VerifyEmailModel>,
+) -> impl IntoResponse
+where
+    T1: UserRepository + Send + Sync,
+    T2: SessionRepository + Send + Sync,
+{
+    match user_use_case.verify_email(verify_email_model, &context).await {
+        Ok(()) => (StatusCode::OK, "Email verified successfully").into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+pub async fn resend_verification<T1, T2>(
+    State(user_use_case): State<Arc<UserUseCase<T1, T2>>>,
+    Json(resend_verification_model): Json<ResendVerificationModel>,
+) -> impl IntoResponse
+where
+    T1: UserRepository + Send + Sync,
+    T2: SessionRepository + Send + Sync,
+{
+    match user_use_case.resend_verification(resend_verification_model).await {
+        Ok(()) => (StatusCode::ACCEPTED, "If the email has a pending account, a verification link has been sent").into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+pub async fn get_me<T1, T2>(
+    State(user_use_case): State<Arc<UserUseCase<T1, T2>>>,
+    CurrentUser { id: user_id, .. }: CurrentUser,
+) -> impl IntoResponse
+where
+    T1: UserRepository + Send + Sync,
+    T2: SessionRepository + Send + Sync,
+{
+    match user_use_case.get_me(user_id).await {
+        Ok(user) => (StatusCode::OK, Json(user)).into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+pub async fn update_me<T1, T2>(
+    State(user_use_case): State<Arc<UserUseCase<T1, T2>>>,
+    CurrentUser { id: user_id, .. }: CurrentUser,
+    context: AuditContext,
+    Json(update_user_model): Json<UpdateUserModel>,
+) -> impl IntoResponse
+where
+    T1: UserRepository + Send + Sync,
+    T2: SessionRepository + Send + Sync,
+{
+    match user_use_case.update_me(user_id, update_user_model, &context).await {
+        Ok(user) => (StatusCode::OK, Json(user)).into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+pub async fn change_password<T1, T2>(
+    State(user_use_case): State<Arc<UserUseCase<T1, T2>>>,
+    current_user: CurrentUser,
+    context: AuditContext,
+    Json(change_password_model): Json<ChangePasswordModel>,
+) -> impl IntoResponse
+where
+    T1: UserRepository + Send + Sync,
+    T2: SessionRepository + Send + Sync,
+{
+    match user_use_case
+        .change_password(current_user.id, current_user.session_id, change_password_model, &context)
+        .await
+    {
+        Ok(revoked) => (StatusCode::OK, Json(json!({ "revoked_sessions": revoked }))).into_response(),
+        Err(e) => e.into_response(),
+    }
+}
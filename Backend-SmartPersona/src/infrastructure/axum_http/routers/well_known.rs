@@ -0,0 +1,16 @@
+use std::sync::Arc;
+
+use axum::{extract::State, response::IntoResponse, routing::get, Json, Router};
+
+use crate::infrastructure::jwt_authentication::jwt_keys::JwtKeys;
+
+pub fn routes(jwt_keys: Arc<JwtKeys>) -> Router {
+    Router::new()
+        .route("/jwks.json", get(jwks))
+        .with_state(jwt_keys)
+}
+
+// Public keys other services use to verify our access tokens without sharing a secret.
+pub async fn jwks(State(jwt_keys): State<Arc<JwtKeys>>) -> impl IntoResponse {
+    Json(jwt_keys.jwks())
+}
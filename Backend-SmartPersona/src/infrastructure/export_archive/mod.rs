@@ -0,0 +1,27 @@
+use std::io::{Cursor, Write};
+
+use anyhow::Result;
+use serde_json::Value;
+use zip::{write::SimpleFileOptions, CompressionMethod, ZipWriter};
+
+/// Packs a JSON export into a ZIP with one pretty-printed `<field>.json` per top-level
+/// field, so each part can be opened on its own.
+pub fn to_zip(export: &Value) -> Result<Vec<u8>> {
+    let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+    let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+
+    match export {
+        Value::Object(fields) => {
+            for (name, value) in fields {
+                zip.start_file(format!("{}.json", name), options)?;
+                zip.write_all(&serde_json::to_vec_pretty(value)?)?;
+            }
+        }
+        other => {
+            zip.start_file("export.json", options)?;
+            zip.write_all(&serde_json::to_vec_pretty(other)?)?;
+        }
+    }
+
+    Ok(zip.finish()?.into_inner())
+}
@@ -0,0 +1,120 @@
+use std::{sync::Arc, time::Duration};
+
+use tracing::{error, info};
+
+use crate::{
+    config::config_model::{Privacy, TokenRefresh, Worker},
+    domain::{
+        repo::{
+            ai_service::AIServiceRepository, data_export::DataExportRepository, generation_job::GenerationJobRepository,
+            personal_data::PersonalDataRepository, prompt_template::PromptTemplateRepository,
+            session::SessionRepository, social_connection::SocialConnectionRepository, user::UserRepository,
+        },
+        usecase::{
+            generation_job::GenerationJobUseCase, privacy::PrivacyUseCase,
+            social_connection_refresh::SocialConnectionRefreshUseCase,
+        },
+    },
+};
+
+/// Spawns `concurrency` polling workers plus one task that returns jobs abandoned by a
+/// crashed worker to the queue once their lease expires. Live workers send a heartbeat
+/// three times per lease so their jobs are never released underneath them.
+pub fn spawn<T1, T2, T3, T4>(generation_job_use_case: Arc<GenerationJobUseCase<T1, T2, T3, T4>>, config: Worker)
+where
+    T1: GenerationJobRepository + Send + Sync + 'static,
+    T2: AIServiceRepository + Send + Sync + 'static,
+    T3: PromptTemplateRepository + Send + Sync + 'static,
+    T4: SocialConnectionRepository + Send + Sync + 'static,
+{
+    let poll_interval = Duration::from_millis(config.poll_interval_ms);
+    let lease_timeout = chrono::Duration::seconds(config.lease_timeout as i64);
+    let heartbeat_interval = Duration::from_secs((config.lease_timeout / 3).max(1));
+
+    for worker_id in 0..config.concurrency {
+        let use_case = Arc::clone(&generation_job_use_case);
+        tokio::spawn(async move {
+            info!("generation job worker {} started", worker_id);
+            loop {
+                match use_case.process_next(heartbeat_interval).await {
+                    Ok(true) => continue,
+                    Ok(false) => tokio::time::sleep(poll_interval).await,
+                    Err(e) => {
+                        error!("generation job worker {} failed: {}", worker_id, e);
+                        tokio::time::sleep(poll_interval).await;
+                    }
+                }
+            }
+        });
+    }
+
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(config.lease_timeout.max(1)));
+        loop {
+            interval.tick().await;
+            match generation_job_use_case.release_stale(lease_timeout).await {
+                Ok(0) => {}
+                Ok(released) => info!("released {} stale generation jobs", released),
+                Err(e) => error!("failed to release stale generation jobs: {}", e),
+            }
+        }
+    });
+}
+
+/// Spawns the task that refreshes social connection tokens before they expire.
+pub fn spawn_token_refresh<T>(refresh_use_case: Arc<SocialConnectionRefreshUseCase<T>>, config: TokenRefresh)
+where
+    T: SocialConnectionRepository + Send + Sync + 'static,
+{
+    let window = chrono::Duration::seconds(config.window as i64);
+    let retry_after = chrono::Duration::seconds(config.retry_after as i64);
+
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(config.interval.max(1)));
+        loop {
+            interval.tick().await;
+            match refresh_use_case.refresh_expiring(window, retry_after, config.batch_size).await {
+                Ok(0) => {}
+                Ok(refreshed) => info!("refreshed {} social connection tokens", refreshed),
+                Err(e) => error!("failed to refresh social connection tokens: {}", e),
+            }
+        }
+    });
+}
+
+/// Spawns the task that builds requested data exports, purges expired ones and erases
+/// accounts whose grace period has ended.
+pub fn spawn_privacy<T1, T2, T3, T4>(privacy_use_case: Arc<PrivacyUseCase<T1, T2, T3, T4>>, config: Privacy)
+where
+    T1: UserRepository + Send + Sync + 'static,
+    T2: SessionRepository + Send + Sync + 'static,
+    T3: DataExportRepository + Send + Sync + 'static,
+    T4: PersonalDataRepository + Send + Sync + 'static,
+{
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(config.poll_interval.max(Duration::from_secs(1)));
+        loop {
+            interval.tick().await;
+            loop {
+                match privacy_use_case.process_next_export().await {
+                    Ok(true) => continue,
+                    Ok(false) => break,
+                    Err(e) => {
+                        error!("failed to process data export: {}", e);
+                        break;
+                    }
+                }
+            }
+            match privacy_use_case.purge_expired_exports().await {
+                Ok(0) => {}
+                Ok(purged) => info!("purged {} expired data exports", purged),
+                Err(e) => error!("failed to purge expired data exports: {}", e),
+            }
+            match privacy_use_case.erase_due().await {
+                Ok(0) => {}
+                Ok(erased) => info!("erased {} accounts", erased),
+                Err(e) => error!("failed to erase accounts: {}", e),
+            }
+        }
+    });
+}
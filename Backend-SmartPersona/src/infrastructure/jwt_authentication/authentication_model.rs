@@ -4,4 +4,43 @@ use serde::{Deserialize, Serialize};
 pub struct LoginModel {
     pub username: String,
     pub password: String,
-}
\ No newline at end of file
+}
+// Second login step: the `mfa_token` from the first step plus a TOTP or recovery code.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MfaVerifyModel {
+    pub mfa_token: String,
+    pub code: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MfaTokenModel {
+    pub mfa_token: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MfaCodeModel {
+    pub code: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MfaEnrolmentModel {
+    pub secret: String,
+    pub otpauth_uri: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecoveryCodesModel {
+    pub recovery_codes: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ForgotPasswordModel {
+    pub email: String,
+}
+
+// `token` comes from the link in the reset email.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResetPasswordModel {
+    pub token: String,
+    pub new_password: String,
+}
@@ -0,0 +1,289 @@
+use std::collections::HashMap;
+
+use anyhow::{Context, Result};
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use jsonwebtoken::{
+    jwk::{
+        AlgorithmParameters, CommonParameters, EllipticCurve, Jwk, JwkSet, KeyAlgorithm,
+        OctetKeyPairParameters, OctetKeyPairType, PublicKeyUse, RSAKeyParameters, RSAKeyType,
+    },
+    Algorithm, DecodingKey, EncodingKey,
+};
+use pkcs1::der::Decode;
+use spki::{der::DecodePem, SubjectPublicKeyInfoOwned};
+
+use crate::config::config_model::Jwt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TokenKind {
+    UserAccess,
+    UserRefresh,
+    AdminAccess,
+    AdminRefresh,
+    MfaPending,
+    EmailVerification,
+}
+
+impl TokenKind {
+    pub fn is_refresh(&self) -> bool {
+        matches!(self, Self::UserRefresh | Self::AdminRefresh)
+    }
+
+    pub fn is_admin(&self) -> bool {
+        matches!(self, Self::AdminAccess | Self::AdminRefresh)
+    }
+
+    /// The refresh token kind issued alongside this access token kind.
+    pub fn refresh(&self) -> Self {
+        if self.is_admin() { Self::AdminRefresh } else { Self::UserRefresh }
+    }
+}
+
+/// Signing and verification keys, loaded once at startup, together with the issuer and
+/// audiences every token is stamped with and checked against.
+pub struct JwtKeys {
+    pub issuer: String,
+    user_audience: String,
+    admin_audience: String,
+    mfa_audience: String,
+    email_verification_audience: String,
+    pub signer: JwtSigner,
+}
+
+/// With HS256 every token kind has its own secret. With RS256/EdDSA one private key signs
+/// everything, the token kind travels in the `typ` header, and tokens name their key in
+/// `kid` so retired keys can keep verifying until their tokens expire.
+pub enum JwtSigner {
+    Hmac {
+        secrets: HashMap<TokenKind, String>,
+    },
+    Asymmetric {
+        algorithm: Algorithm,
+        active_key_id: String,
+        encoding_key: EncodingKey,
+        decoding_keys: HashMap<String, DecodingKey>,
+        jwks: JwkSet,
+    },
+}
+
+impl JwtKeys {
+    pub fn load(config: &Jwt) -> Result<Self> {
+        Ok(Self {
+            issuer: config.issuer.clone(),
+            user_audience: config.audience.clone(),
+            admin_audience: config.admin_audience.clone(),
+            mfa_audience: format!("{}:mfa", config.audience),
+            email_verification_audience: format!("{}:email-verification", config.audience),
+            signer: JwtSigner::load(config)?,
+        })
+    }
+
+    pub fn audience(&self, kind: TokenKind) -> &str {
+        match kind {
+            TokenKind::MfaPending => &self.mfa_audience,
+            TokenKind::EmailVerification => &self.email_verification_audience,
+            _ if kind.is_admin() => &self.admin_audience,
+            _ => &self.user_audience,
+        }
+    }
+
+    /// Public keys for `/.well-known/jwks.json`; empty when tokens are signed with HS256.
+    pub fn jwks(&self) -> JwkSet {
+        match &self.signer {
+            JwtSigner::Hmac { .. } => JwkSet { keys: Vec::new() },
+            JwtSigner::Asymmetric { jwks, .. } => jwks.clone(),
+        }
+    }
+}
+
+impl JwtSigner {
+    fn load(config: &Jwt) -> Result<Self> {
+        let algorithm = match config.signing.algorithm.as_str() {
+            "HS256" => {
+                return Ok(Self::Hmac {
+                    secrets: HashMap::from([
+                        (TokenKind::UserAccess, config.user.user_secret.clone()),
+                        (TokenKind::UserRefresh, config.user.user_refresh_secret.clone()),
+                        (TokenKind::AdminAccess, config.admin.admin_secret.clone()),
+                        (TokenKind::AdminRefresh, config.admin.admin_refresh_secret.clone()),
+                        // Kept apart from access tokens by their audience.
+                        (TokenKind::MfaPending, config.user.user_secret.clone()),
+                        (TokenKind::EmailVerification, config.user.user_secret.clone()),
+                    ]),
+                });
+            }
+            "RS256" => Algorithm::RS256,
+            "EdDSA" => Algorithm::EdDSA,
+            other => return Err(anyhow::anyhow!("Unsupported JWT algorithm: {}", other)),
+        };
+
+        let active_key_id = config
+            .signing
+            .active_key_id
+            .clone()
+            .context("JWT_ACTIVE_KEY_ID not set")?;
+        let private_key_path = config
+            .signing
+            .private_key_path
+            .as_ref()
+            .context("JWT_PRIVATE_KEY_PATH not set")?;
+        if !config.signing.public_key_paths.contains_key(&active_key_id) {
+            return Err(anyhow::anyhow!("JWT_PUBLIC_KEYS has no key for active key id {}", active_key_id));
+        }
+
+        let private_pem = std::fs::read(private_key_path)
+            .with_context(|| format!("Failed to read JWT private key {}", private_key_path))?;
+        let encoding_key = match algorithm {
+            Algorithm::RS256 => EncodingKey::from_rsa_pem(&private_pem)?,
+            _ => EncodingKey::from_ed_pem(&private_pem)?,
+        };
+
+        let mut decoding_keys = HashMap::new();
+        let mut jwks = JwkSet { keys: Vec::new() };
+        for (key_id, path) in &config.signing.public_key_paths {
+            let public_pem = std::fs::read_to_string(path)
+                .with_context(|| format!("Failed to read JWT public key {}", path))?;
+            let decoding_key = match algorithm {
+                Algorithm::RS256 => DecodingKey::from_rsa_pem(public_pem.as_bytes())?,
+                _ => DecodingKey::from_ed_pem(public_pem.as_bytes())?,
+            };
+            decoding_keys.insert(key_id.clone(), decoding_key);
+            jwks.keys.push(public_jwk(algorithm, key_id, &public_pem)?);
+        }
+
+        Ok(Self::Asymmetric {
+            algorithm,
+            active_key_id,
+            encoding_key,
+            decoding_keys,
+            jwks,
+        })
+    }
+}
+
+// Builds the JWK for a SubjectPublicKeyInfo ("BEGIN PUBLIC KEY") PEM.
+fn public_jwk(algorithm: Algorithm, key_id: &str, public_pem: &str) -> Result<Jwk> {
+    let spki = SubjectPublicKeyInfoOwned::from_pem(public_pem)
+        .map_err(|e| anyhow::anyhow!("Invalid public key for {}: {}", key_id, e))?;
+    let public_key = spki.subject_public_key.raw_bytes();
+
+    let (key_algorithm, parameters) = match algorithm {
+        Algorithm::RS256 => {
+            let rsa = pkcs1::RsaPublicKey::from_der(public_key)
+                .map_err(|e| anyhow::anyhow!("Invalid RSA public key for {}: {}", key_id, e))?;
+            (
+                KeyAlgorithm::RS256,
+                AlgorithmParameters::RSA(RSAKeyParameters {
+                    key_type: RSAKeyType::RSA,
+                    n: URL_SAFE_NO_PAD.encode(rsa.modulus.as_bytes()),
+                    e: URL_SAFE_NO_PAD.encode(rsa.public_exponent.as_bytes()),
+                }),
+            )
+        }
+        _ => (
+            KeyAlgorithm::EdDSA,
+            AlgorithmParameters::OctetKeyPair(OctetKeyPairParameters {
+                key_type: OctetKeyPairType::OctetKeyPair,
+                curve: EllipticCurve::Ed25519,
+                x: URL_SAFE_NO_PAD.encode(public_key),
+            }),
+        ),
+    };
+
+    Ok(Jwk {
+        common: CommonParameters {
+            public_key_use: Some(PublicKeyUse::Signature),
+            key_algorithm: Some(key_algorithm),
+            key_id: Some(key_id.to_string()),
+            ..Default::default()
+        },
+        algorithm: parameters,
+    })
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use std::time::Duration;
+
+    use crate::config::config_model::{JwtAdminSecret, JwtSecret, JwtSigning};
+
+    use super::*;
+
+    fn testdata(file: &str) -> String {
+        format!("{}/src/infrastructure/jwt_authentication/testdata/{}", env!("CARGO_MANIFEST_DIR"), file)
+    }
+
+    /// Keys signing with `active_key_id`'s private key and verifying with each of
+    /// `public_key_ids`; ids name the files in `testdata`, e.g. `rs256-2025`.
+    pub(crate) fn load_keys(algorithm: &str, active_key_id: &str, public_key_ids: &[&str]) -> JwtKeys {
+        JwtKeys::load(&config(algorithm, active_key_id, public_key_ids)).unwrap()
+    }
+
+    fn config(algorithm: &str, active_key_id: &str, public_key_ids: &[&str]) -> Jwt {
+        Jwt {
+            user: JwtSecret {
+                user_secret: "user-secret".to_string(),
+                user_refresh_secret: "user-refresh-secret".to_string(),
+            },
+            admin: JwtAdminSecret {
+                admin_secret: "admin-secret".to_string(),
+                admin_refresh_secret: "admin-refresh-secret".to_string(),
+            },
+            access_token_expiration: Duration::from_secs(15 * 60),
+            refresh_token_expiration: Duration::from_secs(7 * 24 * 60 * 60),
+            issuer: "smartpersona".to_string(),
+            audience: "smartpersona-app".to_string(),
+            admin_audience: "smartpersona-admin".to_string(),
+            signing: JwtSigning {
+                algorithm: algorithm.to_string(),
+                active_key_id: Some(active_key_id.to_string()),
+                private_key_path: Some(testdata(&format!("{}.pem", active_key_id))),
+                public_key_paths: public_key_ids
+                    .iter()
+                    .map(|key_id| (key_id.to_string(), testdata(&format!("{}.pub.pem", key_id))))
+                    .collect(),
+            },
+        }
+    }
+
+    const PRIVATE_JWK_MEMBERS: [&str; 6] = ["d", "p", "q", "dp", "dq", "qi"];
+
+    #[test]
+    fn publishes_only_the_public_parts_of_rsa_keys() {
+        let keys = load_keys("RS256", "rs256-2025", &["rs256-2025", "rs256-2024"]);
+
+        let jwks = serde_json::to_value(keys.jwks()).unwrap();
+        let published = jwks["keys"].as_array().unwrap();
+        assert_eq!(published.len(), 2);
+        for jwk in published {
+            assert_eq!(jwk["kty"], "RSA");
+            assert_eq!(jwk["alg"], "RS256");
+            assert_eq!(jwk["use"], "sig");
+            assert!(jwk["n"].is_string() && jwk["e"].is_string());
+            for member in PRIVATE_JWK_MEMBERS {
+                assert!(jwk.get(member).is_none(), "{member} is published");
+            }
+        }
+        let mut key_ids: Vec<&str> = published.iter().map(|jwk| jwk["kid"].as_str().unwrap()).collect();
+        key_ids.sort();
+        assert_eq!(key_ids, vec!["rs256-2024", "rs256-2025"]);
+    }
+
+    #[test]
+    fn publishes_only_the_public_part_of_an_ed25519_key() {
+        let keys = load_keys("EdDSA", "ed25519", &["ed25519"]);
+
+        let jwks = serde_json::to_value(keys.jwks()).unwrap();
+        let jwk = &jwks["keys"][0];
+        assert_eq!(jwk["kty"], "OKP");
+        assert_eq!(jwk["crv"], "Ed25519");
+        assert_eq!(jwk["kid"], "ed25519");
+        assert!(jwk["x"].is_string());
+        assert!(jwk.get("d").is_none());
+    }
+
+    #[test]
+    fn rejects_an_active_key_without_a_public_key() {
+        assert!(JwtKeys::load(&config("RS256", "rs256-2025", &["rs256-2024"])).is_err());
+    }
+}
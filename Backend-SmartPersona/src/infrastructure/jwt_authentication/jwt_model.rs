@@ -1,21 +1,69 @@
 use serde::{Deserialize, Serialize};
 
+use crate::domain::entities::user::Role;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Passport {
     pub access_token : String,
     pub refresh_token : String,
+    // Seconds until each token expires, used as the cookie max age.
+    pub access_token_expires_in : i64,
+    pub refresh_token_expires_in : i64,
+}
+
+pub enum LoginOutcome {
+    Authenticated(Passport),
+    // The password was right; the client finishes with a TOTP or recovery code.
+    MfaRequired { mfa_token: String },
+    // Admins must enrol before they are given a session.
+    MfaEnrolmentRequired { mfa_token: String },
 }
 
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Claims {
     pub sub: String,
-    pub role: Roles,
+    pub role: Role,
+    pub iss: String,
+    pub aud: String,
+    pub exp: usize,
+    pub iat: usize,
+    pub jti: String,
+    // Id of the server-side session, so revoking the session invalidates the token.
+    pub sid: String,
+    // Set when an admin acts as `sub`; `sid` is then the admin's session.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub act: Option<ActorClaim>,
+}
+
+// RFC 8693 actor claim: who is really behind a token issued on someone else's behalf.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ActorClaim {
+    pub sub: String,
+}
+
+// Issued once the password is checked but before the second factor; only the MFA
+// endpoints accept it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MfaPendingClaims {
+    pub sub: String,
+    pub iss: String,
+    pub aud: String,
+    pub exp: usize,
+    pub iat: usize,
+    pub jti: String,
+    // Whether the login started at the admin endpoint, so admin tokens are issued afterwards.
+    pub admin: bool,
+}
+
+// Signed into the link emailed on registration. `email` ties the link to the address it
+// was sent to, so it stops working if the address changes.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EmailVerificationClaims {
+    pub sub: String,
+    pub iss: String,
+    pub aud: String,
     pub exp: usize,
-    pub iat: usize
+    pub iat: usize,
+    pub email: String,
 }
-#[derive(Debug, Clone, Serialize, Deserialize,PartialEq)]
-pub enum Roles {
-    Admin,
-    UserAndCompany
-}
\ No newline at end of file
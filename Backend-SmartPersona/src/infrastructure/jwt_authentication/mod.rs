@@ -1,23 +1,201 @@
 pub mod authentication_model;
+pub mod jwt_keys;
 pub mod jwt_model;
 use anyhow::Result;
 
-use jsonwebtoken::{decode, encode, DecodingKey, EncodingKey, Header, Validation};
+use serde::{de::DeserializeOwned, Serialize};
+use jsonwebtoken::{decode, decode_header, encode, Algorithm, DecodingKey, EncodingKey, Header, Validation};
 
-use crate::infrastructure::jwt_authentication::jwt_model::Claims;
+use crate::{
+    domain::entities::user::Role,
+    infrastructure::jwt_authentication::{
+        jwt_keys::{JwtKeys, JwtSigner, TokenKind},
+        jwt_model::{Claims, EmailVerificationClaims, MfaPendingClaims},
+    },
+};
 
-pub fn generate_token(secret: String,claims: &Claims) -> Result<String>{
-    let token = encode(
-        &Header::default(), 
-        claims, 
-        &EncodingKey::from_secret(secret.as_ref())
-    )?;
+const ACCESS_TOKEN_TYPE: &str = "JWT";
+const REFRESH_TOKEN_TYPE: &str = "refresh+jwt";
+const MFA_TOKEN_TYPE: &str = "mfa+jwt";
+const EMAIL_VERIFICATION_TOKEN_TYPE: &str = "email-verification+jwt";
+
+pub fn generate_token(keys: &JwtKeys, kind: TokenKind, claims: &Claims) -> Result<String> {
+    encode_claims(keys, kind, claims)
+}
+
+pub fn verify_token(keys: &JwtKeys, kind: TokenKind, token: String) -> Result<Claims> {
+    let claims = decode_claims::<Claims>(keys, kind, &token)?;
+
+    if kind.is_admin() && claims.role != Role::Admin {
+        return Err(anyhow::anyhow!("Not an admin token"));
+    }
+    Ok(claims)
+}
+
+pub fn generate_mfa_token(keys: &JwtKeys, claims: &MfaPendingClaims) -> Result<String> {
+    encode_claims(keys, TokenKind::MfaPending, claims)
+}
+
+pub fn verify_mfa_token(keys: &JwtKeys, token: &str) -> Result<MfaPendingClaims> {
+    decode_claims(keys, TokenKind::MfaPending, token)
+}
+
+pub fn generate_email_verification_token(keys: &JwtKeys, claims: &EmailVerificationClaims) -> Result<String> {
+    encode_claims(keys, TokenKind::EmailVerification, claims)
+}
+
+pub fn verify_email_verification_token(keys: &JwtKeys, token: &str) -> Result<EmailVerificationClaims> {
+    decode_claims(keys, TokenKind::EmailVerification, token)
+}
+
+fn encode_claims<T: Serialize>(keys: &JwtKeys, kind: TokenKind, claims: &T) -> Result<String> {
+    let token = match &keys.signer {
+        JwtSigner::Hmac { secrets } => encode(
+            &Header::default(),
+            claims,
+            &EncodingKey::from_secret(hmac_secret(secrets, kind)?.as_ref()),
+        )?,
+        JwtSigner::Asymmetric { algorithm, active_key_id, encoding_key, .. } => {
+            let mut header = Header::new(*algorithm);
+            header.kid = Some(active_key_id.clone());
+            header.typ = Some(token_type(kind).to_string());
+            encode(&header, claims, encoding_key)?
+        }
+    };
     Ok(token)
 }
-pub fn verify_token(secret: String,token: String) -> Result<Claims>{
-    let result = decode(&token,
-         &DecodingKey::from_secret(secret.as_ref()),
-     &Validation::default())?;
-    Ok(result.claims)
+
+fn decode_claims<T: DeserializeOwned>(keys: &JwtKeys, kind: TokenKind, token: &str) -> Result<T> {
+    let claims = match &keys.signer {
+        JwtSigner::Hmac { secrets } => {
+            decode::<T>(token,
+                &DecodingKey::from_secret(hmac_secret(secrets, kind)?.as_ref()),
+                &validation(keys, kind, Algorithm::HS256))?
+            .claims
+        }
+        JwtSigner::Asymmetric { algorithm, decoding_keys, .. } => {
+            let header = decode_header(token)?;
+            if header.typ.as_deref() != Some(token_type(kind)) {
+                return Err(anyhow::anyhow!("Unexpected token type"));
+            }
+            let decoding_key = header
+                .kid
+                .as_ref()
+                .and_then(|kid| decoding_keys.get(kid))
+                .ok_or_else(|| anyhow::anyhow!("Unknown signing key"))?;
+            decode::<T>(token, decoding_key, &validation(keys, kind, *algorithm))?.claims
+        }
+    };
+    Ok(claims)
+}
+
+// Admin tokens carry their own audience, so a user token is never accepted where an
+// admin token is required even when one asymmetric key signs both.
+fn validation(keys: &JwtKeys, kind: TokenKind, algorithm: Algorithm) -> Validation {
+    let mut validation = Validation::new(algorithm);
+    validation.set_issuer(&[&keys.issuer]);
+    validation.set_audience(&[keys.audience(kind)]);
+    validation.set_required_spec_claims(&["exp", "iss", "aud", "sub"]);
+    validation
+}
+
+fn hmac_secret(secrets: &std::collections::HashMap<TokenKind, String>, kind: TokenKind) -> Result<&String> {
+    secrets
+        .get(&kind)
+        .ok_or_else(|| anyhow::anyhow!("No secret configured for {:?} tokens", kind))
+}
+
+fn token_type(kind: TokenKind) -> &'static str {
+    match kind {
+        TokenKind::MfaPending => MFA_TOKEN_TYPE,
+        TokenKind::EmailVerification => EMAIL_VERIFICATION_TOKEN_TYPE,
+        _ if kind.is_refresh() => REFRESH_TOKEN_TYPE,
+        _ => ACCESS_TOKEN_TYPE,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeDelta, Utc};
+    use uuid::Uuid;
+
+    use super::*;
+    use crate::infrastructure::jwt_authentication::jwt_keys::tests::load_keys;
+
+    fn claims(keys: &JwtKeys, kind: TokenKind, role: Role) -> Claims {
+        let now = Utc::now();
+        Claims {
+            sub: Uuid::new_v4().to_string(),
+            role,
+            iss: keys.issuer.clone(),
+            aud: keys.audience(kind).to_string(),
+            exp: (now + TimeDelta::minutes(15)).timestamp() as usize,
+            iat: now.timestamp() as usize,
+            jti: Uuid::new_v4().to_string(),
+            sid: Uuid::new_v4().to_string(),
+            act: None,
+        }
+    }
+
+    fn sign_and_verify(keys: &JwtKeys, algorithm: Algorithm, key_id: &str) {
+        let claims = claims(keys, TokenKind::UserAccess, Role::PersonaUser);
+        let token = generate_token(keys, TokenKind::UserAccess, &claims).unwrap();
+
+        let header = decode_header(&token).unwrap();
+        assert_eq!(header.alg, algorithm);
+        assert_eq!(header.kid.as_deref(), Some(key_id));
+        assert_eq!(header.typ.as_deref(), Some(ACCESS_TOKEN_TYPE));
+        let verified = verify_token(keys, TokenKind::UserAccess, token).unwrap();
+        assert_eq!(verified.sub, claims.sub);
+        assert_eq!(verified.jti, claims.jti);
+    }
+
+    #[test]
+    fn signs_and_verifies_with_rs256() {
+        sign_and_verify(&load_keys("RS256", "rs256-2025", &["rs256-2025"]), Algorithm::RS256, "rs256-2025");
+    }
+
+    #[test]
+    fn signs_and_verifies_with_eddsa() {
+        sign_and_verify(&load_keys("EdDSA", "ed25519", &["ed25519"]), Algorithm::EdDSA, "ed25519");
+    }
+
+    #[test]
+    fn verifies_tokens_signed_with_a_retired_key() {
+        let retired = load_keys("RS256", "rs256-2024", &["rs256-2024"]);
+        let token = generate_token(&retired, TokenKind::UserAccess, &claims(&retired, TokenKind::UserAccess, Role::PersonaUser)).unwrap();
+
+        let rotated = load_keys("RS256", "rs256-2025", &["rs256-2025", "rs256-2024"]);
+        assert!(verify_token(&rotated, TokenKind::UserAccess, token).is_ok());
+    }
+
+    #[test]
+    fn rejects_tokens_signed_with_an_unknown_key() {
+        let removed = load_keys("RS256", "rs256-2024", &["rs256-2024"]);
+        let token = generate_token(&removed, TokenKind::UserAccess, &claims(&removed, TokenKind::UserAccess, Role::PersonaUser)).unwrap();
+
+        let keys = load_keys("RS256", "rs256-2025", &["rs256-2025"]);
+        let error = verify_token(&keys, TokenKind::UserAccess, token).unwrap_err();
+        assert_eq!(error.to_string(), "Unknown signing key");
+    }
+
+    #[test]
+    fn rejects_a_token_of_another_type() {
+        let keys = load_keys("RS256", "rs256-2025", &["rs256-2025"]);
+        let refresh = generate_token(&keys, TokenKind::UserRefresh, &claims(&keys, TokenKind::UserRefresh, Role::PersonaUser)).unwrap();
+
+        let error = verify_token(&keys, TokenKind::UserAccess, refresh).unwrap_err();
+        assert_eq!(error.to_string(), "Unexpected token type");
+    }
+
+    #[test]
+    fn keeps_admin_and_user_tokens_apart() {
+        let keys = load_keys("EdDSA", "ed25519", &["ed25519"]);
+        let admin = generate_token(&keys, TokenKind::AdminAccess, &claims(&keys, TokenKind::AdminAccess, Role::Admin)).unwrap();
+        let user = generate_token(&keys, TokenKind::UserAccess, &claims(&keys, TokenKind::UserAccess, Role::PersonaUser)).unwrap();
+
+        assert!(verify_token(&keys, TokenKind::UserAccess, admin.clone()).is_err());
+        assert!(verify_token(&keys, TokenKind::AdminAccess, user).is_err());
+        assert!(verify_token(&keys, TokenKind::AdminAccess, admin).is_ok());
+    }
 }
-// validation)
\ No newline at end of file
@@ -0,0 +1,79 @@
+use std::path::PathBuf;
+
+use axum::async_trait;
+use chrono::Utc;
+use tracing::info;
+use uuid::Uuid;
+
+use crate::domain::{
+    error::AppResult,
+    repo::mailer::{EmailMessage, Mailer},
+};
+
+/// Logs every message and, when a directory is configured, writes it to `<dir>/<timestamp>-<id>.eml`.
+/// Stands in for SMTP in local development and tests.
+pub struct FileMailer {
+    dir: Option<PathBuf>,
+}
+
+impl FileMailer {
+    pub fn new(dir: Option<PathBuf>) -> Self {
+        Self { dir }
+    }
+}
+
+#[async_trait]
+impl Mailer for FileMailer {
+    async fn send(&self, message: EmailMessage) -> AppResult<()> {
+        info!(to = %message.to, subject = %message.subject, "Email:\n{}", message.body);
+
+        if let Some(dir) = &self.dir {
+            tokio::fs::create_dir_all(dir).await.map_err(anyhow::Error::from)?;
+            let path = dir.join(format!("{}-{}.eml", Utc::now().format("%Y%m%dT%H%M%S"), Uuid::new_v4()));
+            let content = format!("To: {}\nSubject: {}\n\n{}\n", message.to, message.subject, message.body);
+            tokio::fs::write(&path, content).await.map_err(anyhow::Error::from)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message() -> EmailMessage {
+        EmailMessage {
+            to: "alice@example.com".to_string(),
+            subject: "Hello".to_string(),
+            body: "Line one\nLine two".to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn writes_each_message_to_its_own_file() {
+        let dir = std::env::temp_dir().join(format!("file-mailer-test-{}", Uuid::new_v4()));
+        let mailer = FileMailer::new(Some(dir.clone()));
+
+        mailer.send(message()).await.unwrap();
+        mailer.send(message()).await.unwrap();
+
+        let paths: Vec<PathBuf> = std::fs::read_dir(&dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .collect();
+        assert_eq!(paths.len(), 2);
+        for path in &paths {
+            assert_eq!(path.extension().and_then(|ext| ext.to_str()), Some("eml"));
+            assert_eq!(
+                std::fs::read_to_string(path).unwrap(),
+                "To: alice@example.com\nSubject: Hello\n\nLine one\nLine two\n"
+            );
+        }
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn without_a_directory_only_logs() {
+        assert!(FileMailer::new(None).send(message()).await.is_ok());
+    }
+}
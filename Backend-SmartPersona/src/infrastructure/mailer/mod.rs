@@ -0,0 +1,17 @@
+pub mod smtp;
+pub mod file;
+
+use std::{path::PathBuf, sync::Arc};
+
+use anyhow::Result;
+
+use crate::{config::config_model::Mail, domain::repo::mailer::SharedMailer};
+
+// SMTP in deployed environments, everything else writes the mail locally.
+pub fn from_config(config: &Mail) -> Result<SharedMailer> {
+    match config.transport.as_str() {
+        "smtp" => Ok(Arc::new(smtp::SmtpMailer::new(config)?)),
+        "file" => Ok(Arc::new(file::FileMailer::new(config.file_dir.as_ref().map(PathBuf::from)))),
+        other => Err(anyhow::anyhow!("Unsupported MAIL_TRANSPORT {}", other)),
+    }
+}
@@ -0,0 +1,65 @@
+use anyhow::Result;
+use axum::async_trait;
+use lettre::{
+    message::{header::ContentType, Mailbox},
+    transport::smtp::authentication::Credentials,
+    AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor,
+};
+
+use crate::{
+    config::config_model::Mail,
+    domain::{
+        error::{AppError, AppResult},
+        repo::mailer::{EmailMessage, Mailer},
+    },
+};
+
+pub struct SmtpMailer {
+    transport: AsyncSmtpTransport<Tokio1Executor>,
+    from: Mailbox,
+}
+
+impl SmtpMailer {
+    pub fn new(config: &Mail) -> Result<Self> {
+        let host = config
+            .smtp_host
+            .as_deref()
+            .ok_or_else(|| anyhow::anyhow!("SMTP_HOST not set"))?;
+
+        let mut builder = AsyncSmtpTransport::<Tokio1Executor>::relay(host)?;
+        if let Some(port) = config.smtp_port {
+            builder = builder.port(port);
+        }
+        if let (Some(username), Some(password)) = (&config.smtp_username, &config.smtp_password) {
+            builder = builder.credentials(Credentials::new(username.clone(), password.clone()));
+        }
+
+        Ok(Self {
+            transport: builder.build(),
+            from: config.from.parse()?,
+        })
+    }
+}
+
+#[async_trait]
+impl Mailer for SmtpMailer {
+    async fn send(&self, message: EmailMessage) -> AppResult<()> {
+        let to = message
+            .to
+            .parse::<Mailbox>()
+            .map_err(|e| AppError::validation(format!("Invalid email address: {}", e)))?;
+        let email = Message::builder()
+            .from(self.from.clone())
+            .to(to)
+            .subject(message.subject)
+            .header(ContentType::TEXT_PLAIN)
+            .body(message.body)
+            .map_err(anyhow::Error::from)?;
+
+        self.transport
+            .send(email)
+            .await
+            .map_err(|e| AppError::upstream(format!("Failed to send email: {}", e)))?;
+        Ok(())
+    }
+}
@@ -2,4 +2,12 @@ pub mod axum_http;
 pub mod postgres;
 pub mod hashingpassword;
 pub mod jwt_authentication;
-pub mod ai_service_client;
\ No newline at end of file
+pub mod ai_service_client;
+pub mod job_worker;
+pub mod token_encryption;
+pub mod social_post_sources;
+pub mod oauth_token_refresher;
+pub mod totp;
+pub mod mailer;
+pub mod password_policy;
+pub mod export_archive;
\ No newline at end of file
@@ -0,0 +1,149 @@
+use axum::async_trait;
+use reqwest::Client;
+use serde::Deserialize;
+
+use crate::{
+    config::config_model::OAuthProvider,
+    domain::{
+        error::{AppError, AppResult},entities::social_connection::RefreshedTokenEntity, repo::token_refresher::TokenRefresher},
+};
+
+/// Standard OAuth 2.0 `refresh_token` grant against a provider's token endpoint.
+/// Point `token_url` at a local mock server to exercise it without a real provider.
+pub struct OAuthTokenRefresher {
+    client: Client,
+    provider: OAuthProvider,
+}
+
+impl OAuthTokenRefresher {
+    pub fn new(provider: OAuthProvider) -> Self {
+        Self {
+            client: Client::new(),
+            provider,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct TokenResponse {
+    access_token: String,
+    refresh_token: Option<String>,
+    expires_in: Option<i64>,
+}
+
+#[async_trait]
+impl TokenRefresher for OAuthTokenRefresher {
+    fn platform(&self) -> &str {
+        &self.provider.platform
+    }
+
+    async fn refresh(&self, refresh_token: &str) -> AppResult<RefreshedTokenEntity> {
+        let response = self.client
+            .post(&self.provider.token_url)
+            .basic_auth(&self.provider.client_id, Some(&self.provider.client_secret))
+            .form(&[("grant_type", "refresh_token"), ("refresh_token", refresh_token)])
+            .send()
+            .await
+            .map_err(|e| AppError::upstream(format!("Failed to send refresh request to {}: {}", self.provider.platform, e)))?;
+
+        if !response.status().is_success() {
+            let error_body = response.text().await.map_err(|e| AppError::upstream(format!("Failed to read token endpoint error body: {}", e)))?;
+            return Err(AppError::upstream(format!("{} token endpoint returned an error: {}", self.provider.platform, error_body)));
+        }
+
+        let token = response
+            .json::<TokenResponse>()
+            .await
+            .map_err(|e| AppError::upstream(format!("Failed to deserialize token endpoint response: {}", e)))?;
+
+        Ok(RefreshedTokenEntity {
+            access_token: token.access_token,
+            refresh_token: token.refresh_token,
+            expires_in: token.expires_in,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use axum::{
+        http::{HeaderMap, StatusCode},
+        response::{IntoResponse, Response},
+        routing::post,
+        Form, Json, Router,
+    };
+    use base64::{engine::general_purpose::STANDARD, Engine};
+
+    use super::*;
+
+    // Mock token endpoint: accepts the `rt-valid` refresh token from the `client:secret` client.
+    async fn token_endpoint(headers: HeaderMap, Form(form): Form<HashMap<String, String>>) -> Response {
+        let expected_auth = format!("Basic {}", STANDARD.encode("client:secret"));
+        if headers.get("authorization").and_then(|value| value.to_str().ok()) != Some(expected_auth.as_str()) {
+            return (StatusCode::UNAUTHORIZED, "invalid_client").into_response();
+        }
+        if form.get("grant_type").map(String::as_str) != Some("refresh_token")
+            || form.get("refresh_token").map(String::as_str) != Some("rt-valid")
+        {
+            return (StatusCode::BAD_REQUEST, "invalid_grant").into_response();
+        }
+
+        Json(serde_json::json!({
+            "access_token": "at-new",
+            "refresh_token": "rt-rotated",
+            "expires_in": 3600,
+            "token_type": "bearer",
+        }))
+        .into_response()
+    }
+
+    async fn mock_provider() -> OAuthProvider {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            axum::serve(listener, Router::new().route("/token", post(token_endpoint)))
+                .await
+                .unwrap();
+        });
+
+        OAuthProvider {
+            platform: "mock".to_string(),
+            token_url: format!("http://{}/token", address),
+            client_id: "client".to_string(),
+            client_secret: "secret".to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn refreshes_against_the_token_endpoint() {
+        let refresher = OAuthTokenRefresher::new(mock_provider().await);
+
+        let token = refresher.refresh("rt-valid").await.unwrap();
+
+        assert_eq!(token.access_token, "at-new");
+        assert_eq!(token.refresh_token.as_deref(), Some("rt-rotated"));
+        assert_eq!(token.expires_in, Some(3600));
+    }
+
+    #[tokio::test]
+    async fn rejected_refresh_token_is_an_upstream_error() {
+        let refresher = OAuthTokenRefresher::new(mock_provider().await);
+
+        let error = refresher.refresh("rt-revoked").await.unwrap_err();
+
+        assert!(matches!(error, AppError::Upstream(message) if message.contains("invalid_grant")));
+    }
+
+    #[tokio::test]
+    async fn wrong_client_credentials_are_an_upstream_error() {
+        let mut provider = mock_provider().await;
+        provider.client_secret = "wrong".to_string();
+        let refresher = OAuthTokenRefresher::new(provider);
+
+        let error = refresher.refresh("rt-valid").await.unwrap_err();
+
+        assert!(matches!(error, AppError::Upstream(message) if message.contains("invalid_client")));
+    }
+}
@@ -0,0 +1,167 @@
+use std::collections::HashSet;
+
+use anyhow::{Context, Result};
+
+use crate::config::config_model::PasswordPolicy;
+
+// Most common passwords from public breach corpora; `PASSWORD_BREACHED_LIST_PATH` can add more.
+const COMMON_PASSWORDS: &str = include_str!("common_passwords.txt");
+
+/// Checks new passwords against the configured strength rules and a local list of
+/// breached passwords. The list is loaded once at startup and compared case-insensitively.
+pub struct PasswordValidator {
+    policy: PasswordPolicy,
+    breached: HashSet<String>,
+}
+
+impl PasswordValidator {
+    pub fn new(policy: &PasswordPolicy) -> Result<Self> {
+        let mut breached: HashSet<String> = COMMON_PASSWORDS.lines().map(normalize).collect();
+        if let Some(path) = &policy.breached_list_path {
+            let content = std::fs::read_to_string(path)
+                .with_context(|| format!("Failed to read breached password list {}", path))?;
+            breached.extend(content.lines().map(normalize));
+        }
+        breached.remove("");
+
+        Ok(Self { policy: policy.clone(), breached })
+    }
+
+    /// Returns every rule the password breaks; empty when it is acceptable. `username`
+    /// is rejected as part of the password.
+    pub fn check(&self, password: &str, username: Option<&str>) -> Vec<String> {
+        let policy = &self.policy;
+        let length = password.chars().count();
+        let mut problems = Vec::new();
+
+        if length < policy.min_length {
+            problems.push(format!("must be at least {} characters", policy.min_length));
+        }
+        if length > policy.max_length {
+            problems.push(format!("must be at most {} characters", policy.max_length));
+        }
+        if policy.require_uppercase && !password.chars().any(char::is_uppercase) {
+            problems.push("must contain an uppercase letter".to_string());
+        }
+        if policy.require_lowercase && !password.chars().any(char::is_lowercase) {
+            problems.push("must contain a lowercase letter".to_string());
+        }
+        if policy.require_digit && !password.chars().any(|c| c.is_ascii_digit()) {
+            problems.push("must contain a digit".to_string());
+        }
+        if policy.require_symbol && password.chars().all(char::is_alphanumeric) {
+            problems.push("must contain a symbol".to_string());
+        }
+        if let Some(username) = username.map(normalize)
+            && !username.is_empty()
+            && normalize(password).contains(&username)
+        {
+            problems.push("must not contain the username".to_string());
+        }
+        if self.breached.contains(&normalize(password)) {
+            problems.push("is too common and has appeared in a data breach".to_string());
+        }
+
+        problems
+    }
+}
+
+fn normalize(password: &str) -> String {
+    password.trim().to_lowercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn policy() -> PasswordPolicy {
+        PasswordPolicy {
+            min_length: 10,
+            max_length: 64,
+            require_uppercase: true,
+            require_lowercase: true,
+            require_digit: true,
+            require_symbol: true,
+            breached_list_path: None,
+        }
+    }
+
+    fn validator() -> PasswordValidator {
+        PasswordValidator::new(&policy()).unwrap()
+    }
+
+    #[test]
+    fn accepts_a_password_that_meets_every_rule() {
+        assert!(validator().check("Tr1cky-Hors3!", Some("alice")).is_empty());
+    }
+
+    #[test]
+    fn reports_every_broken_rule() {
+        assert_eq!(
+            validator().check("abc", None),
+            vec![
+                "must be at least 10 characters",
+                "must contain an uppercase letter",
+                "must contain a digit",
+                "must contain a symbol",
+            ]
+        );
+        assert_eq!(
+            validator().check("ABCDEFGHIJ", None),
+            vec!["must contain a lowercase letter", "must contain a digit", "must contain a symbol"]
+        );
+    }
+
+    #[test]
+    fn enforces_the_maximum_length() {
+        let password = format!("Aa1!{}", "x".repeat(61));
+        assert_eq!(validator().check(&password, None), vec!["must be at most 64 characters"]);
+    }
+
+    #[test]
+    fn rejects_the_username_in_any_case() {
+        assert_eq!(
+            validator().check("My-Alice-Pass1", Some("ALICE")),
+            vec!["must not contain the username"]
+        );
+        assert!(validator().check("My-Alice-Pass1", Some("")).is_empty());
+    }
+
+    #[test]
+    fn rejects_breached_passwords_ignoring_case() {
+        let validator = PasswordValidator::new(&PasswordPolicy {
+            min_length: 1,
+            require_uppercase: false,
+            require_lowercase: false,
+            require_digit: false,
+            require_symbol: false,
+            ..policy()
+        })
+        .unwrap();
+        assert_eq!(validator.check("PASSWORD", None), vec!["is too common and has appeared in a data breach"]);
+        assert!(validator.check("not-in-the-list", None).is_empty());
+    }
+
+    #[test]
+    fn extra_breached_list_is_loaded_from_the_configured_path() {
+        let path = std::env::temp_dir().join(format!("breached-{}.txt", uuid::Uuid::new_v4()));
+        std::fs::write(&path, "Tr1cky-Hors3!\n").unwrap();
+        let validator = PasswordValidator::new(&PasswordPolicy {
+            breached_list_path: Some(path.to_string_lossy().into_owned()),
+            ..policy()
+        })
+        .unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(validator.check("TR1CKY-hors3!", None), vec!["is too common and has appeared in a data breach"]);
+    }
+
+    #[test]
+    fn missing_breached_list_fails_at_startup() {
+        let policy = PasswordPolicy {
+            breached_list_path: Some("/nonexistent/breached.txt".to_string()),
+            ..policy()
+        };
+        assert!(PasswordValidator::new(&policy).is_err());
+    }
+}
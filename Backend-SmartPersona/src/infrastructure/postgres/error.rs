@@ -0,0 +1,59 @@
+use diesel::result::{DatabaseErrorKind, Error as DieselError};
+
+use crate::domain::error::AppError;
+
+impl From<DieselError> for AppError {
+    fn from(e: DieselError) -> Self {
+        match e {
+            DieselError::NotFound => AppError::not_found("Resource not found"),
+            DieselError::DatabaseError(DatabaseErrorKind::UniqueViolation, _) => {
+                AppError::conflict("Resource already exists")
+            }
+            DieselError::DatabaseError(DatabaseErrorKind::ForeignKeyViolation, _) => {
+                AppError::conflict("Resource is still referenced by other records")
+            }
+            e => AppError::Internal(e.into()),
+        }
+    }
+}
+
+impl From<diesel::r2d2::PoolError> for AppError {
+    fn from(e: diesel::r2d2::PoolError) -> Self {
+        AppError::Internal(e.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn database_error(kind: DatabaseErrorKind) -> DieselError {
+        DieselError::DatabaseError(kind, Box::new("new row violates a constraint".to_string()))
+    }
+
+    #[test]
+    fn maps_a_missing_row_to_not_found() {
+        assert!(matches!(AppError::from(DieselError::NotFound), AppError::NotFound(message) if message == "Resource not found"));
+    }
+
+    #[test]
+    fn maps_constraint_violations_to_conflicts() {
+        assert!(matches!(
+            AppError::from(database_error(DatabaseErrorKind::UniqueViolation)),
+            AppError::Conflict(message) if message == "Resource already exists"
+        ));
+        assert!(matches!(
+            AppError::from(database_error(DatabaseErrorKind::ForeignKeyViolation)),
+            AppError::Conflict(message) if message == "Resource is still referenced by other records"
+        ));
+    }
+
+    #[test]
+    fn maps_anything_else_to_an_internal_error() {
+        assert!(matches!(
+            AppError::from(database_error(DatabaseErrorKind::CheckViolation)),
+            AppError::Internal(_)
+        ));
+        assert!(matches!(AppError::from(DieselError::RollbackTransaction), AppError::Internal(_)));
+    }
+}
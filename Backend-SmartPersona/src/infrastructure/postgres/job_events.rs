@@ -0,0 +1,57 @@
+use std::{thread, time::Duration};
+
+use diesel::{Connection, PgConnection, RunQueryDsl};
+use tokio::sync::broadcast;
+use tracing::{error, info, warn};
+
+use crate::domain::entities::generation_job::GenerationJobEvent;
+
+const CHANNEL: &str = "generation_job_events";
+const POLL_INTERVAL: Duration = Duration::from_millis(200);
+const RECONNECT_DELAY: Duration = Duration::from_secs(5);
+
+/// Fans `generation_job_events` notifications out to in-process subscribers. Every backend
+/// instance runs its own listener, so a job updated by any instance reaches all of them.
+pub struct GenerationJobEvents {
+    sender: broadcast::Sender<GenerationJobEvent>,
+}
+
+impl GenerationJobEvents {
+    pub fn listen(database_url: String) -> Self {
+        let (sender, _) = broadcast::channel(256);
+        let listener_sender = sender.clone();
+
+        thread::spawn(move || loop {
+            if let Err(e) = listen_until_error(&database_url, &listener_sender) {
+                error!("generation job listener disconnected: {}", e);
+            }
+            thread::sleep(RECONNECT_DELAY);
+        });
+
+        Self { sender }
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<GenerationJobEvent> {
+        self.sender.subscribe()
+    }
+}
+
+fn listen_until_error(database_url: &str, sender: &broadcast::Sender<GenerationJobEvent>) -> anyhow::Result<()> {
+    let mut conn = PgConnection::establish(database_url)?;
+    diesel::sql_query(format!("LISTEN {}", CHANNEL)).execute(&mut conn)?;
+    info!("listening on {}", CHANNEL);
+
+    loop {
+        for notification in conn.notifications_iter() {
+            let notification = notification?;
+            match serde_json::from_str::<GenerationJobEvent>(&notification.payload) {
+                // No subscribers is not an error, the event is simply dropped.
+                Ok(event) => {
+                    let _ = sender.send(event);
+                }
+                Err(e) => warn!("invalid {} payload: {}", CHANNEL, e),
+            }
+        }
+        thread::sleep(POLL_INTERVAL);
+    }
+}
@@ -0,0 +1,14 @@
+DROP INDEX IF EXISTS idx_generation_jobs_requester_id;
+DROP INDEX IF EXISTS idx_generation_jobs_pending;
+
+DROP TRIGGER IF EXISTS set_timestamp ON generation_jobs;
+
+ALTER TABLE generation_jobs
+DROP COLUMN updated_at,
+DROP COLUMN started_at,
+DROP COLUMN attempts,
+DROP COLUMN error,
+DROP COLUMN payload;
+
+-- Postgres ลบค่าออกจาก ENUM ไม่ได้ จึงย้าย job ที่ค้างอยู่กลับไปเป็น pending แทน
+UPDATE generation_jobs SET status = 'pending' WHERE status = 'running';
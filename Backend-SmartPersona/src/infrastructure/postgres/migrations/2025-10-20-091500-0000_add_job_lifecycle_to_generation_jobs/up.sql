@@ -0,0 +1,25 @@
+-- ================================
+-- 1. เพิ่มสถานะ running ให้ job_status
+-- ================================
+ALTER TYPE job_status ADD VALUE IF NOT EXISTS 'running' BEFORE 'completed';
+
+-- ================================
+-- 2. เพิ่มคอลัมน์สำหรับ worker
+-- ================================
+ALTER TABLE generation_jobs
+ADD COLUMN payload JSONB NOT NULL DEFAULT '{}'::jsonb,
+ADD COLUMN error TEXT,
+ADD COLUMN attempts INTEGER NOT NULL DEFAULT 0,
+ADD COLUMN started_at TIMESTAMPTZ,
+ADD COLUMN updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW();
+
+CREATE TRIGGER set_timestamp
+BEFORE UPDATE ON generation_jobs
+FOR EACH ROW
+EXECUTE PROCEDURE trigger_set_timestamp();
+
+-- ================================
+-- 3. Index สำหรับดึง job ที่รอทำงาน
+-- ================================
+CREATE INDEX idx_generation_jobs_pending ON generation_jobs(created_at) WHERE status = 'pending';
+CREATE INDEX idx_generation_jobs_requester_id ON generation_jobs(requester_id);
@@ -0,0 +1,3 @@
+DROP TRIGGER IF EXISTS notify_generation_job_status ON generation_jobs;
+DROP TRIGGER IF EXISTS notify_generation_job_insert ON generation_jobs;
+DROP FUNCTION IF EXISTS notify_generation_job_change();
@@ -0,0 +1,27 @@
+-- ================================
+-- 1. ฟังก์ชันส่ง NOTIFY เมื่อสถานะของ job เปลี่ยน
+-- ================================
+CREATE OR REPLACE FUNCTION notify_generation_job_change()
+RETURNS TRIGGER AS $$
+BEGIN
+  PERFORM pg_notify(
+    'generation_job_events',
+    json_build_object('id', NEW.id, 'requester_id', NEW.requester_id, 'status', NEW.status)::text
+  );
+  RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+-- ================================
+-- 2. ผูก trigger กับ generation_jobs
+-- ================================
+CREATE TRIGGER notify_generation_job_insert
+AFTER INSERT ON generation_jobs
+FOR EACH ROW
+EXECUTE PROCEDURE notify_generation_job_change();
+
+CREATE TRIGGER notify_generation_job_status
+AFTER UPDATE OF status ON generation_jobs
+FOR EACH ROW
+WHEN (OLD.status IS DISTINCT FROM NEW.status)
+EXECUTE PROCEDURE notify_generation_job_change();
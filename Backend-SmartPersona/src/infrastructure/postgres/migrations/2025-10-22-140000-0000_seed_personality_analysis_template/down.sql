@@ -0,0 +1 @@
+DELETE FROM prompt_templates WHERE name = 'personality_analysis';
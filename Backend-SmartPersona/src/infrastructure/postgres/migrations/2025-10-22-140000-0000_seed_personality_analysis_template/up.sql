@@ -0,0 +1,24 @@
+-- เพิ่ม template เริ่มต้นสำหรับวิเคราะห์บุคลิกภาพ (ย้ายมาจาก prompt ที่เคย hard-code ใน gemini-service)
+INSERT INTO prompt_templates (name, template_text)
+VALUES (
+'personality_analysis',
+$$You are an AI personality analyzer.
+
+Analyze the following social media posts and describe the user's personality in {{tag_count}} short keywords
+that represent their style or vibe (e.g., creative, formal, minimalist, tech-savvy).
+Then, suggest exactly one theme name suitable for their web design (e.g., "dark_minimalist", "playful_vibrant").
+Write the keywords in {{language}}.
+
+Posts:
+{{posts}}
+
+Respond ONLY with a single valid JSON object — no extra text, no explanation, no markdown code block.
+Use this exact structure:
+
+{
+  "personality_tags": ["string", "string", "string"],
+  "suggested_theme": "string"
+}
+$$
+)
+ON CONFLICT (name) DO NOTHING;
@@ -0,0 +1,17 @@
+ALTER TABLE generation_jobs DROP COLUMN prompt_template_version_id;
+
+ALTER TABLE prompt_templates ADD COLUMN template_text TEXT;
+
+UPDATE prompt_templates t
+SET template_text = v.template_text
+FROM prompt_template_versions v
+WHERE v.id = t.active_version_id;
+
+UPDATE prompt_templates SET template_text = '' WHERE template_text IS NULL;
+ALTER TABLE prompt_templates ALTER COLUMN template_text SET NOT NULL;
+
+ALTER TABLE prompt_templates DROP COLUMN active_version_id;
+
+DROP TRIGGER IF EXISTS prevent_update ON prompt_template_versions;
+DROP FUNCTION IF EXISTS prevent_prompt_template_version_update();
+DROP TABLE IF EXISTS prompt_template_versions;
@@ -0,0 +1,45 @@
+-- ================================
+-- 1. สร้างตาราง prompt_template_versions (แก้ไขไม่ได้หลังสร้าง)
+-- ================================
+CREATE TABLE prompt_template_versions (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    template_id UUID NOT NULL REFERENCES prompt_templates(id) ON DELETE CASCADE,
+    version INTEGER NOT NULL,
+    template_text TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (template_id, version)
+);
+
+CREATE OR REPLACE FUNCTION prevent_prompt_template_version_update()
+RETURNS TRIGGER AS $$
+BEGIN
+  RAISE EXCEPTION 'prompt_template_versions rows are immutable';
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER prevent_update
+BEFORE UPDATE ON prompt_template_versions
+FOR EACH ROW
+EXECUTE PROCEDURE prevent_prompt_template_version_update();
+
+-- ================================
+-- 2. ย้าย template_text เดิมเป็น version 1 และชี้ active version
+-- ================================
+ALTER TABLE prompt_templates
+ADD COLUMN active_version_id UUID REFERENCES prompt_template_versions(id);
+
+INSERT INTO prompt_template_versions (template_id, version, template_text, created_at)
+SELECT id, 1, template_text, updated_at FROM prompt_templates;
+
+UPDATE prompt_templates t
+SET active_version_id = v.id
+FROM prompt_template_versions v
+WHERE v.template_id = t.id AND v.version = 1;
+
+ALTER TABLE prompt_templates DROP COLUMN template_text;
+
+-- ================================
+-- 3. บันทึก version ที่ใช้สร้างผลลัพธ์ของแต่ละ job
+-- ================================
+ALTER TABLE generation_jobs
+ADD COLUMN prompt_template_version_id UUID REFERENCES prompt_template_versions(id);
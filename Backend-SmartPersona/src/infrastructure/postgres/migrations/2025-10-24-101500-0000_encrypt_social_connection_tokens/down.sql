@@ -0,0 +1,3 @@
+ALTER TABLE social_connections
+DROP COLUMN wrapped_data_key,
+DROP COLUMN encryption_key_id;
@@ -0,0 +1,8 @@
+-- ================================
+-- เก็บ token ของ social_connections แบบเข้ารหัส (envelope encryption)
+-- access_token / refresh_token จะเก็บ ciphertext ส่วน data key ที่ถูก wrap ด้วย master key
+-- และ id ของ master key เก็บไว้ในคอลัมน์ใหม่ แถวเดิมที่ encryption_key_id เป็น NULL ยังเป็น plaintext
+-- ================================
+ALTER TABLE social_connections
+ADD COLUMN encryption_key_id VARCHAR(64),
+ADD COLUMN wrapped_data_key TEXT;
@@ -0,0 +1,6 @@
+DROP INDEX IF EXISTS idx_social_connections_expires_at;
+
+ALTER TABLE social_connections
+DROP COLUMN broken_at,
+DROP COLUMN last_refresh_attempt_at,
+DROP COLUMN refresh_failures;
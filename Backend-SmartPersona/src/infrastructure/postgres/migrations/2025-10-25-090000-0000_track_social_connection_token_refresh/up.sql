@@ -0,0 +1,12 @@
+-- ================================
+-- ติดตามการ refresh token ของ social_connections
+-- refresh_failures นับจำนวนครั้งที่ refresh ล้มเหลวติดกัน, last_refresh_attempt_at ใช้กันไม่ให้ลองซ้ำถี่เกินไป
+-- broken_at ถูกตั้งเมื่อ refresh ล้มเหลวเกินกำหนด ผู้ใช้ต้องเชื่อมบัญชีใหม่
+-- ================================
+ALTER TABLE social_connections
+ADD COLUMN refresh_failures INTEGER NOT NULL DEFAULT 0,
+ADD COLUMN last_refresh_attempt_at TIMESTAMPTZ,
+ADD COLUMN broken_at TIMESTAMPTZ;
+
+CREATE INDEX idx_social_connections_expires_at ON social_connections (expires_at)
+WHERE broken_at IS NULL AND refresh_token IS NOT NULL;
@@ -0,0 +1,15 @@
+-- ================================
+-- สร้างตาราง sessions เก็บ refresh token ที่ออกให้ผู้ใช้ (id คือ jti ใน token)
+-- logout จะตั้ง revoked_at ทำให้ token ของ session นั้นใช้ไม่ได้อีก
+-- ================================
+CREATE TABLE sessions (
+    id UUID PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    device VARCHAR(255),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    last_used_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    expires_at TIMESTAMPTZ NOT NULL,
+    revoked_at TIMESTAMPTZ
+);
+
+CREATE INDEX idx_sessions_user_id ON sessions (user_id) WHERE revoked_at IS NULL;
@@ -0,0 +1,2 @@
+ALTER TABLE sessions
+DROP COLUMN current_refresh_token_id;
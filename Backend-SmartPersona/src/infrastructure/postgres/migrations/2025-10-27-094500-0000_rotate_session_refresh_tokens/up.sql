@@ -0,0 +1,12 @@
+-- ================================
+-- refresh token ใช้ได้ครั้งเดียว: session หนึ่งคือ family ของ refresh token
+-- current_refresh_token_id คือ jti ของ refresh token ตัวล่าสุดที่ยังใช้ได้
+-- ถ้ามีการใช้ token ตัวเก่าซ้ำ จะ revoke ทั้ง session
+-- ================================
+ALTER TABLE sessions
+ADD COLUMN current_refresh_token_id UUID;
+
+UPDATE sessions SET current_refresh_token_id = id;
+
+ALTER TABLE sessions
+ALTER COLUMN current_refresh_token_id SET NOT NULL;
@@ -0,0 +1,28 @@
+-- ================================
+-- สร้างตาราง user_mfa เก็บ TOTP secret ของผู้ใช้ (เข้ารหัสแบบ envelope เหมือน social_connections)
+-- confirmed_at เป็น NULL ระหว่างลงทะเบียนจนกว่าผู้ใช้จะยืนยันด้วยรหัสแรก
+-- last_used_step กันการใช้รหัส TOTP เดิมซ้ำ
+-- ================================
+CREATE TABLE user_mfa (
+    user_id UUID PRIMARY KEY REFERENCES users(id) ON DELETE CASCADE,
+    secret TEXT NOT NULL,
+    encryption_key_id VARCHAR(64) NOT NULL,
+    wrapped_data_key TEXT NOT NULL,
+    confirmed_at TIMESTAMPTZ,
+    last_used_step BIGINT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- ================================
+-- recovery code เก็บเป็น hash (argon2) ใช้ได้ครั้งเดียว
+-- ================================
+CREATE TABLE mfa_recovery_codes (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    code_hash TEXT NOT NULL,
+    used_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_mfa_recovery_codes_user_id ON mfa_recovery_codes (user_id) WHERE used_at IS NULL;
@@ -0,0 +1,6 @@
+-- ค่า 'locked' ใน user_status ลบออกจาก enum ไม่ได้ จึงย้ายผู้ใช้กลับเป็น active แทน
+UPDATE users SET status = 'active' WHERE status = 'locked';
+
+ALTER TABLE users
+DROP COLUMN IF EXISTS failed_login_attempts,
+DROP COLUMN IF EXISTS locked_until;
@@ -0,0 +1,9 @@
+-- ================================
+-- นับจำนวนครั้งที่ login ผิดติดกัน และเวลาที่บัญชีถูกล็อกไว้ (back-off แบบ exponential)
+-- ผิดครบจำนวนที่กำหนดจะตั้ง status เป็น 'locked' ชั่วคราวจนถึง locked_until หรือจนกว่า admin จะปลดล็อก
+-- ================================
+ALTER TYPE user_status ADD VALUE IF NOT EXISTS 'locked';
+
+ALTER TABLE users
+ADD COLUMN failed_login_attempts INTEGER NOT NULL DEFAULT 0,
+ADD COLUMN locked_until TIMESTAMPTZ;
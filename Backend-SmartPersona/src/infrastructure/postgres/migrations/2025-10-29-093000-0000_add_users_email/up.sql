@@ -0,0 +1,8 @@
+-- ================================
+-- เพิ่มอีเมลของผู้ใช้ ใช้ส่งลิงก์ตั้งรหัสผ่านใหม่ (เก็บเป็นตัวพิมพ์เล็กเสมอ)
+-- ผู้ใช้เดิมยังไม่มีอีเมลจึงเป็น NULL ได้
+-- ================================
+ALTER TABLE users
+ADD COLUMN email VARCHAR(255);
+
+CREATE UNIQUE INDEX idx_users_email ON users (email) WHERE email IS NOT NULL;
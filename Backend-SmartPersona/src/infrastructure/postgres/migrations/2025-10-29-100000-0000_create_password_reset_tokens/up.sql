@@ -0,0 +1,14 @@
+-- ================================
+-- สร้างตาราง password_reset_tokens เก็บ token สำหรับตั้งรหัสผ่านใหม่
+-- เก็บเฉพาะ hash (SHA-256) ของ token, ใช้ได้ครั้งเดียว (used_at) และหมดอายุตาม expires_at
+-- ================================
+CREATE TABLE password_reset_tokens (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    token_hash VARCHAR(64) NOT NULL UNIQUE,
+    expires_at TIMESTAMPTZ NOT NULL,
+    used_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_password_reset_tokens_user_id ON password_reset_tokens (user_id) WHERE used_at IS NULL;
@@ -0,0 +1,2 @@
+-- ค่า 'banned' ใน user_status ลบออกจาก enum ไม่ได้ จึงย้ายผู้ใช้กลับเป็น suspended แทน
+UPDATE users SET status = 'suspended' WHERE status = 'banned';
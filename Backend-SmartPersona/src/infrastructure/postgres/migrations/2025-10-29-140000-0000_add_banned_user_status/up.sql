@@ -0,0 +1,7 @@
+-- ================================
+-- เพิ่มสถานะ 'banned' และเริ่มใช้ status ตอน login
+-- ผู้ใช้ใหม่เริ่มที่ 'pending' จนกว่าจะยืนยันอีเมล ผู้ใช้เดิมที่ยังเป็น 'pending' ถือว่ายืนยันแล้ว
+-- ================================
+ALTER TYPE user_status ADD VALUE IF NOT EXISTS 'banned';
+
+UPDATE users SET status = 'active' WHERE status = 'pending';
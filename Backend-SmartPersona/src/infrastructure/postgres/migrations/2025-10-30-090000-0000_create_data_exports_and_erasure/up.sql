@@ -0,0 +1,26 @@
+-- ================================
+-- คำขอ export ข้อมูลส่วนตัวของผู้ใช้ ทำงานแบบ background job (ใช้ job_status เดียวกับ generation_jobs)
+-- data เก็บผลลัพธ์เป็น JSON ไว้ให้ดาวน์โหลดจนถึง expires_at
+-- ================================
+CREATE TABLE data_exports (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    status job_status NOT NULL DEFAULT 'pending',
+    data JSONB,
+    error TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    started_at TIMESTAMPTZ,
+    completed_at TIMESTAMPTZ,
+    expires_at TIMESTAMPTZ
+);
+
+CREATE INDEX idx_data_exports_user_id ON data_exports (user_id);
+CREATE INDEX idx_data_exports_pending ON data_exports (created_at) WHERE status = 'pending';
+
+-- ================================
+-- การลบบัญชีตามคำขอ: ลบจริงเมื่อถึง erasure_scheduled_at (หลังช่วงผ่อนผัน) ยกเลิกได้ก่อนถึงเวลา
+-- ================================
+ALTER TABLE users
+ADD COLUMN erasure_scheduled_at TIMESTAMPTZ;
+
+CREATE INDEX idx_users_erasure_scheduled_at ON users (erasure_scheduled_at) WHERE erasure_scheduled_at IS NOT NULL;
@@ -0,0 +1,21 @@
+-- ================================
+-- บันทึกว่าใครทำอะไรกับใคร (login, การแก้ไขบัญชี, การกระทำของแอดมิน, การเผยแพร่โปรไฟล์)
+-- actor_id / target_id ไม่มี foreign key เพื่อให้ประวัติยังอยู่หลังผู้ใช้ถูกลบ
+-- changes เก็บ diff เป็น JSON ในรูป { field: { from, to } } หรือรายละเอียดของเหตุการณ์
+-- ================================
+CREATE TABLE audit_events (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    action VARCHAR(64) NOT NULL,
+    actor_id UUID,
+    target_type VARCHAR(32),
+    target_id UUID,
+    ip_address VARCHAR(64),
+    user_agent TEXT,
+    request_id VARCHAR(128),
+    changes JSONB NOT NULL DEFAULT '{}'::jsonb,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_audit_events_created_at ON audit_events (created_at);
+CREATE INDEX idx_audit_events_actor_id ON audit_events (actor_id, created_at) WHERE actor_id IS NOT NULL;
+CREATE INDEX idx_audit_events_target_id ON audit_events (target_id, created_at) WHERE target_id IS NOT NULL;
@@ -0,0 +1,8 @@
+-- ================================
+-- เพิ่ม heartbeat_at ให้ worker ต่ออายุ lease ระหว่างที่ job ยังทำงานอยู่
+-- job ที่ไม่มี heartbeat เกิน lease_timeout จะถูกคืนกลับไปเป็น pending
+-- ================================
+ALTER TABLE generation_jobs
+ADD COLUMN heartbeat_at TIMESTAMPTZ;
+
+UPDATE generation_jobs SET heartbeat_at = started_at WHERE status = 'running';
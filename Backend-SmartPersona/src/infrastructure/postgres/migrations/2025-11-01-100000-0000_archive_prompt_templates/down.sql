@@ -0,0 +1,7 @@
+DELETE FROM prompt_templates WHERE archived_at IS NOT NULL;
+
+DROP INDEX IF EXISTS idx_prompt_templates_name_active;
+ALTER TABLE prompt_templates ADD CONSTRAINT prompt_templates_name_key UNIQUE (name);
+
+ALTER TABLE prompt_templates
+DROP COLUMN archived_at;
@@ -0,0 +1,12 @@
+-- ================================
+-- 1. เก็บ template ที่ถูกลบไว้เป็น archived แทนการลบจริง
+-- version เดิมยังต้องอยู่เพื่อให้ย้อนดู prompt ของ job เก่าได้
+-- ================================
+ALTER TABLE prompt_templates
+ADD COLUMN archived_at TIMESTAMPTZ;
+
+-- ================================
+-- 2. ชื่อซ้ำได้เฉพาะกับ template ที่ archived แล้ว
+-- ================================
+ALTER TABLE prompt_templates DROP CONSTRAINT prompt_templates_name_key;
+CREATE UNIQUE INDEX idx_prompt_templates_name_active ON prompt_templates(name) WHERE archived_at IS NULL;
@@ -0,0 +1,12 @@
+-- ================================
+-- บันทึก jti ของ MFA token ที่ใช้ login สำเร็จแล้ว ให้ token แต่ละใบใช้ได้ครั้งเดียว
+-- แถวที่หมดอายุแล้วลบทิ้งได้ เพราะ token ที่หมดอายุใช้ไม่ได้อยู่แล้ว
+-- ================================
+CREATE TABLE used_mfa_tokens (
+    jti UUID PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    expires_at TIMESTAMPTZ NOT NULL,
+    used_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_used_mfa_tokens_expires_at ON used_mfa_tokens (expires_at);
@@ -0,0 +1,23 @@
+-- ================================
+-- ให้ username ไม่ซ้ำกันแบบไม่สนตัวพิมพ์เล็กใหญ่ ("Alice" กับ "alice" คือบัญชีเดียวกัน)
+-- ชื่อ constraint UNIQUE เดิมขึ้นกับประวัติ migration (users_email_key หรือ users_username_key)
+-- จึงหาจาก catalog แล้วลบทิ้ง
+-- ================================
+DO $$
+DECLARE
+    constraint_name TEXT;
+BEGIN
+    FOR constraint_name IN
+        SELECT c.conname
+        FROM pg_constraint c
+        JOIN pg_attribute a ON a.attrelid = c.conrelid AND a.attnum = ANY (c.conkey)
+        WHERE c.conrelid = 'users'::regclass
+          AND c.contype = 'u'
+          AND array_length(c.conkey, 1) = 1
+          AND a.attname = 'username'
+    LOOP
+        EXECUTE format('ALTER TABLE users DROP CONSTRAINT %I', constraint_name);
+    END LOOP;
+END $$;
+
+CREATE UNIQUE INDEX idx_users_username_lower ON users (lower(username));
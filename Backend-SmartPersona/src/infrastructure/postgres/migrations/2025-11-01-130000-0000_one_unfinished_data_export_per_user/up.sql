@@ -0,0 +1,20 @@
+-- ================================
+-- ผู้ใช้หนึ่งคนมี export ที่ยังไม่เสร็จ (pending/running) ได้ครั้งละหนึ่งงาน
+-- คำขอที่ซ้อนกันจะชน unique index แทนที่จะสร้างงานซ้ำ
+-- งานที่ซ้ำอยู่แล้วให้เหลือเฉพาะงานแรกของแต่ละผู้ใช้ ที่เหลือปิดเป็น failed
+-- ================================
+UPDATE data_exports
+SET status = 'failed',
+    error = 'Superseded by an earlier export request',
+    completed_at = NOW()
+WHERE status IN ('pending', 'running')
+  AND id NOT IN (
+      SELECT DISTINCT ON (user_id) id
+      FROM data_exports
+      WHERE status IN ('pending', 'running')
+      ORDER BY user_id, created_at
+  );
+
+CREATE UNIQUE INDEX idx_data_exports_one_unfinished_per_user
+ON data_exports (user_id)
+WHERE status IN ('pending', 'running');
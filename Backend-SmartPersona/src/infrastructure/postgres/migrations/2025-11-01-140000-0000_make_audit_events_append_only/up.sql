@@ -0,0 +1,20 @@
+-- ================================
+-- audit_events เป็นแบบเพิ่มได้อย่างเดียว ห้ามแก้ไขหรือลบแถวที่บันทึกไปแล้ว
+-- รวมถึงตอนลบบัญชีผู้ใช้ (erasure) ซึ่งไม่แตะตารางนี้อยู่แล้ว
+-- ================================
+CREATE OR REPLACE FUNCTION reject_audit_event_changes()
+RETURNS TRIGGER AS $$
+BEGIN
+  RAISE EXCEPTION 'audit_events is append-only, % is not allowed', TG_OP;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER audit_events_append_only
+BEFORE UPDATE OR DELETE ON audit_events
+FOR EACH ROW
+EXECUTE PROCEDURE reject_audit_event_changes();
+
+CREATE TRIGGER audit_events_no_truncate
+BEFORE TRUNCATE ON audit_events
+FOR EACH STATEMENT
+EXECUTE PROCEDURE reject_audit_event_changes();
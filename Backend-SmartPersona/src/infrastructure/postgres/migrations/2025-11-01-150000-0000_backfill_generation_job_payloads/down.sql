@@ -0,0 +1,2 @@
+ALTER TABLE generation_jobs
+ALTER COLUMN payload SET DEFAULT '{}'::jsonb;
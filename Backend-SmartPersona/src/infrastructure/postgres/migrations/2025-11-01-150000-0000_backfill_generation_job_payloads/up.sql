@@ -0,0 +1,14 @@
+-- ================================
+-- 1. เติม payload ให้ job ที่สร้างก่อนมีคอลัมน์ payload
+--    job ที่ยังไม่เสร็จจะดึงโพสต์ของผู้ขอใหม่บน worker
+-- ================================
+UPDATE generation_jobs
+SET payload = jsonb_build_object('requester_id', requester_id, 'language', NULL, 'tag_count', NULL)
+WHERE payload = '{}'::jsonb
+  AND status IN ('pending', 'running');
+
+-- ================================
+-- 2. เลิกใช้ค่า default ทุก job ต้องระบุ payload เอง
+-- ================================
+ALTER TABLE generation_jobs
+ALTER COLUMN payload DROP DEFAULT;
@@ -0,0 +1,49 @@
+-- ================================
+-- 1. ยอมให้ลบข้อมูลส่วนบุคคลออกจาก audit_events ได้ทางเดียว คือผ่าน redact_audit_events()
+--    ระหว่างที่ฟังก์ชันทำงาน audit.allow_redaction เป็น on (เฉพาะใน transaction นั้น)
+--    และแก้ได้แค่ ip_address / user_agent เป็น NULL กับแทน changes ด้วย {"redacted": true}
+--    คอลัมน์อื่นต้องเหมือนเดิม การลบแถวและ TRUNCATE ยังห้ามเหมือนเดิม
+-- ================================
+CREATE OR REPLACE FUNCTION reject_audit_event_changes()
+RETURNS TRIGGER AS $$
+BEGIN
+  IF TG_OP = 'UPDATE' AND current_setting('audit.allow_redaction', true) = 'on' THEN
+    IF NEW.id = OLD.id
+       AND NEW.action = OLD.action
+       AND NEW.actor_id IS NOT DISTINCT FROM OLD.actor_id
+       AND NEW.target_type IS NOT DISTINCT FROM OLD.target_type
+       AND NEW.target_id IS NOT DISTINCT FROM OLD.target_id
+       AND NEW.request_id IS NOT DISTINCT FROM OLD.request_id
+       AND NEW.created_at = OLD.created_at
+       AND NEW.ip_address IS NULL
+       AND NEW.user_agent IS NULL
+       AND NEW.changes = '{"redacted": true}'::jsonb
+    THEN
+      RETURN NEW;
+    END IF;
+  END IF;
+  RAISE EXCEPTION 'audit_events is append-only, % is not allowed', TG_OP;
+END;
+$$ LANGUAGE plpgsql;
+
+-- ================================
+-- 2. ลบข้อมูลส่วนบุคคลของผู้ใช้ออกจากทุกแถวที่ผู้ใช้เป็น actor หรือ target
+--    เก็บ id, action และเวลาไว้ ประวัติยังบอกได้ว่าเกิดอะไรขึ้นเมื่อไร
+--    เรียกใน transaction เดียวกับการลบบัญชี คืนจำนวนแถวที่ถูกแก้
+-- ================================
+CREATE OR REPLACE FUNCTION redact_audit_events(erased_user_id UUID)
+RETURNS INTEGER AS $$
+DECLARE
+  redacted INTEGER;
+BEGIN
+  PERFORM set_config('audit.allow_redaction', 'on', true);
+  UPDATE audit_events
+  SET ip_address = NULL,
+      user_agent = NULL,
+      changes = '{"redacted": true}'::jsonb
+  WHERE actor_id = erased_user_id OR target_id = erased_user_id;
+  GET DIAGNOSTICS redacted = ROW_COUNT;
+  PERFORM set_config('audit.allow_redaction', 'off', true);
+  RETURN redacted;
+END;
+$$ LANGUAGE plpgsql;
@@ -1,3 +1,5 @@
 pub mod repositories;
 pub mod postgres_connection;
-pub mod schema;
\ No newline at end of file
+pub mod schema;
+pub mod job_events;
+pub mod error;
\ No newline at end of file
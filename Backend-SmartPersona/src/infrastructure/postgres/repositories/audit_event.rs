@@ -0,0 +1,68 @@
+use std::sync::Arc;
+
+use axum::async_trait;
+use diesel::{dsl::insert_into, pg::Pg, prelude::*};
+
+use crate::{
+    domain::{
+        entities::audit_event::{AuditEventEntity, AuditEventFilter, InsertAuditEventEntity},
+        error::AppResult,
+        repo::audit_event::AuditEventRepository,
+    },
+    infrastructure::postgres::{postgres_connection::DbPool, schema::audit_events},
+};
+
+pub struct AuditEventPostgres {
+    db_pool: Arc<DbPool>,
+}
+
+impl AuditEventPostgres {
+    pub fn new(db_pool: Arc<DbPool>) -> Self {
+        Self { db_pool }
+    }
+}
+
+#[async_trait]
+impl AuditEventRepository for AuditEventPostgres {
+    async fn create(&self, insert_audit_event_entity: InsertAuditEventEntity) -> AppResult<AuditEventEntity> {
+        let mut conn = Arc::clone(&self.db_pool).get()?;
+        let result = insert_into(audit_events::table)
+            .values(insert_audit_event_entity)
+            .returning(AuditEventEntity::as_returning())
+            .get_result::<AuditEventEntity>(&mut conn)?;
+        Ok(result)
+    }
+
+    async fn search(&self, filter: AuditEventFilter) -> AppResult<(Vec<AuditEventEntity>, i64)> {
+        let mut conn = Arc::clone(&self.db_pool).get()?;
+        let total = filtered_events(&filter).count().get_result::<i64>(&mut conn)?;
+        let result = filtered_events(&filter)
+            .order((audit_events::created_at.desc(), audit_events::id.asc()))
+            .limit(filter.limit)
+            .offset(filter.offset)
+            .select(AuditEventEntity::as_select())
+            .load::<AuditEventEntity>(&mut conn)?;
+        Ok((result, total))
+    }
+}
+
+// Shared by the page query and its count so both apply the same filters.
+fn filtered_events(filter: &AuditEventFilter) -> audit_events::BoxedQuery<'static, Pg> {
+    let mut query = audit_events::table.into_boxed();
+    if let Some(actor_id) = filter.actor_id {
+        query = query.filter(audit_events::actor_id.eq(actor_id));
+    }
+    if let Some(target_id) = filter.target_id {
+        query = query.filter(audit_events::target_id.eq(target_id));
+    }
+    if let Some(action) = filter.action.clone() {
+        query = query.filter(audit_events::action.eq(action));
+    }
+    if let Some(from) = filter.from {
+        query = query.filter(audit_events::created_at.ge(from));
+    }
+    if let Some(to) = filter.to {
+        query = query.filter(audit_events::created_at.lt(to));
+    }
+    query
+}
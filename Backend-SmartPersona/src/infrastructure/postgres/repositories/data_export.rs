@@ -0,0 +1,115 @@
+use std::sync::Arc;
+
+use axum::async_trait;
+use chrono::{NaiveDateTime, Utc};
+use diesel::{dsl::{delete, insert_into, update}, prelude::*};
+use uuid::Uuid;
+
+use crate::{
+    domain::{
+        entities::{
+            data_export::{DataExportEntity, InsertDataExportEntity},
+            generation_job::JobStatus,
+        },
+        error::AppResult,
+        repo::data_export::DataExportRepository,
+    },
+    infrastructure::postgres::{postgres_connection::DbPool, schema::data_exports},
+};
+
+pub struct DataExportPostgres {
+    db_pool: Arc<DbPool>,
+}
+
+impl DataExportPostgres {
+    pub fn new(db_pool: Arc<DbPool>) -> Self {
+        Self { db_pool }
+    }
+}
+
+#[async_trait]
+impl DataExportRepository for DataExportPostgres {
+    async fn create(&self, insert_data_export_entity: InsertDataExportEntity) -> AppResult<DataExportEntity> {
+        let mut conn = Arc::clone(&self.db_pool).get()?;
+        let result = insert_into(data_exports::table)
+            .values(insert_data_export_entity)
+            .returning(DataExportEntity::as_returning())
+            .get_result::<DataExportEntity>(&mut conn)?;
+        Ok(result)
+    }
+
+    async fn find_by_id(&self, export_id: Uuid) -> AppResult<DataExportEntity> {
+        let mut conn = Arc::clone(&self.db_pool).get()?;
+        let result = data_exports::table
+            .filter(data_exports::id.eq(export_id))
+            .select(DataExportEntity::as_select())
+            .first::<DataExportEntity>(&mut conn)?;
+        Ok(result)
+    }
+
+    async fn claim_next(&self, stale_before: NaiveDateTime) -> AppResult<Option<DataExportEntity>> {
+        let mut conn = Arc::clone(&self.db_pool).get()?;
+        let result = conn.transaction::<_, diesel::result::Error, _>(|conn| {
+            let claimable_export_id = data_exports::table
+                .filter(
+                    data_exports::status.eq(JobStatus::Pending).or(data_exports::status
+                        .eq(JobStatus::Running)
+                        .and(data_exports::started_at.lt(stale_before))),
+                )
+                .order(data_exports::created_at.asc())
+                .select(data_exports::id)
+                .for_update()
+                .skip_locked()
+                .first::<Uuid>(conn)
+                .optional()?;
+
+            let Some(export_id) = claimable_export_id else {
+                return Ok(None);
+            };
+
+            let data_export = update(data_exports::table.filter(data_exports::id.eq(export_id)))
+                .set((
+                    data_exports::status.eq(JobStatus::Running),
+                    data_exports::started_at.eq(Some(Utc::now().naive_utc())),
+                ))
+                .returning(DataExportEntity::as_returning())
+                .get_result::<DataExportEntity>(conn)?;
+
+            Ok(Some(data_export))
+        })?;
+        Ok(result)
+    }
+
+    async fn mark_completed(&self, export_id: Uuid, data: serde_json::Value, expires_at: NaiveDateTime) -> AppResult<()> {
+        let mut conn = Arc::clone(&self.db_pool).get()?;
+        update(data_exports::table.filter(data_exports::id.eq(export_id)))
+            .set((
+                data_exports::status.eq(JobStatus::Completed),
+                data_exports::data.eq(Some(data)),
+                data_exports::error.eq(None::<String>),
+                data_exports::completed_at.eq(Some(Utc::now().naive_utc())),
+                data_exports::expires_at.eq(Some(expires_at)),
+            ))
+            .execute(&mut conn)?;
+        Ok(())
+    }
+
+    async fn mark_failed(&self, export_id: Uuid, error: String) -> AppResult<()> {
+        let mut conn = Arc::clone(&self.db_pool).get()?;
+        update(data_exports::table.filter(data_exports::id.eq(export_id)))
+            .set((
+                data_exports::status.eq(JobStatus::Failed),
+                data_exports::error.eq(Some(error)),
+                data_exports::completed_at.eq(Some(Utc::now().naive_utc())),
+            ))
+            .execute(&mut conn)?;
+        Ok(())
+    }
+
+    async fn delete_expired(&self, now: NaiveDateTime) -> AppResult<usize> {
+        let mut conn = Arc::clone(&self.db_pool).get()?;
+        let result = delete(data_exports::table.filter(data_exports::expires_at.lt(now)))
+            .execute(&mut conn)?;
+        Ok(result)
+    }
+}
@@ -0,0 +1,195 @@
+use std::sync::Arc;
+
+use axum::async_trait;
+use chrono::NaiveDateTime;
+use diesel::{dsl::{insert_into, update}, prelude::*};
+use uuid::Uuid;
+
+use crate::{
+    domain::{
+        error::AppResult,
+        entities::generation_job::{GenerationJobEntity, InsertGenerationJobEntity, JobStatus},
+        repo::generation_job::GenerationJobRepository,
+    },
+    infrastructure::postgres::{postgres_connection::DbPool, schema::generation_jobs},
+};
+
+pub struct GenerationJobPostgres {
+    db_pool: Arc<DbPool>,
+}
+
+impl GenerationJobPostgres {
+    pub fn new(db_pool: Arc<DbPool>) -> Self {
+        Self { db_pool }
+    }
+}
+
+#[async_trait]
+impl GenerationJobRepository for GenerationJobPostgres {
+    async fn create(&self, insert_generation_job_entity: InsertGenerationJobEntity) -> AppResult<GenerationJobEntity> {
+        let mut conn = Arc::clone(&self.db_pool).get()?;
+        let result = insert_into(generation_jobs::table)
+            .values(insert_generation_job_entity)
+            .returning(GenerationJobEntity::as_returning())
+            .get_result::<GenerationJobEntity>(&mut conn)?;
+        Ok(result)
+    }
+
+    async fn find_by_id(&self, job_id: Uuid) -> AppResult<GenerationJobEntity> {
+        let mut conn = Arc::clone(&self.db_pool).get()?;
+        let result = generation_jobs::table
+            .filter(generation_jobs::id.eq(job_id))
+            .select(GenerationJobEntity::as_select())
+            .first::<GenerationJobEntity>(&mut conn)?;
+        Ok(result)
+    }
+
+    async fn find_by_requester(&self, requester_id: Uuid, limit: i64, offset: i64) -> AppResult<Vec<GenerationJobEntity>> {
+        let mut conn = Arc::clone(&self.db_pool).get()?;
+        let result = generation_jobs::table
+            .filter(generation_jobs::requester_id.eq(requester_id))
+            .order((generation_jobs::created_at.desc(), generation_jobs::id.asc()))
+            .limit(limit)
+            .offset(offset)
+            .select(GenerationJobEntity::as_select())
+            .load::<GenerationJobEntity>(&mut conn)?;
+        Ok(result)
+    }
+
+    async fn claim_next(&self) -> AppResult<Option<GenerationJobEntity>> {
+        let mut conn = Arc::clone(&self.db_pool).get()?;
+        let result = conn.transaction::<_, diesel::result::Error, _>(|conn| {
+            let pending_job_id = generation_jobs::table
+                .filter(generation_jobs::status.eq(JobStatus::Pending))
+                .order(generation_jobs::created_at.asc())
+                .select(generation_jobs::id)
+                .for_update()
+                .skip_locked()
+                .first::<Uuid>(conn)
+                .optional()?;
+
+            let Some(job_id) = pending_job_id else {
+                return Ok(None);
+            };
+
+            let job = update(generation_jobs::table.filter(generation_jobs::id.eq(job_id)))
+                .set((
+                    generation_jobs::status.eq(JobStatus::Running),
+                    generation_jobs::started_at.eq(Some(chrono::Utc::now().naive_utc())),
+                    generation_jobs::heartbeat_at.eq(Some(chrono::Utc::now().naive_utc())),
+                    generation_jobs::attempts.eq(generation_jobs::attempts + 1),
+                ))
+                .returning(GenerationJobEntity::as_returning())
+                .get_result::<GenerationJobEntity>(conn)?;
+
+            Ok(Some(job))
+        })?;
+        Ok(result)
+    }
+
+    async fn record_prompt(
+        &self,
+        job_id: Uuid,
+        attempt: i32,
+        prompt: String,
+        prompt_template_version_id: Uuid,
+        payload: serde_json::Value,
+    ) -> AppResult<bool> {
+        let mut conn = Arc::clone(&self.db_pool).get()?;
+        let result = update(
+            generation_jobs::table
+                .filter(generation_jobs::id.eq(job_id))
+                .filter(generation_jobs::status.eq(JobStatus::Running))
+                .filter(generation_jobs::attempts.eq(attempt)),
+        )
+        .set((
+            generation_jobs::prompt.eq(Some(prompt)),
+            generation_jobs::prompt_template_version_id.eq(Some(prompt_template_version_id)),
+            generation_jobs::payload.eq(payload),
+        ))
+        .execute(&mut conn)?;
+        Ok(result > 0)
+    }
+
+    async fn extend_lease(&self, job_id: Uuid, attempt: i32) -> AppResult<bool> {
+        let mut conn = Arc::clone(&self.db_pool).get()?;
+        let result = update(
+            generation_jobs::table
+                .filter(generation_jobs::id.eq(job_id))
+                .filter(generation_jobs::status.eq(JobStatus::Running))
+                .filter(generation_jobs::attempts.eq(attempt)),
+        )
+        .set(generation_jobs::heartbeat_at.eq(Some(chrono::Utc::now().naive_utc())))
+        .execute(&mut conn)?;
+        Ok(result > 0)
+    }
+
+    async fn mark_completed(&self, job_id: Uuid, attempt: i32, result: serde_json::Value) -> AppResult<bool> {
+        let mut conn = Arc::clone(&self.db_pool).get()?;
+        let result = update(
+            generation_jobs::table
+                .filter(generation_jobs::id.eq(job_id))
+                .filter(generation_jobs::status.eq(JobStatus::Running))
+                .filter(generation_jobs::attempts.eq(attempt)),
+        )
+        .set((
+            generation_jobs::status.eq(JobStatus::Completed),
+            generation_jobs::result.eq(Some(result)),
+            generation_jobs::error.eq(None::<String>),
+            generation_jobs::completed_at.eq(Some(chrono::Utc::now().naive_utc())),
+        ))
+        .execute(&mut conn)?;
+        Ok(result > 0)
+    }
+
+    async fn mark_failed(&self, job_id: Uuid, attempt: i32, error: String) -> AppResult<bool> {
+        let mut conn = Arc::clone(&self.db_pool).get()?;
+        let result = update(
+            generation_jobs::table
+                .filter(generation_jobs::id.eq(job_id))
+                .filter(generation_jobs::status.eq(JobStatus::Running))
+                .filter(generation_jobs::attempts.eq(attempt)),
+        )
+        .set((
+            generation_jobs::status.eq(JobStatus::Failed),
+            generation_jobs::error.eq(Some(error)),
+            generation_jobs::completed_at.eq(Some(chrono::Utc::now().naive_utc())),
+        ))
+        .execute(&mut conn)?;
+        Ok(result > 0)
+    }
+
+    async fn requeue(&self, job_id: Uuid, attempt: i32, error: String) -> AppResult<bool> {
+        let mut conn = Arc::clone(&self.db_pool).get()?;
+        let result = update(
+            generation_jobs::table
+                .filter(generation_jobs::id.eq(job_id))
+                .filter(generation_jobs::status.eq(JobStatus::Running))
+                .filter(generation_jobs::attempts.eq(attempt)),
+        )
+        .set((
+            generation_jobs::status.eq(JobStatus::Pending),
+            generation_jobs::error.eq(Some(error)),
+            generation_jobs::started_at.eq(None::<NaiveDateTime>),
+            generation_jobs::heartbeat_at.eq(None::<NaiveDateTime>),
+        ))
+        .execute(&mut conn)?;
+        Ok(result > 0)
+    }
+
+    async fn release_stale(&self, heartbeat_before: NaiveDateTime) -> AppResult<usize> {
+        let mut conn = Arc::clone(&self.db_pool).get()?;
+        let result = update(
+            generation_jobs::table
+                .filter(generation_jobs::status.eq(JobStatus::Running))
+                .filter(generation_jobs::heartbeat_at.lt(heartbeat_before)),
+        )
+        .set((
+            generation_jobs::status.eq(JobStatus::Pending),
+            generation_jobs::started_at.eq(None::<NaiveDateTime>),
+            generation_jobs::heartbeat_at.eq(None::<NaiveDateTime>),
+        ))
+        .execute(&mut conn)?;
+        Ok(result)
+    }
+}
@@ -0,0 +1,207 @@
+use std::sync::Arc;
+
+use axum::async_trait;
+use chrono::{NaiveDateTime, Utc};
+use diesel::{dsl::{delete, insert_into, update}, prelude::*, upsert::excluded};
+use uuid::Uuid;
+
+use crate::{
+    domain::{
+        entities::mfa::{InsertRecoveryCodeEntity, RecoveryCodeEntity, UserMfaEntity},
+        error::AppResult,
+        repo::mfa::MfaRepository,
+    },
+    infrastructure::{
+        postgres::{
+            postgres_connection::DbPool,
+            schema::{mfa_recovery_codes, used_mfa_tokens, user_mfa},
+        },
+        token_encryption::{Envelope, EnvelopeCipher},
+    },
+};
+
+#[derive(Debug, Clone, Queryable, Selectable)]
+#[diesel(table_name = user_mfa)]
+struct UserMfaRow {
+    user_id: Uuid,
+    secret: String,
+    encryption_key_id: String,
+    wrapped_data_key: String,
+    confirmed_at: Option<NaiveDateTime>,
+    last_used_step: Option<i64>,
+    created_at: NaiveDateTime,
+    updated_at: NaiveDateTime,
+}
+
+#[derive(Debug, Clone, Insertable)]
+#[diesel(table_name = user_mfa)]
+struct InsertUserMfaRow {
+    user_id: Uuid,
+    secret: String,
+    encryption_key_id: String,
+    wrapped_data_key: String,
+    created_at: NaiveDateTime,
+    updated_at: NaiveDateTime,
+}
+
+pub struct MfaPostgres {
+    db_pool: Arc<DbPool>,
+    cipher: Arc<EnvelopeCipher>,
+}
+
+impl MfaPostgres {
+    pub fn new(db_pool: Arc<DbPool>, cipher: Arc<EnvelopeCipher>) -> Self {
+        Self { db_pool, cipher }
+    }
+
+    fn decrypt(&self, row: UserMfaRow) -> AppResult<UserMfaEntity> {
+        let data_key = self.cipher.open_envelope(&Envelope {
+            key_id: row.encryption_key_id,
+            wrapped_data_key: row.wrapped_data_key,
+        })?;
+
+        Ok(UserMfaEntity {
+            secret: data_key.decrypt(&secret_aad(row.user_id), &row.secret)?,
+            user_id: row.user_id,
+            confirmed_at: row.confirmed_at,
+            last_used_step: row.last_used_step,
+            created_at: row.created_at,
+            updated_at: row.updated_at,
+        })
+    }
+}
+
+#[async_trait]
+impl MfaRepository for MfaPostgres {
+    async fn find_by_user_id(&self, user_id: Uuid) -> AppResult<UserMfaEntity> {
+        let mut conn = Arc::clone(&self.db_pool).get()?;
+        let result = user_mfa::table
+            .filter(user_mfa::user_id.eq(user_id))
+            .select(UserMfaRow::as_select())
+            .first::<UserMfaRow>(&mut conn)?;
+
+        self.decrypt(result)
+    }
+
+    async fn start_enrolment(&self, user_id: Uuid, secret: String) -> AppResult<UserMfaEntity> {
+        let (envelope, data_key) = self.cipher.new_envelope()?;
+        let now = Utc::now().naive_utc();
+        let row = InsertUserMfaRow {
+            user_id,
+            secret: data_key.encrypt(&secret_aad(user_id), &secret)?,
+            encryption_key_id: envelope.key_id,
+            wrapped_data_key: envelope.wrapped_data_key,
+            created_at: now,
+            updated_at: now,
+        };
+
+        let mut conn = Arc::clone(&self.db_pool).get()?;
+        let result = insert_into(user_mfa::table)
+            .values(row)
+            .on_conflict(user_mfa::user_id)
+            .do_update()
+            .set((
+                user_mfa::secret.eq(excluded(user_mfa::secret)),
+                user_mfa::encryption_key_id.eq(excluded(user_mfa::encryption_key_id)),
+                user_mfa::wrapped_data_key.eq(excluded(user_mfa::wrapped_data_key)),
+                user_mfa::confirmed_at.eq(None::<NaiveDateTime>),
+                user_mfa::last_used_step.eq(None::<i64>),
+                user_mfa::updated_at.eq(now),
+            ))
+            .returning(UserMfaRow::as_returning())
+            .get_result::<UserMfaRow>(&mut conn)?;
+
+        self.decrypt(result)
+    }
+
+    async fn confirm(&self, user_id: Uuid, recovery_codes: Vec<InsertRecoveryCodeEntity>) -> AppResult<()> {
+        let mut conn = Arc::clone(&self.db_pool).get()?;
+        conn.transaction::<_, diesel::result::Error, _>(|conn| {
+            let now = Utc::now().naive_utc();
+            update(user_mfa::table.filter(user_mfa::user_id.eq(user_id)))
+                .set((user_mfa::confirmed_at.eq(Some(now)), user_mfa::updated_at.eq(now)))
+                .execute(conn)?;
+            delete(mfa_recovery_codes::table.filter(mfa_recovery_codes::user_id.eq(user_id))).execute(conn)?;
+            insert_into(mfa_recovery_codes::table)
+                .values(&recovery_codes)
+                .execute(conn)?;
+            Ok(())
+        })?;
+        Ok(())
+    }
+
+    async fn use_step(&self, user_id: Uuid, step: i64) -> AppResult<bool> {
+        let mut conn = Arc::clone(&self.db_pool).get()?;
+        let result = update(
+            user_mfa::table
+                .filter(user_mfa::user_id.eq(user_id))
+                .filter(user_mfa::last_used_step.is_null().or(user_mfa::last_used_step.lt(step))),
+        )
+        .set(user_mfa::last_used_step.eq(Some(step)))
+        .execute(&mut conn)?;
+        Ok(result == 1)
+    }
+
+    async fn unused_recovery_codes(&self, user_id: Uuid) -> AppResult<Vec<RecoveryCodeEntity>> {
+        let mut conn = Arc::clone(&self.db_pool).get()?;
+        let result = mfa_recovery_codes::table
+            .filter(mfa_recovery_codes::user_id.eq(user_id))
+            .filter(mfa_recovery_codes::used_at.is_null())
+            .select(RecoveryCodeEntity::as_select())
+            .load::<RecoveryCodeEntity>(&mut conn)?;
+        Ok(result)
+    }
+
+    async fn use_recovery_code(&self, recovery_code_id: Uuid) -> AppResult<bool> {
+        let mut conn = Arc::clone(&self.db_pool).get()?;
+        let result = update(
+            mfa_recovery_codes::table
+                .filter(mfa_recovery_codes::id.eq(recovery_code_id))
+                .filter(mfa_recovery_codes::used_at.is_null()),
+        )
+        .set(mfa_recovery_codes::used_at.eq(Some(Utc::now().naive_utc())))
+        .execute(&mut conn)?;
+        Ok(result == 1)
+    }
+
+    async fn replace_recovery_codes(&self, user_id: Uuid, recovery_codes: Vec<InsertRecoveryCodeEntity>) -> AppResult<()> {
+        let mut conn = Arc::clone(&self.db_pool).get()?;
+        conn.transaction::<_, diesel::result::Error, _>(|conn| {
+            delete(mfa_recovery_codes::table.filter(mfa_recovery_codes::user_id.eq(user_id))).execute(conn)?;
+            insert_into(mfa_recovery_codes::table)
+                .values(&recovery_codes)
+                .execute(conn)?;
+            Ok(())
+        })?;
+        Ok(())
+    }
+
+    async fn delete(&self, user_id: Uuid) -> AppResult<()> {
+        let mut conn = Arc::clone(&self.db_pool).get()?;
+        conn.transaction::<_, diesel::result::Error, _>(|conn| {
+            delete(mfa_recovery_codes::table.filter(mfa_recovery_codes::user_id.eq(user_id))).execute(conn)?;
+            delete(user_mfa::table.filter(user_mfa::user_id.eq(user_id))).execute(conn)?;
+            Ok(())
+        })?;
+        Ok(())
+    }
+
+    async fn use_mfa_token(&self, jti: Uuid, user_id: Uuid, expires_at: NaiveDateTime) -> AppResult<bool> {
+        let mut conn = Arc::clone(&self.db_pool).get()?;
+        delete(used_mfa_tokens::table.filter(used_mfa_tokens::expires_at.lt(Utc::now().naive_utc()))).execute(&mut conn)?;
+
+        let result = insert_into(used_mfa_tokens::table)
+            .values((
+                used_mfa_tokens::jti.eq(jti),
+                used_mfa_tokens::user_id.eq(user_id),
+                used_mfa_tokens::expires_at.eq(expires_at),
+            ))
+            .on_conflict_do_nothing()
+            .execute(&mut conn)?;
+        Ok(result == 1)
+    }
+}
+
+fn secret_aad(user_id: Uuid) -> Vec<u8> {
+    format!("user_mfa:{}", user_id).into_bytes()
+}
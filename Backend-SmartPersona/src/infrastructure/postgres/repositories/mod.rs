@@ -1 +1,11 @@
-pub mod user;
\ No newline at end of file
+pub mod user;
+pub mod profile;
+pub mod generation_job;
+pub mod prompt_template;
+pub mod social_connection;
+pub mod session;
+pub mod mfa;
+pub mod password_reset;
+pub mod data_export;
+pub mod personal_data;
+pub mod audit_event;
\ No newline at end of file
@@ -0,0 +1,85 @@
+use std::sync::Arc;
+
+use axum::async_trait;
+use chrono::{NaiveDateTime, Utc};
+use diesel::{dsl::{insert_into, update}, prelude::*};
+use uuid::Uuid;
+
+use crate::{
+    domain::{
+        entities::password_reset::{InsertPasswordResetTokenEntity, PasswordResetTokenEntity},
+        error::AppResult,
+        repo::password_reset::PasswordResetRepository,
+    },
+    infrastructure::postgres::{
+        postgres_connection::DbPool,
+        schema::{password_reset_tokens, sessions, users},
+    },
+};
+
+pub struct PasswordResetPostgres {
+    db_pool: Arc<DbPool>,
+}
+
+impl PasswordResetPostgres {
+    pub fn new(db_pool: Arc<DbPool>) -> Self {
+        Self { db_pool }
+    }
+}
+
+#[async_trait]
+impl PasswordResetRepository for PasswordResetPostgres {
+    async fn create(&self, insert_password_reset_token_entity: InsertPasswordResetTokenEntity) -> AppResult<PasswordResetTokenEntity> {
+        let mut conn = Arc::clone(&self.db_pool).get()?;
+        let result = insert_into(password_reset_tokens::table)
+            .values(insert_password_reset_token_entity)
+            .returning(PasswordResetTokenEntity::as_returning())
+            .get_result::<PasswordResetTokenEntity>(&mut conn)?;
+        Ok(result)
+    }
+
+    async fn reset_password(&self, token_hash: String, password_hash: String) -> AppResult<Uuid> {
+        let now = Utc::now().naive_utc();
+        let mut conn = Arc::clone(&self.db_pool).get()?;
+        let user_id = conn.transaction::<_, diesel::result::Error, _>(|conn| {
+            let user_id = update(
+                password_reset_tokens::table
+                    .filter(password_reset_tokens::token_hash.eq(token_hash))
+                    .filter(password_reset_tokens::used_at.is_null())
+                    .filter(password_reset_tokens::expires_at.gt(now)),
+            )
+            .set(password_reset_tokens::used_at.eq(Some(now)))
+            .returning(password_reset_tokens::user_id)
+            .get_result::<Uuid>(conn)?;
+
+            update(
+                password_reset_tokens::table
+                    .filter(password_reset_tokens::user_id.eq(user_id))
+                    .filter(password_reset_tokens::used_at.is_null()),
+            )
+            .set(password_reset_tokens::used_at.eq(Some(now)))
+            .execute(conn)?;
+
+            // Proving ownership of the email also lifts a lockout from failed logins.
+            update(users::table.filter(users::id.eq(user_id)))
+                .set((
+                    users::password_hash.eq(password_hash),
+                    users::failed_login_attempts.eq(0),
+                    users::locked_until.eq(None::<NaiveDateTime>),
+                    users::updated_at.eq(now),
+                ))
+                .execute(conn)?;
+
+            update(
+                sessions::table
+                    .filter(sessions::user_id.eq(user_id))
+                    .filter(sessions::revoked_at.is_null()),
+            )
+            .set(sessions::revoked_at.eq(Some(now)))
+            .execute(conn)?;
+
+            Ok(user_id)
+        })?;
+        Ok(user_id)
+    }
+}
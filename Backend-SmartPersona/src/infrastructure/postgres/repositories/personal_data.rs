@@ -0,0 +1,269 @@
+use std::sync::Arc;
+
+use axum::async_trait;
+use chrono::NaiveDateTime;
+use diesel::{dsl::delete, prelude::*};
+use uuid::Uuid;
+
+use crate::{
+    domain::{
+        entities::{
+            generation_job::GenerationJobEntity, personal_data::PersonalDataEntity, profile::ProfileEntity,
+            session::SessionEntity, social_connection::SocialConnectionEntity, user::UserEntity,
+        },
+        error::AppResult,
+        repo::personal_data::PersonalDataRepository,
+    },
+    infrastructure::postgres::{
+        postgres_connection::DbPool,
+        schema::{
+            data_exports, generation_jobs, mfa_recovery_codes, password_reset_tokens, profiles, sessions,
+            social_connections, used_mfa_tokens, user_mfa, users,
+        },
+    },
+};
+
+define_sql_function!(fn redact_audit_events(erased_user_id: diesel::sql_types::Uuid) -> diesel::sql_types::Integer);
+
+type SocialConnectionMetadata = (Uuid, Uuid, String, String, Option<NaiveDateTime>, NaiveDateTime, i32, Option<NaiveDateTime>);
+
+pub struct PersonalDataPostgres {
+    db_pool: Arc<DbPool>,
+}
+
+impl PersonalDataPostgres {
+    pub fn new(db_pool: Arc<DbPool>) -> Self {
+        Self { db_pool }
+    }
+}
+
+#[async_trait]
+impl PersonalDataRepository for PersonalDataPostgres {
+    // Read in one transaction so the export is a consistent snapshot.
+    async fn collect(&self, user_id: Uuid) -> AppResult<PersonalDataEntity> {
+        let mut conn = Arc::clone(&self.db_pool).get()?;
+        let result = conn.transaction::<_, diesel::result::Error, _>(|conn| {
+            let user = users::table
+                .filter(users::id.eq(user_id))
+                .select(UserEntity::as_select())
+                .first::<UserEntity>(conn)?;
+            let profiles = profiles::table
+                .filter(profiles::owner_id.eq(user_id))
+                .order(profiles::created_at.asc())
+                .select(ProfileEntity::as_select())
+                .load::<ProfileEntity>(conn)?;
+            // Tokens are left out of the select, so they are never decrypted or exported.
+            let social_connections = social_connections::table
+                .filter(social_connections::user_id.eq(user_id))
+                .order(social_connections::created_at.asc())
+                .select((
+                    social_connections::id,
+                    social_connections::user_id,
+                    social_connections::platform,
+                    social_connections::platform_user_id,
+                    social_connections::expires_at,
+                    social_connections::created_at,
+                    social_connections::refresh_failures,
+                    social_connections::broken_at,
+                ))
+                .load::<SocialConnectionMetadata>(conn)?
+                .into_iter()
+                .map(|(id, user_id, platform, platform_user_id, expires_at, created_at, refresh_failures, broken_at)| {
+                    SocialConnectionEntity {
+                        id,
+                        user_id,
+                        platform,
+                        platform_user_id,
+                        access_token: String::new(),
+                        refresh_token: None,
+                        expires_at,
+                        created_at,
+                        refresh_failures,
+                        broken_at,
+                    }
+                })
+                .collect();
+            let generation_jobs = generation_jobs::table
+                .filter(generation_jobs::requester_id.eq(user_id))
+                .order(generation_jobs::created_at.asc())
+                .select(GenerationJobEntity::as_select())
+                .load::<GenerationJobEntity>(conn)?;
+            let sessions = sessions::table
+                .filter(sessions::user_id.eq(user_id))
+                .order(sessions::created_at.asc())
+                .select(SessionEntity::as_select())
+                .load::<SessionEntity>(conn)?;
+            let mfa_confirmed_at = user_mfa::table
+                .filter(user_mfa::user_id.eq(user_id))
+                .select(user_mfa::confirmed_at)
+                .first::<Option<NaiveDateTime>>(conn)
+                .optional()?
+                .flatten();
+
+            Ok(PersonalDataEntity {
+                user,
+                profiles,
+                social_connections,
+                generation_jobs,
+                sessions,
+                mfa_confirmed_at,
+            })
+        })?;
+        Ok(result)
+    }
+
+    // Rows are deleted child tables first rather than left to ON DELETE CASCADE, so the
+    // erasure stays complete even if a foreign key is ever changed. Audit events are kept
+    // for the record, but every one the user acted in or was the target of loses its IP
+    // address, user agent and changes through `redact_audit_events`, the one change the
+    // database allows to them.
+    async fn erase(&self, user_id: Uuid, due_before: NaiveDateTime) -> AppResult<bool> {
+        let mut conn = Arc::clone(&self.db_pool).get()?;
+        let result = conn.transaction::<_, diesel::result::Error, _>(|conn| {
+            // Locks the user row so a concurrent cancel either wins or waits for the erasure.
+            let scheduled = users::table
+                .filter(users::id.eq(user_id))
+                .filter(users::erasure_scheduled_at.le(due_before))
+                .select(users::id)
+                .for_update()
+                .first::<Uuid>(conn)
+                .optional()?;
+            if scheduled.is_none() {
+                return Ok(false);
+            }
+
+            delete(data_exports::table.filter(data_exports::user_id.eq(user_id))).execute(conn)?;
+            delete(password_reset_tokens::table.filter(password_reset_tokens::user_id.eq(user_id))).execute(conn)?;
+            delete(mfa_recovery_codes::table.filter(mfa_recovery_codes::user_id.eq(user_id))).execute(conn)?;
+            delete(used_mfa_tokens::table.filter(used_mfa_tokens::user_id.eq(user_id))).execute(conn)?;
+            delete(user_mfa::table.filter(user_mfa::user_id.eq(user_id))).execute(conn)?;
+            delete(sessions::table.filter(sessions::user_id.eq(user_id))).execute(conn)?;
+            delete(social_connections::table.filter(social_connections::user_id.eq(user_id))).execute(conn)?;
+            delete(generation_jobs::table.filter(generation_jobs::requester_id.eq(user_id))).execute(conn)?;
+            delete(profiles::table.filter(profiles::owner_id.eq(user_id))).execute(conn)?;
+            delete(users::table.filter(users::id.eq(user_id))).execute(conn)?;
+            diesel::select(redact_audit_events(user_id)).get_result::<i32>(conn)?;
+            Ok(true)
+        })?;
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeDelta, Utc};
+    use diesel::dsl::insert_into;
+    use serde_json::json;
+
+    use super::*;
+    use crate::{
+        domain::entities::{audit_event::InsertAuditEventEntity, user::RegisterUserEntity},
+        infrastructure::postgres::{postgres_connection::create_pool, schema::audit_events},
+    };
+
+    // Needs a database with every migration applied; skipped unless `TEST_DATABASE_URL` is set.
+    fn test_pool() -> Option<Arc<DbPool>> {
+        let database_url = std::env::var("TEST_DATABASE_URL").ok()?;
+        Some(Arc::new(create_pool(&database_url).unwrap()))
+    }
+
+    fn audit_event(action: &str, actor_id: Option<Uuid>, target_id: Option<Uuid>, changes: serde_json::Value) -> InsertAuditEventEntity {
+        InsertAuditEventEntity {
+            action: action.to_string(),
+            actor_id,
+            target_type: target_id.map(|_| "user".to_string()),
+            target_id,
+            ip_address: Some("203.0.113.7".to_string()),
+            user_agent: Some("Mozilla/5.0 (alice's laptop)".to_string()),
+            request_id: Some(Uuid::new_v4().to_string()),
+            changes,
+            created_at: Utc::now().naive_utc(),
+        }
+    }
+
+    #[tokio::test]
+    async fn erasure_leaves_nothing_identifying_in_the_audit_log() {
+        let Some(db_pool) = test_pool() else {
+            eprintln!("TEST_DATABASE_URL is not set, skipping");
+            return;
+        };
+        let mut conn = Arc::clone(&db_pool).get().unwrap();
+        let username = format!("erase-{}", Uuid::new_v4().simple());
+        let email = format!("{}@example.com", username);
+        let now = Utc::now().naive_utc();
+
+        let user_id = insert_into(users::table)
+            .values(RegisterUserEntity {
+                username: username.clone(),
+                password_hash: "hash".to_string(),
+                email: email.clone(),
+                first_name: "Alice".to_string(),
+                last_name: "Erasable".to_string(),
+                created_at: now,
+                updated_at: now,
+            })
+            .returning(users::id)
+            .get_result::<Uuid>(&mut conn)
+            .unwrap();
+        diesel::update(users::table.filter(users::id.eq(user_id)))
+            .set(users::erasure_scheduled_at.eq(Some(now - TimeDelta::minutes(1))))
+            .execute(&mut conn)
+            .unwrap();
+        let admin_id = Uuid::new_v4();
+        let other_user_id = Uuid::new_v4();
+        insert_into(audit_events::table)
+            .values(vec![
+                audit_event("user.register", Some(user_id), Some(user_id), json!({ "username": username, "email": email })),
+                audit_event("auth.login_failed", None, Some(user_id), json!({ "username": username, "reason": "invalid_password" })),
+                audit_event("admin.user.update", Some(admin_id), Some(user_id), json!({ "email": { "from": email, "to": null } })),
+                audit_event("profile.update", Some(user_id), None, json!({ "display_name": { "from": null, "to": "Alice" } })),
+                audit_event("user.register", Some(other_user_id), Some(other_user_id), json!({ "username": "someone-else" })),
+            ])
+            .execute(&mut conn)
+            .unwrap();
+
+        let erased = PersonalDataPostgres::new(Arc::clone(&db_pool))
+            .erase(user_id, now)
+            .await
+            .unwrap();
+        assert!(erased);
+
+        let rows = audit_events::table
+            .filter(
+                audit_events::actor_id
+                    .eq_any([user_id, other_user_id])
+                    .or(audit_events::target_id.eq(user_id)),
+            )
+            .select((
+                audit_events::actor_id,
+                audit_events::ip_address,
+                audit_events::user_agent,
+                audit_events::changes,
+            ))
+            .load::<(Option<Uuid>, Option<String>, Option<String>, serde_json::Value)>(&mut conn)
+            .unwrap();
+        assert_eq!(rows.len(), 5);
+        for (actor_id, ip_address, user_agent, changes) in rows {
+            if actor_id == Some(other_user_id) {
+                assert_eq!(ip_address.as_deref(), Some("203.0.113.7"));
+                assert_eq!(changes, json!({ "username": "someone-else" }));
+                continue;
+            }
+            assert_eq!(ip_address, None);
+            assert_eq!(user_agent, None);
+            assert_eq!(changes, json!({ "redacted": true }));
+        }
+        let mentions = audit_events::table
+            .filter(audit_events::changes.retrieve_as_text("username").eq(&username))
+            .count()
+            .get_result::<i64>(&mut conn)
+            .unwrap();
+        assert_eq!(mentions, 0);
+        let users_left = users::table
+            .filter(users::id.eq(user_id))
+            .count()
+            .get_result::<i64>(&mut conn)
+            .unwrap();
+        assert_eq!(users_left, 0);
+    }
+}
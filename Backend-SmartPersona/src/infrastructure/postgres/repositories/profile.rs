@@ -0,0 +1,100 @@
+use std::sync::Arc;
+
+use axum::async_trait;
+use diesel::{dsl::{delete, exists, insert_into, select, update}, prelude::*};
+use uuid::Uuid;
+
+use crate::{
+    domain::{
+        error::AppResult,
+        entities::profile::{EditProfileEntity, InsertProfileEntity, ProfileEntity},
+        repo::profile::ProfileRepository,
+    },
+    infrastructure::postgres::{postgres_connection::DbPool, schema::profiles},
+};
+
+pub struct ProfilePostgres {
+    db_pool: Arc<DbPool>,
+}
+
+impl ProfilePostgres {
+    pub fn new(db_pool: Arc<DbPool>) -> Self {
+        Self { db_pool }
+    }
+}
+
+#[async_trait]
+impl ProfileRepository for ProfilePostgres {
+    async fn create(&self, insert_profile_entity: InsertProfileEntity) -> AppResult<ProfileEntity> {
+        let mut conn = Arc::clone(&self.db_pool).get()?;
+        let result = insert_into(profiles::table)
+            .values(insert_profile_entity)
+            .returning(ProfileEntity::as_returning())
+            .get_result::<ProfileEntity>(&mut conn)?;
+        Ok(result)
+    }
+
+    async fn find_by_id(&self, profile_id: Uuid) -> AppResult<ProfileEntity> {
+        let mut conn = Arc::clone(&self.db_pool).get()?;
+        let result = profiles::table
+            .filter(profiles::id.eq(profile_id))
+            .select(ProfileEntity::as_select())
+            .first::<ProfileEntity>(&mut conn)?;
+        Ok(result)
+    }
+
+    async fn find_by_owner(&self, owner_id: Uuid) -> AppResult<Vec<ProfileEntity>> {
+        let mut conn = Arc::clone(&self.db_pool).get()?;
+        let result = profiles::table
+            .filter(profiles::owner_id.eq(owner_id))
+            .order(profiles::created_at.desc())
+            .select(ProfileEntity::as_select())
+            .load::<ProfileEntity>(&mut conn)?;
+        Ok(result)
+    }
+
+    async fn update(&self, profile_id: Uuid, edit_profile_entity: EditProfileEntity) -> AppResult<ProfileEntity> {
+        let mut conn = Arc::clone(&self.db_pool).get()?;
+        let result = update(profiles::table.filter(profiles::id.eq(profile_id)))
+            .set(edit_profile_entity)
+            .returning(ProfileEntity::as_returning())
+            .get_result::<ProfileEntity>(&mut conn)?;
+        Ok(result)
+    }
+
+    async fn delete(&self, profile_id: Uuid) -> AppResult<()> {
+        let mut conn = Arc::clone(&self.db_pool).get()?;
+        delete(profiles::table.filter(profiles::id.eq(profile_id))).execute(&mut conn)?;
+        Ok(())
+    }
+
+    async fn find_by_slug(&self, slug: String) -> AppResult<ProfileEntity> {
+        let mut conn = Arc::clone(&self.db_pool).get()?;
+        let result = profiles::table
+            .filter(profiles::shareable_link_slug.eq(slug))
+            .select(ProfileEntity::as_select())
+            .first::<ProfileEntity>(&mut conn)?;
+        Ok(result)
+    }
+
+    async fn slug_exists(&self, slug: String) -> AppResult<bool> {
+        let mut conn = Arc::clone(&self.db_pool).get()?;
+        let result = select(exists(
+            profiles::table.filter(profiles::shareable_link_slug.eq(slug)),
+        ))
+        .get_result::<bool>(&mut conn)?;
+        Ok(result)
+    }
+
+    async fn set_slug(&self, profile_id: Uuid, slug: Option<String>) -> AppResult<ProfileEntity> {
+        let mut conn = Arc::clone(&self.db_pool).get()?;
+        let result = update(profiles::table.filter(profiles::id.eq(profile_id)))
+            .set((
+                profiles::shareable_link_slug.eq(slug),
+                profiles::updated_at.eq(chrono::Utc::now().naive_utc()),
+            ))
+            .returning(ProfileEntity::as_returning())
+            .get_result::<ProfileEntity>(&mut conn)?;
+        Ok(result)
+    }
+}
@@ -0,0 +1,222 @@
+use std::sync::Arc;
+
+use axum::async_trait;
+use diesel::{dsl::{insert_into, update}, prelude::*};
+use uuid::Uuid;
+
+use crate::{
+    domain::{
+        error::{AppError, AppResult},
+        entities::prompt_template::{
+            EditPromptTemplateEntity, InsertPromptTemplateEntity, InsertPromptTemplateVersionEntity,
+            PromptTemplateEntity, PromptTemplateVersionEntity,
+        },
+        repo::prompt_template::PromptTemplateRepository,
+    },
+    infrastructure::postgres::{
+        postgres_connection::DbPool,
+        schema::{prompt_template_versions, prompt_templates},
+    },
+};
+
+pub struct PromptTemplatePostgres {
+    db_pool: Arc<DbPool>,
+}
+
+impl PromptTemplatePostgres {
+    pub fn new(db_pool: Arc<DbPool>) -> Self {
+        Self { db_pool }
+    }
+}
+
+#[async_trait]
+impl PromptTemplateRepository for PromptTemplatePostgres {
+    async fn create(
+        &self,
+        insert_prompt_template_entity: InsertPromptTemplateEntity,
+        template_text: String,
+    ) -> AppResult<PromptTemplateEntity> {
+        let mut conn = Arc::clone(&self.db_pool).get()?;
+        let result = conn.transaction::<_, diesel::result::Error, _>(|conn| {
+            let created_at = insert_prompt_template_entity.created_at;
+            let template = insert_into(prompt_templates::table)
+                .values(insert_prompt_template_entity)
+                .returning(PromptTemplateEntity::as_returning())
+                .get_result::<PromptTemplateEntity>(conn)?;
+
+            let version_id = insert_into(prompt_template_versions::table)
+                .values(InsertPromptTemplateVersionEntity {
+                    template_id: template.id,
+                    version: 1,
+                    template_text,
+                    created_at,
+                })
+                .returning(prompt_template_versions::id)
+                .get_result::<Uuid>(conn)?;
+
+            update(prompt_templates::table.filter(prompt_templates::id.eq(template.id)))
+                .set(prompt_templates::active_version_id.eq(Some(version_id)))
+                .returning(PromptTemplateEntity::as_returning())
+                .get_result::<PromptTemplateEntity>(conn)
+        })?;
+        Ok(result)
+    }
+
+    async fn find_all(&self) -> AppResult<Vec<PromptTemplateEntity>> {
+        let mut conn = Arc::clone(&self.db_pool).get()?;
+        let result = prompt_templates::table
+            .filter(prompt_templates::archived_at.is_null())
+            .order(prompt_templates::name.asc())
+            .select(PromptTemplateEntity::as_select())
+            .load::<PromptTemplateEntity>(&mut conn)?;
+        Ok(result)
+    }
+
+    async fn find_by_id(&self, template_id: Uuid) -> AppResult<PromptTemplateEntity> {
+        let mut conn = Arc::clone(&self.db_pool).get()?;
+        let result = prompt_templates::table
+            .filter(prompt_templates::id.eq(template_id))
+            .filter(prompt_templates::archived_at.is_null())
+            .select(PromptTemplateEntity::as_select())
+            .first::<PromptTemplateEntity>(&mut conn)?;
+        Ok(result)
+    }
+
+    async fn find_by_name(&self, name: String) -> AppResult<PromptTemplateEntity> {
+        let mut conn = Arc::clone(&self.db_pool).get()?;
+        let result = prompt_templates::table
+            .filter(prompt_templates::name.eq(name))
+            .filter(prompt_templates::archived_at.is_null())
+            .select(PromptTemplateEntity::as_select())
+            .first::<PromptTemplateEntity>(&mut conn)?;
+        Ok(result)
+    }
+
+    async fn update(
+        &self,
+        template_id: Uuid,
+        edit_prompt_template_entity: EditPromptTemplateEntity,
+        template_text: Option<String>,
+    ) -> AppResult<PromptTemplateEntity> {
+        let mut conn = Arc::clone(&self.db_pool).get()?;
+        let result = conn.transaction::<_, diesel::result::Error, _>(|conn| {
+            let template = update(
+                prompt_templates::table
+                    .filter(prompt_templates::id.eq(template_id))
+                    .filter(prompt_templates::archived_at.is_null()),
+            )
+            .set(edit_prompt_template_entity)
+            .returning(PromptTemplateEntity::as_returning())
+            .get_result::<PromptTemplateEntity>(conn)?;
+
+            match template_text {
+                Some(template_text) => {
+                    let version = insert_next_version(conn, template_id, template_text)?;
+                    Ok(PromptTemplateEntity {
+                        active_version_id: Some(version.id),
+                        ..template
+                    })
+                }
+                None => Ok(template),
+            }
+        })?;
+        Ok(result)
+    }
+
+    async fn archive(&self, template_id: Uuid) -> AppResult<()> {
+        let mut conn = Arc::clone(&self.db_pool).get()?;
+        let archived = update(
+            prompt_templates::table
+                .filter(prompt_templates::id.eq(template_id))
+                .filter(prompt_templates::archived_at.is_null()),
+        )
+        .set(prompt_templates::archived_at.eq(Some(chrono::Utc::now().naive_utc())))
+        .execute(&mut conn)?;
+
+        if archived == 0 {
+            return Err(AppError::not_found("Template not found"));
+        }
+        Ok(())
+    }
+
+    async fn find_versions(&self, template_id: Uuid) -> AppResult<Vec<PromptTemplateVersionEntity>> {
+        let mut conn = Arc::clone(&self.db_pool).get()?;
+        let result = prompt_template_versions::table
+            .filter(prompt_template_versions::template_id.eq(template_id))
+            .order(prompt_template_versions::version.desc())
+            .select(PromptTemplateVersionEntity::as_select())
+            .load::<PromptTemplateVersionEntity>(&mut conn)?;
+        Ok(result)
+    }
+
+    async fn find_version(&self, template_id: Uuid, version: i32) -> AppResult<PromptTemplateVersionEntity> {
+        let mut conn = Arc::clone(&self.db_pool).get()?;
+        let result = prompt_template_versions::table
+            .filter(prompt_template_versions::template_id.eq(template_id))
+            .filter(prompt_template_versions::version.eq(version))
+            .select(PromptTemplateVersionEntity::as_select())
+            .first::<PromptTemplateVersionEntity>(&mut conn)?;
+        Ok(result)
+    }
+
+    async fn find_version_by_id(&self, version_id: Uuid) -> AppResult<PromptTemplateVersionEntity> {
+        let mut conn = Arc::clone(&self.db_pool).get()?;
+        let result = prompt_template_versions::table
+            .filter(prompt_template_versions::id.eq(version_id))
+            .select(PromptTemplateVersionEntity::as_select())
+            .first::<PromptTemplateVersionEntity>(&mut conn)?;
+        Ok(result)
+    }
+
+    async fn set_active_version(&self, template_id: Uuid, version_id: Uuid) -> AppResult<PromptTemplateEntity> {
+        let mut conn = Arc::clone(&self.db_pool).get()?;
+        let result = update(
+            prompt_templates::table
+                .filter(prompt_templates::id.eq(template_id))
+                .filter(prompt_templates::archived_at.is_null()),
+        )
+        .set((
+            prompt_templates::active_version_id.eq(Some(version_id)),
+            prompt_templates::updated_at.eq(chrono::Utc::now().naive_utc()),
+        ))
+        .returning(PromptTemplateEntity::as_returning())
+        .get_result::<PromptTemplateEntity>(&mut conn)?;
+        Ok(result)
+    }
+}
+
+// Appends the next version number and marks it active. Must run inside a transaction.
+fn insert_next_version(
+    conn: &mut PgConnection,
+    template_id: Uuid,
+    template_text: String,
+) -> Result<PromptTemplateVersionEntity, diesel::result::Error> {
+    // Locking the template row serialises concurrent edits of the same template.
+    prompt_templates::table
+        .filter(prompt_templates::id.eq(template_id))
+        .filter(prompt_templates::archived_at.is_null())
+        .select(prompt_templates::id)
+        .for_update()
+        .first::<Uuid>(conn)?;
+
+    let latest_version = prompt_template_versions::table
+        .filter(prompt_template_versions::template_id.eq(template_id))
+        .select(diesel::dsl::max(prompt_template_versions::version))
+        .first::<Option<i32>>(conn)?;
+
+    let version = insert_into(prompt_template_versions::table)
+        .values(InsertPromptTemplateVersionEntity {
+            template_id,
+            version: latest_version.unwrap_or(0) + 1,
+            template_text,
+            created_at: chrono::Utc::now().naive_utc(),
+        })
+        .returning(PromptTemplateVersionEntity::as_returning())
+        .get_result::<PromptTemplateVersionEntity>(conn)?;
+
+    update(prompt_templates::table.filter(prompt_templates::id.eq(template_id)))
+        .set(prompt_templates::active_version_id.eq(Some(version.id)))
+        .execute(conn)?;
+
+    Ok(version)
+}
@@ -0,0 +1,99 @@
+use std::sync::Arc;
+
+use axum::async_trait;
+use chrono::{NaiveDateTime, Utc};
+use diesel::{dsl::{insert_into, update}, prelude::*};
+use uuid::Uuid;
+
+use crate::{
+    domain::{
+        entities::session::{InsertSessionEntity, SessionEntity},
+        error::AppResult,
+        repo::session::SessionRepository,
+    },
+    infrastructure::postgres::{postgres_connection::DbPool, schema::sessions},
+};
+
+pub struct SessionPostgres {
+    db_pool: Arc<DbPool>,
+}
+
+impl SessionPostgres {
+    pub fn new(db_pool: Arc<DbPool>) -> Self {
+        Self { db_pool }
+    }
+}
+
+#[async_trait]
+impl SessionRepository for SessionPostgres {
+    async fn create(&self, insert_session_entity: InsertSessionEntity) -> AppResult<SessionEntity> {
+        let mut conn = Arc::clone(&self.db_pool).get()?;
+        let result = insert_into(sessions::table)
+            .values(insert_session_entity)
+            .returning(SessionEntity::as_returning())
+            .get_result::<SessionEntity>(&mut conn)?;
+        Ok(result)
+    }
+
+    async fn find_by_id(&self, session_id: Uuid) -> AppResult<SessionEntity> {
+        let mut conn = Arc::clone(&self.db_pool).get()?;
+        let result = sessions::table
+            .filter(sessions::id.eq(session_id))
+            .select(SessionEntity::as_select())
+            .first::<SessionEntity>(&mut conn)?;
+        Ok(result)
+    }
+
+    async fn rotate_refresh_token(&self, session_id: Uuid, presented_token_id: Uuid, new_token_id: Uuid) -> AppResult<bool> {
+        let mut conn = Arc::clone(&self.db_pool).get()?;
+        let result = update(
+            sessions::table
+                .filter(sessions::id.eq(session_id))
+                .filter(sessions::current_refresh_token_id.eq(presented_token_id))
+                .filter(sessions::revoked_at.is_null()),
+        )
+        .set((
+            sessions::current_refresh_token_id.eq(new_token_id),
+            sessions::last_used_at.eq(Utc::now().naive_utc()),
+        ))
+        .execute(&mut conn)?;
+        Ok(result == 1)
+    }
+
+    async fn revoke(&self, session_id: Uuid) -> AppResult<()> {
+        let mut conn = Arc::clone(&self.db_pool).get()?;
+        update(
+            sessions::table
+                .filter(sessions::id.eq(session_id))
+                .filter(sessions::revoked_at.is_null()),
+        )
+        .set(sessions::revoked_at.eq(Some(Utc::now().naive_utc())))
+        .execute(&mut conn)?;
+        Ok(())
+    }
+
+    async fn revoke_all_for_user(&self, user_id: Uuid) -> AppResult<usize> {
+        let mut conn = Arc::clone(&self.db_pool).get()?;
+        let result = update(
+            sessions::table
+                .filter(sessions::user_id.eq(user_id))
+                .filter(sessions::revoked_at.is_null()),
+        )
+        .set(sessions::revoked_at.eq(Some::<NaiveDateTime>(Utc::now().naive_utc())))
+        .execute(&mut conn)?;
+        Ok(result)
+    }
+
+    async fn revoke_others_for_user(&self, user_id: Uuid, keep_session_id: Uuid) -> AppResult<usize> {
+        let mut conn = Arc::clone(&self.db_pool).get()?;
+        let result = update(
+            sessions::table
+                .filter(sessions::user_id.eq(user_id))
+                .filter(sessions::id.ne(keep_session_id))
+                .filter(sessions::revoked_at.is_null()),
+        )
+        .set(sessions::revoked_at.eq(Some::<NaiveDateTime>(Utc::now().naive_utc())))
+        .execute(&mut conn)?;
+        Ok(result)
+    }
+}
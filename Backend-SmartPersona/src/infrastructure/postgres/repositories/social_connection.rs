@@ -0,0 +1,290 @@
+use std::sync::Arc;
+
+use axum::async_trait;
+use chrono::{NaiveDateTime, Utc};
+use diesel::{dsl::{delete, insert_into, update}, prelude::*, upsert::excluded};
+use uuid::Uuid;
+
+use crate::{
+    domain::{
+        error::{AppError, AppResult},
+        entities::social_connection::{InsertSocialConnectionEntity, SocialConnectionEntity},
+        repo::social_connection::SocialConnectionRepository,
+    },
+    infrastructure::{
+        postgres::{postgres_connection::DbPool, schema::social_connections},
+        token_encryption::{Envelope, EnvelopeCipher},
+    },
+};
+
+#[derive(Debug, Clone, Queryable, Selectable)]
+#[diesel(table_name = social_connections)]
+struct SocialConnectionRow {
+    id: Uuid,
+    user_id: Uuid,
+    platform: String,
+    platform_user_id: String,
+    access_token: String,
+    refresh_token: Option<String>,
+    expires_at: Option<NaiveDateTime>,
+    created_at: NaiveDateTime,
+    encryption_key_id: Option<String>,
+    wrapped_data_key: Option<String>,
+    refresh_failures: i32,
+    broken_at: Option<NaiveDateTime>,
+}
+
+#[derive(Debug, Clone, Insertable)]
+#[diesel(table_name = social_connections)]
+struct InsertSocialConnectionRow {
+    user_id: Uuid,
+    platform: String,
+    platform_user_id: String,
+    access_token: String,
+    refresh_token: Option<String>,
+    expires_at: Option<NaiveDateTime>,
+    created_at: NaiveDateTime,
+    encryption_key_id: Option<String>,
+    wrapped_data_key: Option<String>,
+}
+
+pub struct SocialConnectionPostgres {
+    db_pool: Arc<DbPool>,
+    cipher: Arc<EnvelopeCipher>,
+}
+
+impl SocialConnectionPostgres {
+    pub fn new(db_pool: Arc<DbPool>, cipher: Arc<EnvelopeCipher>) -> Self {
+        Self { db_pool, cipher }
+    }
+
+    fn encrypt(&self, entity: InsertSocialConnectionEntity) -> AppResult<InsertSocialConnectionRow> {
+        let (envelope, data_key) = self.cipher.new_envelope()?;
+        let aad = token_aad(entity.user_id, &entity.platform);
+
+        Ok(InsertSocialConnectionRow {
+            access_token: data_key.encrypt(&aad, &entity.access_token)?,
+            refresh_token: entity
+                .refresh_token
+                .map(|refresh_token| data_key.encrypt(&aad, &refresh_token))
+                .transpose()?,
+            user_id: entity.user_id,
+            platform: entity.platform,
+            platform_user_id: entity.platform_user_id,
+            expires_at: entity.expires_at,
+            created_at: entity.created_at,
+            encryption_key_id: Some(envelope.key_id),
+            wrapped_data_key: Some(envelope.wrapped_data_key),
+        })
+    }
+
+    /// Encrypts the tokens of connections linked before encryption was introduced.
+    /// Returns how many rows were encrypted.
+    pub async fn encrypt_plaintext_tokens(&self) -> AppResult<usize> {
+        let mut conn = Arc::clone(&self.db_pool).get()?;
+        let rows = social_connections::table
+            .filter(social_connections::encryption_key_id.is_null())
+            .select(SocialConnectionRow::as_select())
+            .load::<SocialConnectionRow>(&mut conn)?;
+
+        let mut encrypted = 0;
+        for row in rows {
+            let (envelope, data_key) = self.cipher.new_envelope()?;
+            let aad = token_aad(row.user_id, &row.platform);
+            let access_token = data_key.encrypt(&aad, &row.access_token)?;
+            let refresh_token = row
+                .refresh_token
+                .map(|refresh_token| data_key.encrypt(&aad, &refresh_token))
+                .transpose()?;
+
+            // The null check skips rows that were relinked, and so encrypted, in the meantime.
+            encrypted += update(
+                social_connections::table
+                    .filter(social_connections::id.eq(row.id))
+                    .filter(social_connections::encryption_key_id.is_null()),
+            )
+            .set((
+                social_connections::access_token.eq(access_token),
+                social_connections::refresh_token.eq(refresh_token),
+                social_connections::encryption_key_id.eq(Some(envelope.key_id)),
+                social_connections::wrapped_data_key.eq(Some(envelope.wrapped_data_key)),
+            ))
+            .execute(&mut conn)?;
+        }
+        Ok(encrypted)
+    }
+
+    fn decrypt(&self, row: SocialConnectionRow) -> AppResult<SocialConnectionEntity> {
+        let (access_token, refresh_token) = match (row.encryption_key_id, row.wrapped_data_key) {
+            (Some(key_id), Some(wrapped_data_key)) => {
+                let data_key = self.cipher.open_envelope(&Envelope { key_id, wrapped_data_key })?;
+                let aad = token_aad(row.user_id, &row.platform);
+                (
+                    data_key.decrypt(&aad, &row.access_token)?,
+                    row.refresh_token
+                        .map(|refresh_token| data_key.decrypt(&aad, &refresh_token))
+                        .transpose()?,
+                )
+            }
+            // `encrypt_plaintext_tokens` runs at startup, so a plaintext row means the backfill
+            // failed or the row was written behind the repository's back.
+            _ => {
+                return Err(AppError::Internal(anyhow::anyhow!(
+                    "Social connection {} holds unencrypted tokens",
+                    row.id
+                )))
+            }
+        };
+
+        Ok(SocialConnectionEntity {
+            id: row.id,
+            user_id: row.user_id,
+            platform: row.platform,
+            platform_user_id: row.platform_user_id,
+            access_token,
+            refresh_token,
+            expires_at: row.expires_at,
+            created_at: row.created_at,
+            refresh_failures: row.refresh_failures,
+            broken_at: row.broken_at,
+        })
+    }
+}
+
+#[async_trait]
+impl SocialConnectionRepository for SocialConnectionPostgres {
+    async fn upsert(&self, insert_social_connection_entity: InsertSocialConnectionEntity) -> AppResult<SocialConnectionEntity> {
+        let row = self.encrypt(insert_social_connection_entity)?;
+
+        let mut conn = Arc::clone(&self.db_pool).get()?;
+        let result = insert_into(social_connections::table)
+            .values(row)
+            .on_conflict((social_connections::user_id, social_connections::platform))
+            .do_update()
+            .set((
+                social_connections::platform_user_id.eq(excluded(social_connections::platform_user_id)),
+                social_connections::access_token.eq(excluded(social_connections::access_token)),
+                social_connections::refresh_token.eq(excluded(social_connections::refresh_token)),
+                social_connections::expires_at.eq(excluded(social_connections::expires_at)),
+                social_connections::encryption_key_id.eq(excluded(social_connections::encryption_key_id)),
+                social_connections::wrapped_data_key.eq(excluded(social_connections::wrapped_data_key)),
+                // Relinking repairs a connection that broke while refreshing.
+                social_connections::refresh_failures.eq(0),
+                social_connections::last_refresh_attempt_at.eq(None::<NaiveDateTime>),
+                social_connections::broken_at.eq(None::<NaiveDateTime>),
+            ))
+            .returning(SocialConnectionRow::as_returning())
+            .get_result::<SocialConnectionRow>(&mut conn)?;
+
+        self.decrypt(result)
+    }
+
+    async fn find_by_user(&self, user_id: Uuid) -> AppResult<Vec<SocialConnectionEntity>> {
+        let mut conn = Arc::clone(&self.db_pool).get()?;
+        let result = social_connections::table
+            .filter(social_connections::user_id.eq(user_id))
+            .order(social_connections::platform.asc())
+            .select(SocialConnectionRow::as_select())
+            .load::<SocialConnectionRow>(&mut conn)?;
+
+        result.into_iter().map(|row| self.decrypt(row)).collect()
+    }
+
+    async fn find_by_user_and_platform(&self, user_id: Uuid, platform: String) -> AppResult<SocialConnectionEntity> {
+        let mut conn = Arc::clone(&self.db_pool).get()?;
+        let result = social_connections::table
+            .filter(social_connections::user_id.eq(user_id))
+            .filter(social_connections::platform.eq(platform))
+            .select(SocialConnectionRow::as_select())
+            .first::<SocialConnectionRow>(&mut conn)?;
+
+        self.decrypt(result)
+    }
+
+    async fn delete_by_user_and_platform(&self, user_id: Uuid, platform: String) -> AppResult<usize> {
+        let mut conn = Arc::clone(&self.db_pool).get()?;
+        let result = delete(
+            social_connections::table
+                .filter(social_connections::user_id.eq(user_id))
+                .filter(social_connections::platform.eq(platform)),
+        )
+        .execute(&mut conn)?;
+        Ok(result)
+    }
+
+    async fn claim_due_for_refresh(&self, expires_before: NaiveDateTime, retry_before: NaiveDateTime, limit: i64) -> AppResult<Vec<SocialConnectionEntity>> {
+        let mut conn = Arc::clone(&self.db_pool).get()?;
+        let rows = conn.transaction::<_, diesel::result::Error, _>(|conn| {
+            let ids = social_connections::table
+                .filter(social_connections::broken_at.is_null())
+                .filter(social_connections::refresh_token.is_not_null())
+                .filter(social_connections::expires_at.le(expires_before))
+                .filter(
+                    social_connections::last_refresh_attempt_at
+                        .is_null()
+                        .or(social_connections::last_refresh_attempt_at.lt(retry_before)),
+                )
+                .order(social_connections::expires_at.asc())
+                .limit(limit)
+                .select(social_connections::id)
+                .for_update()
+                .skip_locked()
+                .load::<Uuid>(conn)?;
+
+            update(social_connections::table.filter(social_connections::id.eq_any(ids)))
+                .set(social_connections::last_refresh_attempt_at.eq(Utc::now().naive_utc()))
+                .returning(SocialConnectionRow::as_returning())
+                .get_results::<SocialConnectionRow>(conn)
+        })?;
+
+        rows.into_iter().map(|row| self.decrypt(row)).collect()
+    }
+
+    async fn update_tokens(&self, social_connection_entity: SocialConnectionEntity) -> AppResult<SocialConnectionEntity> {
+        let row = self.encrypt(InsertSocialConnectionEntity {
+            user_id: social_connection_entity.user_id,
+            platform: social_connection_entity.platform,
+            platform_user_id: social_connection_entity.platform_user_id,
+            access_token: social_connection_entity.access_token,
+            refresh_token: social_connection_entity.refresh_token,
+            expires_at: social_connection_entity.expires_at,
+            created_at: social_connection_entity.created_at,
+        })?;
+
+        let mut conn = Arc::clone(&self.db_pool).get()?;
+        let result = update(social_connections::table.filter(social_connections::id.eq(social_connection_entity.id)))
+            .set((
+                social_connections::access_token.eq(row.access_token),
+                social_connections::refresh_token.eq(row.refresh_token),
+                social_connections::expires_at.eq(row.expires_at),
+                social_connections::encryption_key_id.eq(row.encryption_key_id),
+                social_connections::wrapped_data_key.eq(row.wrapped_data_key),
+                social_connections::refresh_failures.eq(0),
+            ))
+            .returning(SocialConnectionRow::as_returning())
+            .get_result::<SocialConnectionRow>(&mut conn)?;
+
+        self.decrypt(result)
+    }
+
+    async fn record_refresh_failure(&self, connection_id: Uuid) -> AppResult<i32> {
+        let mut conn = Arc::clone(&self.db_pool).get()?;
+        let result = update(social_connections::table.filter(social_connections::id.eq(connection_id)))
+            .set(social_connections::refresh_failures.eq(social_connections::refresh_failures + 1))
+            .returning(social_connections::refresh_failures)
+            .get_result::<i32>(&mut conn)?;
+        Ok(result)
+    }
+
+    async fn mark_broken(&self, connection_id: Uuid) -> AppResult<()> {
+        let mut conn = Arc::clone(&self.db_pool).get()?;
+        update(social_connections::table.filter(social_connections::id.eq(connection_id)))
+            .set(social_connections::broken_at.eq(Utc::now().naive_utc()))
+            .execute(&mut conn)?;
+        Ok(())
+    }
+}
+
+fn token_aad(user_id: Uuid, platform: &str) -> Vec<u8> {
+    format!("social_connections:{}:{}", user_id, platform).into_bytes()
+}
@@ -1,10 +1,13 @@
 use std::sync::Arc;
 use uuid::Uuid;
 use axum::async_trait;
-use anyhow::{Ok, Result};
-use diesel::{dsl::insert_into, prelude::*};
+use crate::domain::error::AppResult;
+use chrono::{NaiveDateTime, Utc};
+use diesel::{dsl::{insert_into, update}, pg::Pg, prelude::*};
 
-use crate::{domain::{entities::user::{RegisterUserEntity, UserEntity}, repo::user::UserRepository}, infrastructure::postgres::{postgres_connection::DbPool, schema::users}};
+use crate::{domain::{entities::user::{RegisterUserEntity, Role, UpdateUserEntity, UserEntity, UserSearchFilter, UserStatus}, repo::user::UserRepository}, infrastructure::postgres::{postgres_connection::DbPool, schema::users}};
+
+define_sql_function!(fn lower(x: diesel::sql_types::Text) -> diesel::sql_types::Text);
 
 pub struct UserPostgres{
     db_pool:Arc<DbPool>
@@ -16,7 +19,7 @@ impl UserPostgres {
 }
 #[async_trait]
 impl UserRepository for UserPostgres {
-        async fn register(&self,register_user_entity:RegisterUserEntity) -> Result<Uuid>{
+        async fn register(&self,register_user_entity:RegisterUserEntity) -> AppResult<Uuid>{
             let mut conn = Arc::clone(&self.db_pool).get()?;
             let result = insert_into(users::table)
                  .values(register_user_entity)
@@ -24,12 +27,143 @@ impl UserRepository for UserPostgres {
                  .get_result::<Uuid>(&mut conn)?;
                 Ok(result)
         }
-    async fn find_by_username(&self,username:String) -> Result<UserEntity>{
+    async fn find_by_username(&self,username:String) -> AppResult<UserEntity>{
+        let mut conn = Arc::clone(&self.db_pool).get()?;
+        let result = users::table
+        .filter(lower(users::username).eq(username.to_lowercase()))
+        .select(UserEntity::as_select())
+        .first::<UserEntity>(&mut conn)?;
+        Ok(result)
+    }
+    async fn find_by_id(&self,user_id:Uuid) -> AppResult<UserEntity>{
+        let mut conn = Arc::clone(&self.db_pool).get()?;
+        let result = users::table
+        .filter(users::id.eq(user_id))
+        .select(UserEntity::as_select())
+        .first::<UserEntity>(&mut conn)?;
+        Ok(result)
+    }
+    async fn find_by_email(&self,email:String) -> AppResult<UserEntity>{
         let mut conn = Arc::clone(&self.db_pool).get()?;
         let result = users::table
-        .filter(users::username.eq(username))
+        .filter(users::email.eq(email))
         .select(UserEntity::as_select())
         .first::<UserEntity>(&mut conn)?;
         Ok(result)
     }
+    async fn record_failed_login(&self,user_id:Uuid) -> AppResult<i32>{
+        let mut conn = Arc::clone(&self.db_pool).get()?;
+        let result = update(users::table.filter(users::id.eq(user_id)))
+        .set(users::failed_login_attempts.eq(users::failed_login_attempts + 1))
+        .returning(users::failed_login_attempts)
+        .get_result::<i32>(&mut conn)?;
+        Ok(result)
+    }
+    async fn delay_login(&self,user_id:Uuid,until:NaiveDateTime) -> AppResult<()>{
+        let mut conn = Arc::clone(&self.db_pool).get()?;
+        update(users::table.filter(users::id.eq(user_id)))
+        .set(users::locked_until.eq(Some(until)))
+        .execute(&mut conn)?;
+        Ok(())
+    }
+    async fn clear_failed_logins(&self,user_id:Uuid) -> AppResult<()>{
+        let mut conn = Arc::clone(&self.db_pool).get()?;
+        update(users::table.filter(users::id.eq(user_id)))
+        .set((users::failed_login_attempts.eq(0), users::locked_until.eq(None::<NaiveDateTime>)))
+        .execute(&mut conn)?;
+        Ok(())
+    }
+    async fn update(&self,user_id:Uuid,update_user_entity:UpdateUserEntity) -> AppResult<UserEntity>{
+        let mut conn = Arc::clone(&self.db_pool).get()?;
+        let result = update(users::table.filter(users::id.eq(user_id)))
+        .set((update_user_entity, users::updated_at.eq(Utc::now().naive_utc())))
+        .returning(UserEntity::as_returning())
+        .get_result::<UserEntity>(&mut conn)?;
+        Ok(result)
+    }
+    async fn schedule_erasure(&self,user_id:Uuid,erasure_scheduled_at:Option<NaiveDateTime>) -> AppResult<()>{
+        let mut conn = Arc::clone(&self.db_pool).get()?;
+        update(users::table.filter(users::id.eq(user_id)))
+        .set((users::erasure_scheduled_at.eq(erasure_scheduled_at), users::updated_at.eq(Utc::now().naive_utc())))
+        .execute(&mut conn)?;
+        Ok(())
+    }
+    async fn find_due_for_erasure(&self,now:NaiveDateTime) -> AppResult<Vec<Uuid>>{
+        let mut conn = Arc::clone(&self.db_pool).get()?;
+        let result = users::table
+        .filter(users::erasure_scheduled_at.le(now))
+        .select(users::id)
+        .load::<Uuid>(&mut conn)?;
+        Ok(result)
+    }
+    async fn search(&self,filter:UserSearchFilter) -> AppResult<(Vec<UserEntity>,i64)>{
+        let mut conn = Arc::clone(&self.db_pool).get()?;
+        let total = filtered_users(&filter)
+        .count()
+        .get_result::<i64>(&mut conn)?;
+        let result = filtered_users(&filter)
+        .order((users::created_at.desc(), users::id.asc()))
+        .limit(filter.limit)
+        .offset(filter.offset)
+        .select(UserEntity::as_select())
+        .load::<UserEntity>(&mut conn)?;
+        Ok((result, total))
+    }
+    async fn update_role(&self,user_id:Uuid,role:Role) -> AppResult<UserEntity>{
+        let mut conn = Arc::clone(&self.db_pool).get()?;
+        let result = update(users::table.filter(users::id.eq(user_id)))
+        .set((users::role.eq(role), users::updated_at.eq(Utc::now().naive_utc())))
+        .returning(UserEntity::as_returning())
+        .get_result::<UserEntity>(&mut conn)?;
+        Ok(result)
+    }
+    async fn update_status(&self,user_id:Uuid,status:UserStatus) -> AppResult<UserEntity>{
+        let mut conn = Arc::clone(&self.db_pool).get()?;
+        let result = update(users::table.filter(users::id.eq(user_id)))
+        .set((users::status.eq(status), users::updated_at.eq(Utc::now().naive_utc())))
+        .returning(UserEntity::as_returning())
+        .get_result::<UserEntity>(&mut conn)?;
+        Ok(result)
+    }
+    async fn update_password(&self,user_id:Uuid,password_hash:String) -> AppResult<()>{
+        let mut conn = Arc::clone(&self.db_pool).get()?;
+        update(users::table.filter(users::id.eq(user_id)))
+        .set((users::password_hash.eq(password_hash), users::updated_at.eq(Utc::now().naive_utc())))
+        .execute(&mut conn)?;
+        Ok(())
+    }
+    async fn verify_email(&self,user_id:Uuid) -> AppResult<bool>{
+        let mut conn = Arc::clone(&self.db_pool).get()?;
+        let updated = update(users::table.filter(users::id.eq(user_id)).filter(users::status.eq(UserStatus::Pending)))
+        .set((users::status.eq(UserStatus::Active), users::updated_at.eq(Utc::now().naive_utc())))
+        .execute(&mut conn)?;
+        Ok(updated > 0)
+    }
+}
+
+// Shared by the page query and its count so both apply the same filters.
+fn filtered_users(filter:&UserSearchFilter) -> users::BoxedQuery<'static, Pg>{
+    let mut query = users::table.into_boxed();
+    if let Some(search) = filter.query.as_deref().map(str::trim).filter(|search| !search.is_empty()) {
+        let pattern = format!("%{}%", search.replace('\\', "\\\\").replace('%', "\\%").replace('_', "\\_"));
+        query = query.filter(
+            users::username.ilike(pattern.clone())
+            .or(users::email.ilike(pattern.clone()))
+            .or(users::first_name.ilike(pattern.clone()))
+            .or(users::last_name.ilike(pattern))
+        );
+    }
+    if let Some(role) = filter.role {
+        query = query.filter(users::role.eq(role));
+    }
+    if let Some(status) = filter.status {
+        query = query.filter(users::status.eq(status));
+    }
+    if let Some(created_from) = filter.created_from {
+        query = query.filter(users::created_at.ge(created_from));
+    }
+    if let Some(created_to) = filter.created_to {
+        query = query.filter(users::created_at.lt(created_to));
+    }
+    query
 }
\ No newline at end of file
@@ -1,11 +1,11 @@
 // @generated automatically by Diesel CLI.
 
 pub mod sql_types {
-    #[derive(diesel::query_builder::QueryId, Clone, diesel::sql_types::SqlType)]
+    #[derive(diesel::query_builder::QueryId, diesel::sql_types::SqlType)]
     #[diesel(postgres_type(name = "job_status"))]
     pub struct JobStatus;
 
-    #[derive(diesel::query_builder::QueryId, Clone, diesel::sql_types::SqlType)]
+    #[derive(diesel::query_builder::QueryId, diesel::sql_types::SqlType)]
     #[diesel(postgres_type(name = "profile_status"))]
     pub struct ProfileStatus;
 
@@ -13,11 +13,47 @@ pub mod sql_types {
     #[diesel(postgres_type(name = "user_role"))]
     pub struct UserRole;
 
-    #[derive(diesel::query_builder::QueryId, Clone, diesel::sql_types::SqlType)]
+    #[derive(diesel::query_builder::QueryId, diesel::sql_types::SqlType)]
     #[diesel(postgres_type(name = "user_status"))]
     pub struct UserStatus;
 }
 
+diesel::table! {
+    audit_events (id) {
+        id -> Uuid,
+        #[max_length = 64]
+        action -> Varchar,
+        actor_id -> Nullable<Uuid>,
+        #[max_length = 32]
+        target_type -> Nullable<Varchar>,
+        target_id -> Nullable<Uuid>,
+        #[max_length = 64]
+        ip_address -> Nullable<Varchar>,
+        user_agent -> Nullable<Text>,
+        #[max_length = 128]
+        request_id -> Nullable<Varchar>,
+        changes -> Jsonb,
+        created_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+    use super::sql_types::JobStatus;
+
+    data_exports (id) {
+        id -> Uuid,
+        user_id -> Uuid,
+        status -> JobStatus,
+        data -> Nullable<Jsonb>,
+        error -> Nullable<Text>,
+        created_at -> Timestamptz,
+        started_at -> Nullable<Timestamptz>,
+        completed_at -> Nullable<Timestamptz>,
+        expires_at -> Nullable<Timestamptz>,
+    }
+}
+
 diesel::table! {
     use diesel::sql_types::*;
     use super::sql_types::JobStatus;
@@ -30,6 +66,35 @@ diesel::table! {
         result -> Nullable<Jsonb>,
         created_at -> Timestamptz,
         completed_at -> Nullable<Timestamptz>,
+        payload -> Jsonb,
+        error -> Nullable<Text>,
+        attempts -> Int4,
+        started_at -> Nullable<Timestamptz>,
+        updated_at -> Timestamptz,
+        prompt_template_version_id -> Nullable<Uuid>,
+        heartbeat_at -> Nullable<Timestamptz>,
+    }
+}
+
+diesel::table! {
+    mfa_recovery_codes (id) {
+        id -> Uuid,
+        user_id -> Uuid,
+        code_hash -> Text,
+        used_at -> Nullable<Timestamptz>,
+        created_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    password_reset_tokens (id) {
+        id -> Uuid,
+        user_id -> Uuid,
+        #[max_length = 64]
+        token_hash -> Varchar,
+        expires_at -> Timestamptz,
+        used_at -> Nullable<Timestamptz>,
+        created_at -> Timestamptz,
     }
 }
 
@@ -50,14 +115,39 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    prompt_template_versions (id) {
+        id -> Uuid,
+        template_id -> Uuid,
+        version -> Int4,
+        template_text -> Text,
+        created_at -> Timestamptz,
+    }
+}
+
 diesel::table! {
     prompt_templates (id) {
         id -> Uuid,
         #[max_length = 255]
         name -> Varchar,
-        template_text -> Text,
         created_at -> Timestamptz,
         updated_at -> Timestamptz,
+        active_version_id -> Nullable<Uuid>,
+        archived_at -> Nullable<Timestamptz>,
+    }
+}
+
+diesel::table! {
+    sessions (id) {
+        id -> Uuid,
+        user_id -> Uuid,
+        #[max_length = 255]
+        device -> Nullable<Varchar>,
+        created_at -> Timestamptz,
+        last_used_at -> Timestamptz,
+        expires_at -> Timestamptz,
+        revoked_at -> Nullable<Timestamptz>,
+        current_refresh_token_id -> Uuid,
     }
 }
 
@@ -73,6 +163,35 @@ diesel::table! {
         refresh_token -> Nullable<Text>,
         expires_at -> Nullable<Timestamptz>,
         created_at -> Timestamptz,
+        #[max_length = 64]
+        encryption_key_id -> Nullable<Varchar>,
+        wrapped_data_key -> Nullable<Text>,
+        refresh_failures -> Int4,
+        last_refresh_attempt_at -> Nullable<Timestamptz>,
+        broken_at -> Nullable<Timestamptz>,
+    }
+}
+
+diesel::table! {
+    used_mfa_tokens (jti) {
+        jti -> Uuid,
+        user_id -> Uuid,
+        expires_at -> Timestamptz,
+        used_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    user_mfa (user_id) {
+        user_id -> Uuid,
+        secret -> Text,
+        #[max_length = 64]
+        encryption_key_id -> Varchar,
+        wrapped_data_key -> Text,
+        confirmed_at -> Nullable<Timestamptz>,
+        last_used_step -> Nullable<Int8>,
+        created_at -> Timestamptz,
+        updated_at -> Timestamptz,
     }
 }
 
@@ -97,17 +216,38 @@ diesel::table! {
         first_name -> Varchar,
         #[max_length = 255]
         last_name -> Varchar,
+        failed_login_attempts -> Int4,
+        locked_until -> Nullable<Timestamptz>,
+        #[max_length = 255]
+        email -> Nullable<Varchar>,
+        erasure_scheduled_at -> Nullable<Timestamptz>,
     }
 }
 
+diesel::joinable!(data_exports -> users (user_id));
+diesel::joinable!(generation_jobs -> prompt_template_versions (prompt_template_version_id));
 diesel::joinable!(generation_jobs -> users (requester_id));
+diesel::joinable!(mfa_recovery_codes -> users (user_id));
+diesel::joinable!(password_reset_tokens -> users (user_id));
 diesel::joinable!(profiles -> users (owner_id));
+diesel::joinable!(prompt_template_versions -> prompt_templates (template_id));
+diesel::joinable!(sessions -> users (user_id));
 diesel::joinable!(social_connections -> users (user_id));
+diesel::joinable!(used_mfa_tokens -> users (user_id));
+diesel::joinable!(user_mfa -> users (user_id));
 
 diesel::allow_tables_to_appear_in_same_query!(
+    audit_events,
+    data_exports,
     generation_jobs,
+    mfa_recovery_codes,
+    password_reset_tokens,
     profiles,
+    prompt_template_versions,
     prompt_templates,
+    sessions,
     social_connections,
+    used_mfa_tokens,
+    user_mfa,
     users,
 );
@@ -0,0 +1,62 @@
+use axum::async_trait;
+use reqwest::Client;
+use serde::Deserialize;
+
+use crate::domain::{
+    error::{AppError, AppResult},entities::social_connection::SocialConnectionEntity, repo::social_post_source::SocialPostSource};
+
+pub struct FacebookPostSource {
+    client: Client,
+    base_url: String,
+}
+
+impl FacebookPostSource {
+    pub fn new(base_url: String) -> Self {
+        Self {
+            client: Client::new(),
+            base_url,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct FeedResponse {
+    data: Vec<FeedPost>,
+}
+
+#[derive(Deserialize)]
+struct FeedPost {
+    message: Option<String>,
+}
+
+#[async_trait]
+impl SocialPostSource for FacebookPostSource {
+    fn platform(&self) -> &str {
+        "facebook"
+    }
+
+    async fn fetch_recent_posts(&self, connection: &SocialConnectionEntity, limit: usize) -> AppResult<Vec<String>> {
+        let url = format!("{}/me/posts", self.base_url);
+
+        let response = self.client
+            .get(&url)
+            .bearer_auth(&connection.access_token)
+            .query(&[("fields", "message".to_string()), ("limit", limit.to_string())])
+            .send()
+            .await
+            .map_err(|e| AppError::upstream(format!("Failed to send request to Facebook: {}", e)))?;
+
+        if !response.status().is_success() {
+            let error_body = response.text().await.map_err(|e| AppError::upstream(format!("Failed to read Facebook error body: {}", e)))?;
+            return Err(AppError::upstream(format!("Facebook returned an error: {}", error_body)));
+        }
+
+        let feed = response
+            .json::<FeedResponse>()
+            .await
+            .map_err(|e| AppError::upstream(format!("Failed to deserialize Facebook feed: {}", e)))?;
+
+        // Photo or share posts without text carry no `message`.
+        Ok(feed.data.into_iter().filter_map(|post| post.message).collect())
+    }
+}
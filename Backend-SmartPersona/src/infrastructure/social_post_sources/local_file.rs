@@ -0,0 +1,65 @@
+use std::path::PathBuf;
+
+use axum::async_trait;
+use tracing::warn;
+
+use crate::domain::{
+    error::{AppError, AppResult},entities::social_connection::SocialConnectionEntity, repo::social_post_source::SocialPostSource};
+
+/// Reads posts from `<dir>/<platform>/<platform_user_id>.json`, a JSON array of strings.
+/// Stands in for a real platform in local development and tests.
+pub struct LocalFilePostSource {
+    dir: PathBuf,
+    platform: String,
+}
+
+impl LocalFilePostSource {
+    pub fn new(dir: PathBuf, platform: String) -> Self {
+        Self { dir, platform }
+    }
+
+    // `platform_user_id` comes from the linked account, so it must not be able to name a
+    // file outside `dir`.
+    async fn posts_path(&self, platform_user_id: &str) -> Option<PathBuf> {
+        let is_safe_id = !platform_user_id.is_empty()
+            && platform_user_id
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+        if !is_safe_id {
+            return None;
+        }
+
+        let dir = tokio::fs::canonicalize(&self.dir).await.ok()?;
+        let path = tokio::fs::canonicalize(dir.join(&self.platform).join(format!("{}.json", platform_user_id)))
+            .await
+            .ok()?;
+        path.starts_with(&dir).then_some(path)
+    }
+}
+
+#[async_trait]
+impl SocialPostSource for LocalFilePostSource {
+    fn platform(&self) -> &str {
+        &self.platform
+    }
+
+    async fn fetch_recent_posts(&self, connection: &SocialConnectionEntity, limit: usize) -> AppResult<Vec<String>> {
+        let unavailable = || AppError::upstream(format!("Failed to fetch {} posts", self.platform));
+
+        let Some(path) = self.posts_path(&connection.platform_user_id).await else {
+            warn!("no local posts file for {} connection {}", self.platform, connection.id);
+            return Err(unavailable());
+        };
+
+        let content = tokio::fs::read_to_string(&path).await.map_err(|e| {
+            warn!("failed to read {}: {}", path.display(), e);
+            unavailable()
+        })?;
+        let posts = serde_json::from_str::<Vec<String>>(&content).map_err(|e| {
+            warn!("failed to parse {}: {}", path.display(), e);
+            unavailable()
+        })?;
+
+        Ok(posts.into_iter().take(limit).collect())
+    }
+}
@@ -0,0 +1,3 @@
+pub mod facebook;
+pub mod x;
+pub mod local_file;
@@ -0,0 +1,67 @@
+use axum::async_trait;
+use reqwest::Client;
+use serde::Deserialize;
+
+use crate::domain::{
+    error::{AppError, AppResult},entities::social_connection::SocialConnectionEntity, repo::social_post_source::SocialPostSource};
+
+// The timeline endpoint only accepts page sizes in this range.
+const MIN_RESULTS: usize = 5;
+const MAX_RESULTS: usize = 100;
+
+pub struct XPostSource {
+    client: Client,
+    base_url: String,
+}
+
+impl XPostSource {
+    pub fn new(base_url: String) -> Self {
+        Self {
+            client: Client::new(),
+            base_url,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct TimelineResponse {
+    #[serde(default)]
+    data: Vec<Tweet>,
+}
+
+#[derive(Deserialize)]
+struct Tweet {
+    text: String,
+}
+
+#[async_trait]
+impl SocialPostSource for XPostSource {
+    fn platform(&self) -> &str {
+        "x"
+    }
+
+    async fn fetch_recent_posts(&self, connection: &SocialConnectionEntity, limit: usize) -> AppResult<Vec<String>> {
+        let url = format!("{}/users/{}/tweets", self.base_url, connection.platform_user_id);
+        let max_results = limit.clamp(MIN_RESULTS, MAX_RESULTS);
+
+        let response = self.client
+            .get(&url)
+            .bearer_auth(&connection.access_token)
+            .query(&[("max_results", max_results.to_string()), ("exclude", "retweets".to_string())])
+            .send()
+            .await
+            .map_err(|e| AppError::upstream(format!("Failed to send request to X: {}", e)))?;
+
+        if !response.status().is_success() {
+            let error_body = response.text().await.map_err(|e| AppError::upstream(format!("Failed to read X error body: {}", e)))?;
+            return Err(AppError::upstream(format!("X returned an error: {}", error_body)));
+        }
+
+        let timeline = response
+            .json::<TimelineResponse>()
+            .await
+            .map_err(|e| AppError::upstream(format!("Failed to deserialize X timeline: {}", e)))?;
+
+        Ok(timeline.data.into_iter().take(limit).map(|tweet| tweet.text).collect())
+    }
+}
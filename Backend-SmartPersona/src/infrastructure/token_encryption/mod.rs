@@ -0,0 +1,198 @@
+use std::collections::HashMap;
+
+use aes_gcm::{
+    aead::{Aead, AeadCore, KeyInit, OsRng, Payload},
+    Aes256Gcm, Key, Nonce,
+};
+use anyhow::Result;
+use base64::{engine::general_purpose::STANDARD, Engine};
+
+use crate::config::config_model::TokenEncryption;
+
+const NONCE_LENGTH: usize = 12;
+
+/// Envelope encryption for third-party credentials. Each record gets a fresh data key that
+/// encrypts its tokens; the data key itself is wrapped by a master key from `Config`, whose
+/// id is stored next to the record so old master keys keep working after rotation.
+pub struct EnvelopeCipher {
+    master_keys: HashMap<String, Aes256Gcm>,
+    active_key_id: String,
+}
+
+/// The wrapped data key of one record and the id of the master key that wrapped it.
+#[derive(Debug, Clone)]
+pub struct Envelope {
+    pub key_id: String,
+    pub wrapped_data_key: String,
+}
+
+pub struct DataKey {
+    cipher: Aes256Gcm,
+}
+
+impl EnvelopeCipher {
+    pub fn new(config: &TokenEncryption) -> Result<Self> {
+        let mut master_keys = HashMap::new();
+        for (key_id, encoded_key) in &config.keys {
+            let key_bytes = STANDARD.decode(encoded_key)?;
+            if key_bytes.len() != 32 {
+                return Err(anyhow::anyhow!("Token encryption key {} must be 32 bytes", key_id));
+            }
+            master_keys.insert(key_id.clone(), Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&key_bytes)));
+        }
+
+        if !master_keys.contains_key(&config.active_key_id) {
+            return Err(anyhow::anyhow!("Unknown active token encryption key {}", config.active_key_id));
+        }
+
+        Ok(Self {
+            master_keys,
+            active_key_id: config.active_key_id.clone(),
+        })
+    }
+
+    pub fn new_envelope(&self) -> Result<(Envelope, DataKey)> {
+        let data_key = Aes256Gcm::generate_key(&mut OsRng);
+        let master_key = &self.master_keys[&self.active_key_id];
+
+        let envelope = Envelope {
+            key_id: self.active_key_id.clone(),
+            wrapped_data_key: seal(master_key, self.active_key_id.as_bytes(), &data_key)?,
+        };
+        Ok((envelope, DataKey { cipher: Aes256Gcm::new(&data_key) }))
+    }
+
+    pub fn open_envelope(&self, envelope: &Envelope) -> Result<DataKey> {
+        let master_key = self
+            .master_keys
+            .get(&envelope.key_id)
+            .ok_or_else(|| anyhow::anyhow!("Unknown token encryption key {}", envelope.key_id))?;
+
+        let data_key = open(master_key, envelope.key_id.as_bytes(), &envelope.wrapped_data_key)?;
+        if data_key.len() != 32 {
+            return Err(anyhow::anyhow!("Wrapped data key has an invalid length"));
+        }
+        Ok(DataKey {
+            cipher: Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&data_key)),
+        })
+    }
+}
+
+impl DataKey {
+    // `aad` binds the ciphertext to its record so it cannot be copied onto another row.
+    pub fn encrypt(&self, aad: &[u8], plaintext: &str) -> Result<String> {
+        seal(&self.cipher, aad, plaintext.as_bytes())
+    }
+
+    pub fn decrypt(&self, aad: &[u8], ciphertext: &str) -> Result<String> {
+        Ok(String::from_utf8(open(&self.cipher, aad, ciphertext)?)?)
+    }
+}
+
+// Output is base64(nonce || ciphertext).
+fn seal(cipher: &Aes256Gcm, aad: &[u8], plaintext: &[u8]) -> Result<String> {
+    let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+    let ciphertext = cipher
+        .encrypt(&nonce, Payload { msg: plaintext, aad })
+        .map_err(|_| anyhow::anyhow!("Failed to encrypt token"))?;
+
+    let mut sealed = nonce.to_vec();
+    sealed.extend_from_slice(&ciphertext);
+    Ok(STANDARD.encode(sealed))
+}
+
+fn open(cipher: &Aes256Gcm, aad: &[u8], sealed: &str) -> Result<Vec<u8>> {
+    let sealed = STANDARD.decode(sealed)?;
+    if sealed.len() < NONCE_LENGTH {
+        return Err(anyhow::anyhow!("Encrypted token is too short"));
+    }
+
+    let (nonce, ciphertext) = sealed.split_at(NONCE_LENGTH);
+    cipher
+        .decrypt(Nonce::from_slice(nonce), Payload { msg: ciphertext, aad })
+        .map_err(|_| anyhow::anyhow!("Failed to decrypt token"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(active_key_id: &str, key_ids: &[&str]) -> TokenEncryption {
+        TokenEncryption {
+            active_key_id: active_key_id.to_string(),
+            keys: key_ids
+                .iter()
+                .enumerate()
+                .map(|(i, key_id)| (key_id.to_string(), STANDARD.encode([i as u8 + 1; 32])))
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn roundtrip_through_a_reopened_envelope() {
+        let cipher = EnvelopeCipher::new(&config("k1", &["k1"])).unwrap();
+        let (envelope, data_key) = cipher.new_envelope().unwrap();
+        let ciphertext = data_key.encrypt(b"row-1", "access-token").unwrap();
+
+        assert_ne!(ciphertext, "access-token");
+        let reopened = cipher.open_envelope(&envelope).unwrap();
+        assert_eq!(reopened.decrypt(b"row-1", &ciphertext).unwrap(), "access-token");
+    }
+
+    #[test]
+    fn old_master_key_still_opens_after_rotation() {
+        let old_cipher = EnvelopeCipher::new(&config("k1", &["k1"])).unwrap();
+        let (envelope, data_key) = old_cipher.new_envelope().unwrap();
+        let ciphertext = data_key.encrypt(b"row-1", "access-token").unwrap();
+
+        let rotated_cipher = EnvelopeCipher::new(&config("k2", &["k1", "k2"])).unwrap();
+        let reopened = rotated_cipher.open_envelope(&envelope).unwrap();
+        assert_eq!(reopened.decrypt(b"row-1", &ciphertext).unwrap(), "access-token");
+    }
+
+    #[test]
+    fn wrong_aad_is_rejected() {
+        let cipher = EnvelopeCipher::new(&config("k1", &["k1"])).unwrap();
+        let (_, data_key) = cipher.new_envelope().unwrap();
+        let ciphertext = data_key.encrypt(b"row-1", "access-token").unwrap();
+
+        assert!(data_key.decrypt(b"row-2", &ciphertext).is_err());
+    }
+
+    #[test]
+    fn tampered_ciphertext_is_rejected() {
+        let cipher = EnvelopeCipher::new(&config("k1", &["k1"])).unwrap();
+        let (_, data_key) = cipher.new_envelope().unwrap();
+        let mut sealed = STANDARD.decode(data_key.encrypt(b"row-1", "access-token").unwrap()).unwrap();
+        let last = sealed.len() - 1;
+        sealed[last] ^= 0x01;
+
+        assert!(data_key.decrypt(b"row-1", &STANDARD.encode(sealed)).is_err());
+    }
+
+    #[test]
+    fn tampered_or_relabelled_envelope_is_rejected() {
+        let cipher = EnvelopeCipher::new(&config("k1", &["k1", "k2"])).unwrap();
+        let (envelope, _) = cipher.new_envelope().unwrap();
+
+        // The key id is the AAD of the wrapped data key, so it cannot be swapped.
+        let relabelled = Envelope {
+            key_id: "k2".to_string(),
+            wrapped_data_key: envelope.wrapped_data_key.clone(),
+        };
+        assert!(cipher.open_envelope(&relabelled).is_err());
+
+        let unknown = Envelope {
+            key_id: "k3".to_string(),
+            wrapped_data_key: envelope.wrapped_data_key,
+        };
+        assert!(cipher.open_envelope(&unknown).is_err());
+    }
+
+    #[test]
+    fn rejects_keys_that_are_not_32_bytes() {
+        let mut config = config("k1", &["k1"]);
+        config.keys.insert("k1".to_string(), STANDARD.encode([1u8; 16]));
+        assert!(EnvelopeCipher::new(&config).is_err());
+    }
+}
@@ -0,0 +1,110 @@
+use anyhow::Result;
+use totp_rs::{Algorithm, Secret, TOTP};
+
+const DIGITS: usize = 6;
+const STEP_SECONDS: u64 = 30;
+// Codes from the neighbouring time steps are accepted too, for phones whose clock drifts.
+const SKEW_STEPS: u64 = 1;
+
+/// A new random 160-bit secret, base32 encoded.
+pub fn generate_secret() -> String {
+    Secret::generate_secret().to_encoded().to_string()
+}
+
+/// The `otpauth://` URI authenticator apps read from a QR code.
+pub fn otpauth_uri(secret: &str, issuer: &str, account_name: &str) -> Result<String> {
+    Ok(totp(secret, Some(issuer.to_string()), account_name.to_string())?.get_url())
+}
+
+/// Returns the time step the code belongs to, or `None` when it doesn't match. Callers
+/// store the step so the same code can't be used twice.
+pub fn verify(secret: &str, code: &str, unix_time: u64) -> Result<Option<i64>> {
+    let totp = totp(secret, None, String::new())?;
+    let current_step = unix_time / STEP_SECONDS;
+
+    for step in current_step.saturating_sub(SKEW_STEPS)..=current_step + SKEW_STEPS {
+        if constant_time_eq(totp.generate(step * STEP_SECONDS).as_bytes(), code.trim().as_bytes()) {
+            return Ok(Some(step as i64));
+        }
+    }
+    Ok(None)
+}
+
+fn totp(secret: &str, issuer: Option<String>, account_name: String) -> Result<TOTP> {
+    let secret = Secret::Encoded(secret.to_string())
+        .to_bytes()
+        .map_err(|e| anyhow::anyhow!("Invalid TOTP secret: {:?}", e))?;
+    Ok(TOTP::new_unchecked(Algorithm::SHA1, DIGITS, 0, STEP_SECONDS, secret, issuer, account_name))
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // The RFC 6238 SHA-1 seed "12345678901234567890", base32 encoded.
+    const RFC_SECRET: &str = "GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ";
+
+    // RFC 6238 appendix B, SHA-1, truncated to our 6 digits.
+    const RFC_VECTORS: &[(u64, &str)] = &[
+        (59, "287082"),
+        (1111111109, "081804"),
+        (1111111111, "050471"),
+        (1234567890, "005924"),
+        (2000000000, "279037"),
+        (20000000000, "353130"),
+    ];
+
+    #[test]
+    fn matches_rfc_6238_test_vectors() {
+        for &(unix_time, code) in RFC_VECTORS {
+            let step = (unix_time / STEP_SECONDS) as i64;
+            assert_eq!(verify(RFC_SECRET, code, unix_time).unwrap(), Some(step), "time {}", unix_time);
+        }
+    }
+
+    #[test]
+    fn accepts_one_step_of_clock_skew() {
+        // "287082" belongs to step 1 (t = 30..59).
+        assert_eq!(verify(RFC_SECRET, "287082", 59 - STEP_SECONDS).unwrap(), Some(1));
+        assert_eq!(verify(RFC_SECRET, "287082", 59 + STEP_SECONDS).unwrap(), Some(1));
+        assert_eq!(verify(RFC_SECRET, "287082", 59 + 2 * STEP_SECONDS).unwrap(), None);
+    }
+
+    #[test]
+    fn rejects_wrong_codes() {
+        assert_eq!(verify(RFC_SECRET, "287083", 59).unwrap(), None);
+        assert_eq!(verify(RFC_SECRET, "28708", 59).unwrap(), None);
+        assert_eq!(verify(RFC_SECRET, "", 59).unwrap(), None);
+    }
+
+    #[test]
+    fn ignores_surrounding_whitespace() {
+        assert_eq!(verify(RFC_SECRET, " 287082\n", 59).unwrap(), Some(1));
+    }
+
+    #[test]
+    fn rejects_invalid_secret() {
+        assert!(verify("not base32!", "287082", 59).is_err());
+    }
+
+    #[test]
+    fn otpauth_uri_carries_secret_and_issuer() {
+        let uri = otpauth_uri(RFC_SECRET, "SmartPersona", "alice").unwrap();
+
+        assert!(uri.starts_with("otpauth://totp/SmartPersona:alice?"), "{}", uri);
+        assert!(uri.contains(&format!("secret={}", RFC_SECRET)), "{}", uri);
+        assert!(uri.contains("issuer=SmartPersona"), "{}", uri);
+    }
+
+    #[test]
+    fn generated_secret_is_usable() {
+        let secret = generate_secret();
+        let code = totp(&secret, None, String::new()).unwrap().generate(1_700_000_000);
+
+        assert_eq!(verify(&secret, &code, 1_700_000_000).unwrap(), Some(1_700_000_000 / STEP_SECONDS as i64));
+    }
+}
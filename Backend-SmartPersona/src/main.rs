@@ -1,6 +1,6 @@
 use std::sync::Arc;
 
-use rust_api::{config::{self, config_loader}, infrastructure::{axum_http::http_serve::start, postgres::postgres_connection}};
+use rust_api::{config::config_loader, infrastructure::{axum_http::http_serve::start, postgres::postgres_connection}};
 use tracing::info;
 
 #[tokio::main]
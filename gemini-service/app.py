@@ -5,7 +5,7 @@ import google.generativeai as genai
 from flask import Flask, request, jsonify
 from dotenv import load_dotenv
 from pydantic import BaseModel
-from typing import List
+from typing import List, Optional
 
 load_dotenv()
 app = Flask(__name__)
@@ -14,6 +14,8 @@ app = Flask(__name__)
 class AIAnalysisRequest(BaseModel):
     user_id: str
     posts: List[str]
+    # prompt ที่ backend render จาก prompt_templates แล้ว ถ้าไม่ส่งมาจะใช้ prompt เริ่มต้นด้านล่าง
+    prompt: Optional[str] = None
 
 class AIAnalysisResponse(BaseModel):
     personality_tags: List[str]
@@ -33,7 +35,7 @@ def analyze_personality_endpoint():
 
     try:
         all_posts = "\n".join(request_data.posts)
-        prompt = f"""
+        prompt = request_data.prompt or f"""
 You are an AI personality analyzer.
 
 Analyze the following social media posts and describe the user's personality in 3–5 short keywords